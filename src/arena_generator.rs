@@ -0,0 +1,614 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+
+use crate::arena_validation::{validate_arena, ArenaValidationError};
+use crate::components::{
+    arena_properties_to_ron, ArenaCircle, ArenaFloor, ArenaKingHill, ArenaNames, ArenaProperties,
+    ArenaRaceCheckpoint, ArenaRectangle, ObstacleType, PlayerSpawnPoint, WeaponBoxSpawner,
+};
+use crate::resources::GameModes;
+
+//Layouts failing validation are re-rolled from the next seed
+const GENERATOR_MAX_ATTEMPTS: u64 = 50;
+const OBSTACLE_PLACEMENT_TRIES: u32 = 300;
+
+//Portion of the floor covered by walls at a density of 1.0
+const MAX_WALL_COVERAGE: f32 = 0.3;
+
+const EDGE_MARGIN: f32 = 20.0;
+const OBSTACLE_GAP: f32 = 30.0; //room for a vehicle to drive between walls
+const RESERVED_CLEARANCE: f32 = 20.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ArenaSymmetry {
+    Mirror,     //mirrored left-right and top-bottom
+    Rotational, //4-fold on square arenas, 2-fold otherwise
+}
+
+#[derive(Clone, Debug)]
+pub struct ArenaGeneratorSettings {
+    pub seed: u64,
+    pub width: f32,
+    pub height: f32,
+    pub obstacle_density: f32, //0.0 to 1.0
+    pub symmetry: ArenaSymmetry,
+    pub game_mode: GameModes,
+}
+
+impl Default for ArenaGeneratorSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            width: 400.0,
+            height: 400.0,
+            obstacle_density: 0.5,
+            symmetry: ArenaSymmetry::Rotational,
+            game_mode: GameModes::DeathmatchKills,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum GeneratedObstacle {
+    Circle {
+        x: f32,
+        y: f32,
+        radius: f32,
+    },
+    Rectangle {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+impl GeneratedObstacle {
+    fn bounding_radius(&self) -> f32 {
+        match *self {
+            GeneratedObstacle::Circle { radius, .. } => radius,
+            GeneratedObstacle::Rectangle { width, height, .. } => (width / 2.0).hypot(height / 2.0),
+        }
+    }
+
+    fn center(&self) -> (f32, f32) {
+        match *self {
+            GeneratedObstacle::Circle { x, y, .. } => (x, y),
+            GeneratedObstacle::Rectangle { x, y, .. } => (x, y),
+        }
+    }
+}
+
+//Command line generator, ex: cargo run -- generate-arena --seed 7 --mode KingOfTheHill --out hill.ron
+pub fn generate_arena_command(args: &[String]) -> bool {
+    let mut settings = ArenaGeneratorSettings::default();
+    let mut out_path: Option<String> = None;
+
+    for arg_pair in args.chunks(2) {
+        if arg_pair.len() < 2 {
+            println!("Missing value for {}", arg_pair[0]);
+            return false;
+        }
+
+        let value = arg_pair[1].as_str();
+
+        let parsed = match arg_pair[0].as_str() {
+            "--seed" => value.parse().map(|seed| settings.seed = seed).is_ok(),
+            "--width" => value.parse().map(|width| settings.width = width).is_ok(),
+            "--height" => value.parse().map(|height| settings.height = height).is_ok(),
+            "--density" => value
+                .parse()
+                .map(|density| settings.obstacle_density = density)
+                .is_ok(),
+            "--symmetry" => match value {
+                "mirror" => {
+                    settings.symmetry = ArenaSymmetry::Mirror;
+                    true
+                }
+                "rotational" => {
+                    settings.symmetry = ArenaSymmetry::Rotational;
+                    true
+                }
+                _ => false,
+            },
+            "--mode" => ron::de::from_str(value)
+                .map(|game_mode| settings.game_mode = game_mode)
+                .is_ok(),
+            "--out" => {
+                out_path = Some(value.to_string());
+                true
+            }
+            _ => false,
+        };
+
+        if !parsed {
+            println!("Invalid argument: {} {}", arg_pair[0], value);
+            return false;
+        }
+    }
+
+    match generate_arena(&settings) {
+        Ok(arena_properties) => {
+            let arena_ron = arena_properties_to_ron(&arena_properties);
+
+            if let Some(out_path) = out_path {
+                if let Err(error) = std::fs::write(&out_path, arena_ron) {
+                    println!("Failed to write {}: {}", out_path, error);
+                    return false;
+                }
+                println!("Arena saved to {}", out_path);
+            } else {
+                println!("{}", arena_ron);
+            }
+            true
+        }
+        Err(errors) => {
+            println!(
+                "Failed to generate a valid arena after {} attempts:",
+                GENERATOR_MAX_ATTEMPTS
+            );
+            for error in errors.iter() {
+                println!("{}", error);
+            }
+            false
+        }
+    }
+}
+
+//Generates arenas until one passes the same checks as the arena validation tool
+pub fn generate_arena(
+    settings: &ArenaGeneratorSettings,
+) -> Result<ArenaProperties, Vec<ArenaValidationError>> {
+    let mut last_errors = Vec::new();
+
+    for attempt in 0..GENERATOR_MAX_ATTEMPTS {
+        let mut rng = StdRng::seed_from_u64(settings.seed.wrapping_add(attempt));
+
+        let arena_properties = generate_arena_layout(settings, &mut rng);
+
+        let checkpoint_count = if settings.game_mode == GameModes::Race {
+            Some(arena_properties.race_checkpoints.len() as i32)
+        } else {
+            None
+        };
+
+        let errors = validate_arena(ArenaNames::Generated, &arena_properties, checkpoint_count);
+
+        if errors.is_empty() {
+            return Ok(arena_properties);
+        }
+
+        last_errors = errors;
+    }
+
+    Err(last_errors)
+}
+
+fn generate_arena_layout(settings: &ArenaGeneratorSettings, rng: &mut StdRng) -> ArenaProperties {
+    let width = settings.width;
+    let height = settings.height;
+
+    let mut arena_properties = ArenaProperties {
+        width,
+        height,
+        floor: vec![ArenaFloor {
+            x: width / 2.0,
+            y: height / 2.0,
+            width,
+            height,
+        }],
+        ..ArenaProperties::default()
+    };
+
+    //Areas that must be kept free of walls, as (x, y, radius)
+    let mut reserved: Vec<(f32, f32, f32)> = Vec::new();
+
+    //Track island for racing, so that the checkpoints form a closed loop around it
+    let mut obstacles: Vec<GeneratedObstacle> = Vec::new();
+
+    if settings.game_mode == GameModes::Race {
+        let island = GeneratedObstacle::Rectangle {
+            x: width / 2.0,
+            y: height / 2.0,
+            width: width * 0.4,
+            height: height * 0.4,
+        };
+        obstacles.push(island);
+
+        let use_corner_checkpoints = rng.gen::<bool>();
+
+        //counter-clockwise from the finish line on the right side
+        let track_width = width * 0.3;
+        let track_height = height * 0.3;
+        let corner_length = track_width.hypot(track_height);
+        let corner_rotation = track_height.atan2(track_width) / PI * 180.0;
+
+        let mut checkpoints = vec![
+            (width - track_width / 2.0, height / 2.0, track_width, 0.0),
+            (width / 2.0, height - track_height / 2.0, track_height, 90.0),
+            (track_width / 2.0, height / 2.0, track_width, 0.0),
+            (width / 2.0, track_height / 2.0, track_height, 90.0),
+        ];
+
+        if use_corner_checkpoints {
+            checkpoints.insert(
+                1,
+                (
+                    width - track_width / 2.0,
+                    height - track_height / 2.0,
+                    corner_length,
+                    corner_rotation,
+                ),
+            );
+            checkpoints.insert(
+                3,
+                (
+                    track_width / 2.0,
+                    height - track_height / 2.0,
+                    corner_length,
+                    -corner_rotation,
+                ),
+            );
+            checkpoints.insert(
+                5,
+                (
+                    track_width / 2.0,
+                    track_height / 2.0,
+                    corner_length,
+                    corner_rotation,
+                ),
+            );
+            checkpoints.push((
+                width - track_width / 2.0,
+                track_height / 2.0,
+                corner_length,
+                -corner_rotation,
+            ));
+        }
+
+        for (x, y, length, rotation) in checkpoints.iter() {
+            arena_properties.race_checkpoints.push(ArenaRaceCheckpoint {
+                x: *x,
+                y: *y,
+                length: *length,
+                rotation: *rotation,
+            });
+            reserved.push((*x, *y, length / 2.0));
+        }
+
+        //starting grid just behind the finish line, facing up the track
+        for idx in 0..4 {
+            let x = width - track_width + (idx as f32 + 0.5) * track_width / 4.0;
+            let y = height / 2.0 - 14.0;
+
+            arena_properties.player_spawn_points.push(PlayerSpawnPoint {
+                x,
+                y,
+                rotation: 0.0,
+            });
+            reserved.push((x, y, RESERVED_CLEARANCE));
+        }
+
+        for (x, y) in [
+            (track_width / 2.0, track_height / 2.0 + height * 0.1),
+            (
+                width - track_width / 2.0,
+                height - track_height / 2.0 - height * 0.1,
+            ),
+        ]
+        .iter()
+        {
+            arena_properties
+                .weapon_spawners
+                .push(weapon_spawner(*x, *y));
+            reserved.push((*x, *y, RESERVED_CLEARANCE));
+        }
+    } else {
+        //spawn in the corners facing the center, opposite corners first for 2 player matches
+        let spawn_margin_x = width * 0.2;
+        let spawn_margin_y = height * 0.2;
+
+        for (x, y) in [
+            (spawn_margin_x, spawn_margin_y),
+            (width - spawn_margin_x, height - spawn_margin_y),
+            (spawn_margin_x, height - spawn_margin_y),
+            (width - spawn_margin_x, spawn_margin_y),
+        ]
+        .iter()
+        {
+            let dx = width / 2.0 - x;
+            let dy = height / 2.0 - y;
+
+            arena_properties.player_spawn_points.push(PlayerSpawnPoint {
+                x: *x,
+                y: *y,
+                rotation: (-dx).atan2(dy) / PI * 180.0,
+            });
+            reserved.push((*x, *y, RESERVED_CLEARANCE * 2.0));
+        }
+
+        for (x, y) in [
+            (width / 3.0, height / 3.0),
+            (width / 3.0, height * 2.0 / 3.0),
+            (width * 2.0 / 3.0, height / 3.0),
+            (width * 2.0 / 3.0, height * 2.0 / 3.0),
+        ]
+        .iter()
+        {
+            arena_properties
+                .weapon_spawners
+                .push(weapon_spawner(*x, *y));
+            reserved.push((*x, *y, RESERVED_CLEARANCE));
+        }
+
+        if settings.game_mode == GameModes::KingOfTheHill {
+            let radius = width.min(height) * 0.1;
+
+            arena_properties.king_hills.push(ArenaKingHill {
+                x: width / 2.0,
+                y: height / 2.0,
+                radius,
+            });
+            reserved.push((width / 2.0, height / 2.0, radius + RESERVED_CLEARANCE));
+        }
+    }
+
+    //Random walls, placed as symmetric sets
+    let target_wall_area =
+        settings.obstacle_density.max(0.0).min(1.0) * MAX_WALL_COVERAGE * width * height;
+    let mut wall_area = 0.0;
+
+    let max_size = width.min(height) * 0.15;
+
+    for _try in 0..OBSTACLE_PLACEMENT_TRIES {
+        if wall_area >= target_wall_area {
+            break;
+        }
+
+        let x = rng.gen_range(EDGE_MARGIN, width - EDGE_MARGIN);
+        let y = rng.gen_range(EDGE_MARGIN, height - EDGE_MARGIN);
+
+        let obstacle = if rng.gen::<bool>() {
+            GeneratedObstacle::Circle {
+                x,
+                y,
+                radius: rng.gen_range(8.0, max_size / 2.0).round(),
+            }
+        } else {
+            GeneratedObstacle::Rectangle {
+                x,
+                y,
+                width: (rng.gen_range(10.0, max_size) / 5.0).round() * 5.0,
+                height: (rng.gen_range(10.0, max_size) / 5.0).round() * 5.0,
+            }
+        };
+
+        let copies = symmetric_copies(settings, obstacle);
+
+        let fits = copies.iter().enumerate().all(|(idx, copy)| {
+            obstacle_fits(copy, width, height, &obstacles, &reserved)
+                && copies[..idx]
+                    .iter()
+                    .all(|other| !obstacles_too_close(copy, other))
+        });
+
+        if fits {
+            for copy in copies.iter() {
+                wall_area += match *copy {
+                    GeneratedObstacle::Circle { radius, .. } => PI * radius.powi(2),
+                    GeneratedObstacle::Rectangle { width, height, .. } => width * height,
+                };
+                obstacles.push(*copy);
+            }
+        }
+    }
+
+    for obstacle in obstacles.iter() {
+        match *obstacle {
+            GeneratedObstacle::Circle { x, y, radius } => {
+                arena_properties.arena_circles.push(ArenaCircle {
+                    obstacle_type: ObstacleType::Wall,
+                    x,
+                    y,
+                    radius,
+                    effect: None,
+                    health: None,
+                });
+            }
+            GeneratedObstacle::Rectangle {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                arena_properties.arena_rectangles.push(ArenaRectangle {
+                    obstacle_type: ObstacleType::Wall,
+                    x,
+                    y,
+                    width,
+                    height,
+                    rotation: 0.0,
+                    effects: None,
+                    health: None,
+                });
+            }
+        }
+    }
+
+    arena_properties
+}
+
+fn weapon_spawner(x: f32, y: f32) -> WeaponBoxSpawner {
+    WeaponBoxSpawner {
+        x,
+        y,
+        weapon_names: None,
+        first_spawn_time: None,
+        spawn_time: None,
+        ammo: None,
+    }
+}
+
+fn symmetric_copies(
+    settings: &ArenaGeneratorSettings,
+    obstacle: GeneratedObstacle,
+) -> Vec<GeneratedObstacle> {
+    let width = settings.width;
+    let height = settings.height;
+    let center_x = width / 2.0;
+    let center_y = height / 2.0;
+
+    //(x, y, rotated by 90 degrees)
+    let (x, y) = obstacle.center();
+    let positions: Vec<(f32, f32, bool)> = match settings.symmetry {
+        ArenaSymmetry::Mirror => vec![
+            (x, y, false),
+            (width - x, y, false),
+            (x, height - y, false),
+            (width - x, height - y, false),
+        ],
+        ArenaSymmetry::Rotational => {
+            let px = x - center_x;
+            let py = y - center_y;
+
+            if (width - height).abs() < 0.001 {
+                vec![
+                    (x, y, false),
+                    (center_x - py, center_y + px, true),
+                    (center_x - px, center_y - py, false),
+                    (center_x + py, center_y - px, true),
+                ]
+            } else {
+                vec![(x, y, false), (center_x - px, center_y - py, false)]
+            }
+        }
+    };
+
+    let mut copies: Vec<GeneratedObstacle> = Vec::new();
+
+    for (copy_x, copy_y, rotated) in positions.iter() {
+        //obstacles on a line of symmetry are their own copy
+        let duplicate = copies.iter().any(|copy| {
+            let (other_x, other_y) = copy.center();
+            (other_x - copy_x).abs() < 0.5 && (other_y - copy_y).abs() < 0.5
+        });
+
+        if !duplicate {
+            copies.push(match obstacle {
+                GeneratedObstacle::Circle { radius, .. } => GeneratedObstacle::Circle {
+                    x: *copy_x,
+                    y: *copy_y,
+                    radius,
+                },
+                GeneratedObstacle::Rectangle { width, height, .. } => {
+                    if *rotated {
+                        GeneratedObstacle::Rectangle {
+                            x: *copy_x,
+                            y: *copy_y,
+                            width: height,
+                            height: width,
+                        }
+                    } else {
+                        GeneratedObstacle::Rectangle {
+                            x: *copy_x,
+                            y: *copy_y,
+                            width,
+                            height,
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    copies
+}
+
+fn obstacle_fits(
+    obstacle: &GeneratedObstacle,
+    width: f32,
+    height: f32,
+    obstacles: &Vec<GeneratedObstacle>,
+    reserved: &Vec<(f32, f32, f32)>,
+) -> bool {
+    let (x, y) = obstacle.center();
+    let radius = obstacle.bounding_radius();
+
+    if x - radius < EDGE_MARGIN
+        || x + radius > width - EDGE_MARGIN
+        || y - radius < EDGE_MARGIN
+        || y + radius > height - EDGE_MARGIN
+    {
+        return false;
+    }
+
+    for (reserved_x, reserved_y, reserved_radius) in reserved.iter() {
+        if (x - reserved_x).hypot(y - reserved_y) < radius + reserved_radius {
+            return false;
+        }
+    }
+
+    obstacles
+        .iter()
+        .all(|other| !obstacles_too_close(obstacle, other))
+}
+
+fn obstacles_too_close(obstacle: &GeneratedObstacle, other: &GeneratedObstacle) -> bool {
+    let (x, y) = obstacle.center();
+    let (other_x, other_y) = other.center();
+
+    let dist = (x - other_x).hypot(y - other_y);
+
+    dist < obstacle.bounding_radius() + other.bounding_radius() + OBSTACLE_GAP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_arenas_pass_validation() {
+        for (seed, symmetry, game_mode) in [
+            (1, ArenaSymmetry::Rotational, GameModes::DeathmatchKills),
+            (2, ArenaSymmetry::Mirror, GameModes::KingOfTheHill),
+            (3, ArenaSymmetry::Mirror, GameModes::Race),
+            (4, ArenaSymmetry::Rotational, GameModes::DeathmatchStock),
+        ]
+        .iter()
+        {
+            let settings = ArenaGeneratorSettings {
+                seed: *seed,
+                symmetry: *symmetry,
+                game_mode: *game_mode,
+                ..ArenaGeneratorSettings::default()
+            };
+
+            let arena_properties = generate_arena(&settings).unwrap();
+
+            if *game_mode == GameModes::KingOfTheHill {
+                assert_eq!(arena_properties.king_hills.len(), 1);
+            } else if *game_mode == GameModes::Race {
+                assert!(arena_properties.race_checkpoints.len() >= 4);
+            }
+
+            //same seed, same arena
+            assert_eq!(generate_arena(&settings).unwrap(), arena_properties);
+        }
+    }
+
+    #[test]
+    fn generated_arena_round_trips_through_ron() {
+        let arena_properties = generate_arena(&ArenaGeneratorSettings {
+            width: 600.0,
+            height: 400.0,
+            ..ArenaGeneratorSettings::default()
+        })
+        .unwrap();
+
+        let arena_ron = arena_properties_to_ron(&arena_properties);
+        let loaded: ArenaProperties = ron::de::from_str(&arena_ron).unwrap();
+
+        assert_eq!(loaded, arena_properties);
+    }
+}
@@ -0,0 +1,547 @@
+use navmesh::{NavMesh, NavPathMode, NavQuery, NavVec3};
+use std::f32::consts::PI;
+use std::fmt;
+
+use crate::components::{
+    ArenaCircle, ArenaNames, ArenaProperties, ArenaRectangle, ArenaStoreResource, ObstacleType,
+};
+use crate::load_ron_asset;
+use crate::resources::{
+    build_arena_nav_mesh, build_nav_mesh_final, get_arena_nav_mesh_obstacles, point_on_nav_mesh,
+    ArenaNavMesh, GameModes,
+};
+
+//Rough half-size of a vehicle, spawn points closer than this to a wall will start stuck in it
+const SPAWN_CLEARANCE: f32 = 7.0;
+//Weapon box hitbox half-size, matches reform_weapon_spawner
+const WEAPON_BOX_HALF_SIZE: f32 = 5.5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArenaValidationError {
+    pub arena_name: ArenaNames,
+    pub element: &'static str,
+    pub index: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ArenaValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(index) = self.index {
+            write!(
+                f,
+                "{:?} {}[{}]: {}",
+                self.arena_name, self.element, index, self.message
+            )
+        } else {
+            write!(
+                f,
+                "{:?} {}: {}",
+                self.arena_name, self.element, self.message
+            )
+        }
+    }
+}
+
+//Command line report, run with `cargo run -- validate-arenas`
+pub fn validate_arenas_command() -> bool {
+    let arena_store = ArenaStoreResource {
+        properties: load_ron_asset(&["game", "arena_properties.ron"]),
+        game_modes: load_ron_asset(&["game", "arena_game_modes.ron"]),
+    };
+
+    let errors = validate_arena_store(&arena_store);
+
+    for error in errors.iter() {
+        println!("{}", error);
+    }
+
+    if errors.is_empty() {
+        println!(
+            "{} arenas validated, no problems found",
+            arena_store.properties.len()
+        );
+        true
+    } else {
+        println!("{} arena problems found", errors.len());
+        false
+    }
+}
+
+//Validates every arena in the store, plus the game mode to arena assignments
+pub fn validate_arena_store(arena_store: &ArenaStoreResource) -> Vec<ArenaValidationError> {
+    let mut errors: Vec<ArenaValidationError> = Vec::new();
+
+    let mut arena_names: Vec<&ArenaNames> = arena_store.properties.keys().collect();
+    arena_names.sort_by_key(|arena_name| format!("{:?}", arena_name));
+
+    for arena_name in arena_names {
+        errors.append(&mut validate_arena(
+            *arena_name,
+            &arena_store.properties[arena_name],
+            None,
+        ));
+    }
+
+    let mut game_modes: Vec<&GameModes> = arena_store.game_modes.keys().collect();
+    game_modes.sort_by_key(|game_mode| format!("{:?}", game_mode));
+
+    for game_mode in game_modes {
+        for arena_name in arena_store.game_modes[game_mode].iter() {
+            if let Some(arena_properties) = arena_store.properties.get(arena_name) {
+                if *game_mode == GameModes::Race && arena_properties.race_checkpoints.len() < 2 {
+                    errors.push(ArenaValidationError {
+                        arena_name: *arena_name,
+                        element: "race_checkpoints",
+                        index: None,
+                        message: format!(
+                            "used for {:?} but has {} checkpoints, needs a finish line and at least one checkpoint",
+                            game_mode,
+                            arena_properties.race_checkpoints.len()
+                        ),
+                    });
+                } else if *game_mode == GameModes::KingOfTheHill
+                    && arena_properties.king_hills.is_empty()
+                {
+                    errors.push(ArenaValidationError {
+                        arena_name: *arena_name,
+                        element: "king_hills",
+                        index: None,
+                        message: format!("used for {:?} but has no hills", game_mode),
+                    });
+                }
+            } else {
+                errors.push(ArenaValidationError {
+                    arena_name: *arena_name,
+                    element: "arena_properties",
+                    index: None,
+                    message: format!("used for {:?} but not defined", game_mode),
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+//checkpoint_count should be the GameModeSetup value when validating the arena about to be played
+pub fn validate_arena(
+    arena_name: ArenaNames,
+    arena_properties: &ArenaProperties,
+    checkpoint_count: Option<i32>,
+) -> Vec<ArenaValidationError> {
+    let mut errors: Vec<ArenaValidationError> = Vec::new();
+
+    let width = arena_properties.width;
+    let height = arena_properties.height;
+
+    let mut push_error = |element: &'static str, index: Option<usize>, message: String| {
+        errors.push(ArenaValidationError {
+            arena_name,
+            element,
+            index,
+            message,
+        });
+    };
+
+    let in_bounds = |x: f32, y: f32| x >= 0.0 && x <= width && y >= 0.0 && y <= height;
+
+    //Spawn points
+    for (idx, spawn_point) in arena_properties.player_spawn_points.iter().enumerate() {
+        if !in_bounds(spawn_point.x, spawn_point.y) {
+            push_error(
+                "player_spawn_points",
+                Some(idx),
+                format!(
+                    "({}, {}) is outside the arena",
+                    spawn_point.x, spawn_point.y
+                ),
+            );
+        }
+
+        if let Some((wall_element, wall_idx)) = find_overlapping_wall(
+            arena_properties,
+            spawn_point.x,
+            spawn_point.y,
+            SPAWN_CLEARANCE,
+        ) {
+            push_error(
+                "player_spawn_points",
+                Some(idx),
+                format!(
+                    "({}, {}) is inside {}[{}]",
+                    spawn_point.x, spawn_point.y, wall_element, wall_idx
+                ),
+            );
+        }
+    }
+
+    for (idx, spawn_point) in arena_properties.enemy_spawn_points.iter().enumerate() {
+        if !in_bounds(spawn_point.x, spawn_point.y) {
+            push_error(
+                "enemy_spawn_points",
+                Some(idx),
+                format!(
+                    "({}, {}) is outside the arena",
+                    spawn_point.x, spawn_point.y
+                ),
+            );
+        }
+
+        if let Some((wall_element, wall_idx)) = find_overlapping_wall(
+            arena_properties,
+            spawn_point.x,
+            spawn_point.y,
+            SPAWN_CLEARANCE,
+        ) {
+            push_error(
+                "enemy_spawn_points",
+                Some(idx),
+                format!(
+                    "({}, {}) is inside {}[{}]",
+                    spawn_point.x, spawn_point.y, wall_element, wall_idx
+                ),
+            );
+        }
+    }
+
+    //Weapon spawners
+    for (idx, weapon_spawner) in arena_properties.weapon_spawners.iter().enumerate() {
+        if !in_bounds(weapon_spawner.x, weapon_spawner.y) {
+            push_error(
+                "weapon_spawners",
+                Some(idx),
+                format!(
+                    "({}, {}) is outside the arena",
+                    weapon_spawner.x, weapon_spawner.y
+                ),
+            );
+        }
+
+        if let Some((wall_element, wall_idx)) = find_overlapping_wall(
+            arena_properties,
+            weapon_spawner.x,
+            weapon_spawner.y,
+            WEAPON_BOX_HALF_SIZE,
+        ) {
+            push_error(
+                "weapon_spawners",
+                Some(idx),
+                format!(
+                    "({}, {}) overlaps {}[{}]",
+                    weapon_spawner.x, weapon_spawner.y, wall_element, wall_idx
+                ),
+            );
+        }
+    }
+
+    //Race checkpoints
+    if let Some(checkpoint_count) = checkpoint_count {
+        if checkpoint_count as usize != arena_properties.race_checkpoints.len() {
+            push_error(
+                "race_checkpoints",
+                None,
+                format!(
+                    "checkpoint_count is {} but arena has {} race checkpoints",
+                    checkpoint_count,
+                    arena_properties.race_checkpoints.len()
+                ),
+            );
+        }
+    }
+
+    for (idx, race_checkpoint) in arena_properties.race_checkpoints.iter().enumerate() {
+        if !in_bounds(race_checkpoint.x, race_checkpoint.y) {
+            push_error(
+                "race_checkpoints",
+                Some(idx),
+                format!(
+                    "({}, {}) is outside the arena",
+                    race_checkpoint.x, race_checkpoint.y
+                ),
+            );
+        }
+    }
+
+    //Reachability
+    let arena_nav_mesh = build_arena_nav_mesh(
+        width,
+        height,
+        &arena_properties.floor,
+        &get_arena_nav_mesh_obstacles(arena_properties),
+    );
+    let nav_mesh = build_nav_mesh_final(&arena_nav_mesh);
+
+    if let Ok(nav_mesh) = &nav_mesh {
+        //paths are only searched from spawn points the vehicles can drive away from,
+        //spawns already reported outside the arena or inside a wall aren't reported again
+        for (idx, spawn_point) in arena_properties.player_spawn_points.iter().enumerate() {
+            if in_bounds(spawn_point.x, spawn_point.y)
+                && find_overlapping_wall(
+                    arena_properties,
+                    spawn_point.x,
+                    spawn_point.y,
+                    SPAWN_CLEARANCE,
+                )
+                .is_none()
+                && !point_on_nav_mesh(&arena_nav_mesh, spawn_point.x, spawn_point.y)
+            {
+                push_error(
+                    "player_spawn_points",
+                    Some(idx),
+                    format!(
+                        "({}, {}) is not on the nav mesh",
+                        spawn_point.x, spawn_point.y
+                    ),
+                );
+            }
+        }
+
+        for (idx, king_hill) in arena_properties.king_hills.iter().enumerate() {
+            if !is_reachable(
+                nav_mesh,
+                &arena_nav_mesh,
+                arena_properties,
+                king_hill.x,
+                king_hill.y,
+            ) {
+                push_error(
+                    "king_hills",
+                    Some(idx),
+                    format!(
+                        "({}, {}) cannot be reached from any player spawn point",
+                        king_hill.x, king_hill.y
+                    ),
+                );
+            }
+        }
+
+        for (idx, race_checkpoint) in arena_properties.race_checkpoints.iter().enumerate() {
+            if !is_reachable(
+                nav_mesh,
+                &arena_nav_mesh,
+                arena_properties,
+                race_checkpoint.x,
+                race_checkpoint.y,
+            ) {
+                push_error(
+                    "race_checkpoints",
+                    Some(idx),
+                    format!(
+                        "({}, {}) cannot be reached from any player spawn point",
+                        race_checkpoint.x, race_checkpoint.y
+                    ),
+                );
+            }
+        }
+    } else if let Err(nav_mesh_error) = &nav_mesh {
+        if !arena_properties.king_hills.is_empty() || !arena_properties.race_checkpoints.is_empty()
+        {
+            push_error(
+                "nav_mesh",
+                None,
+                format!("nav mesh could not be built: {}", nav_mesh_error),
+            );
+        }
+    }
+
+    errors
+}
+
+fn find_overlapping_wall(
+    arena_properties: &ArenaProperties,
+    x: f32,
+    y: f32,
+    clearance: f32,
+) -> Option<(&'static str, usize)> {
+    for (idx, arena_rect) in arena_properties.arena_rectangles.iter().enumerate() {
+        if arena_rect.obstacle_type == ObstacleType::Wall
+            && rectangle_overlaps(arena_rect, x, y, clearance)
+        {
+            return Some(("arena_rectangles", idx));
+        }
+    }
+
+    for (idx, arena_circle) in arena_properties.arena_circles.iter().enumerate() {
+        if arena_circle.obstacle_type == ObstacleType::Wall
+            && circle_overlaps(arena_circle, x, y, clearance)
+        {
+            return Some(("arena_circles", idx));
+        }
+    }
+
+    None
+}
+
+//Arena rectangle hitboxes are rotated in game, so check in the rectangle's own frame
+fn rectangle_overlaps(arena_rect: &ArenaRectangle, x: f32, y: f32, clearance: f32) -> bool {
+    let angle = arena_rect.rotation / 180.0 * PI;
+    let x_diff = x - arena_rect.x;
+    let y_diff = y - arena_rect.y;
+
+    let local_x = x_diff * angle.cos() + y_diff * angle.sin();
+    let local_y = -x_diff * angle.sin() + y_diff * angle.cos();
+
+    local_x.abs() < arena_rect.width / 2.0 + clearance
+        && local_y.abs() < arena_rect.height / 2.0 + clearance
+}
+
+fn circle_overlaps(arena_circle: &ArenaCircle, x: f32, y: f32, clearance: f32) -> bool {
+    let dist = ((x - arena_circle.x).powi(2) + (y - arena_circle.y).powi(2)).sqrt();
+
+    dist < arena_circle.radius + clearance
+}
+
+//The path query snaps both ends onto the mesh, so a point or spawn inside a wall would
+//otherwise count as reachable through the nearest open triangle
+fn is_reachable(
+    nav_mesh: &NavMesh,
+    arena_nav_mesh: &ArenaNavMesh,
+    arena_properties: &ArenaProperties,
+    x: f32,
+    y: f32,
+) -> bool {
+    if !point_on_nav_mesh(arena_nav_mesh, x, y) {
+        return false;
+    }
+
+    for spawn_point in arena_properties.player_spawn_points.iter() {
+        if !point_on_nav_mesh(arena_nav_mesh, spawn_point.x, spawn_point.y) {
+            continue;
+        }
+
+        let path = nav_mesh.find_path(
+            NavVec3::new(spawn_point.x, spawn_point.y, 0.0),
+            NavVec3::new(x, y, 0.0),
+            NavQuery::Accuracy,
+            NavPathMode::Accuracy,
+        );
+
+        if path.is_some() {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{ArenaFloor, ArenaKingHill, PlayerSpawnPoint, WeaponBoxSpawner};
+
+    fn walled_arena() -> ArenaProperties {
+        ArenaProperties {
+            width: 400.0,
+            height: 400.0,
+            arena_rectangles: vec![ArenaRectangle {
+                obstacle_type: ObstacleType::Wall,
+                x: 200.0,
+                y: 200.0,
+                width: 40.0,
+                height: 400.0,
+                rotation: 0.0,
+                effects: None,
+                health: None,
+            }],
+            player_spawn_points: vec![
+                PlayerSpawnPoint {
+                    x: 80.0,
+                    y: 80.0,
+                    rotation: 0.0,
+                },
+                PlayerSpawnPoint {
+                    x: 200.0,
+                    y: 100.0,
+                    rotation: 0.0,
+                },
+            ],
+            weapon_spawners: vec![WeaponBoxSpawner {
+                x: 215.0,
+                y: 300.0,
+                weapon_names: None,
+                first_spawn_time: None,
+                spawn_time: None,
+                ammo: None,
+            }],
+            king_hills: vec![ArenaKingHill {
+                x: 320.0,
+                y: 200.0,
+                radius: 40.0,
+            }],
+            ..ArenaProperties::default()
+        }
+    }
+
+    #[test]
+    fn shipped_arenas_are_valid() {
+        let arena_store = ArenaStoreResource {
+            properties: load_ron_asset(&["game", "arena_properties.ron"]),
+            game_modes: load_ron_asset(&["game", "arena_game_modes.ron"]),
+        };
+
+        let errors = validate_arena_store(&arena_store);
+
+        let report: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert!(errors.is_empty(), "{}", report.join("\n"));
+    }
+
+    #[test]
+    fn reports_geometry_and_reachability_problems() {
+        let errors = validate_arena(ArenaNames::OpenEmptyMap, &walled_arena(), Some(2));
+
+        let found: Vec<(&str, Option<usize>)> = errors
+            .iter()
+            .map(|error| (error.element, error.index))
+            .collect();
+
+        assert!(found.contains(&("player_spawn_points", Some(1))));
+        assert!(found.contains(&("weapon_spawners", Some(0))));
+        assert!(found.contains(&("race_checkpoints", None)));
+        assert!(found.contains(&("king_hills", Some(0))));
+        assert!(!found.contains(&("player_spawn_points", Some(0))));
+    }
+
+    #[test]
+    fn targets_inside_walls_are_unreachable() {
+        let mut arena_properties = walled_arena();
+        arena_properties.arena_rectangles[0].height = 100.0; //no longer splits the arena
+        arena_properties.player_spawn_points.truncate(1);
+        arena_properties.weapon_spawners.clear();
+        arena_properties.king_hills[0].x = 200.0;
+
+        let errors = validate_arena(ArenaNames::OpenEmptyMap, &arena_properties, None);
+
+        let found: Vec<(&str, Option<usize>)> = errors
+            .iter()
+            .map(|error| (error.element, error.index))
+            .collect();
+
+        assert_eq!(found, vec![("king_hills", Some(0))]);
+    }
+
+    #[test]
+    fn spawns_off_the_floor_are_reported() {
+        let mut arena_properties = walled_arena();
+        arena_properties.arena_rectangles[0].height = 100.0;
+        arena_properties.weapon_spawners.clear();
+        arena_properties.king_hills[0].x = 100.0;
+        arena_properties.king_hills[0].y = 300.0;
+        arena_properties.player_spawn_points[1].x = 300.0;
+        arena_properties.player_spawn_points[1].y = 300.0;
+        arena_properties.floor = vec![ArenaFloor {
+            x: 100.0,
+            y: 200.0,
+            width: 200.0,
+            height: 400.0,
+        }];
+
+        let errors = validate_arena(ArenaNames::OpenEmptyMap, &arena_properties, None);
+
+        let found: Vec<(&str, Option<usize>)> = errors
+            .iter()
+            .map(|error| (error.element, error.index))
+            .collect();
+
+        assert_eq!(found, vec![("player_spawn_points", Some(1))]);
+    }
+}
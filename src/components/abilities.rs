@@ -0,0 +1,157 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage, Entity};
+use serde::Deserialize;
+
+use crate::components::Vehicle;
+
+pub const CLOAKED_VEHICLE_ALPHA: f32 = 0.25;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum VehicleAbilityTypes {
+    NitroBoost,       //extra thrust and top speed for a short time
+    SideDash,         //sideways burst towards the strafe direction
+    ShieldOvercharge, //shields pushed above their max until it wears off
+    Cloak,            //hidden from auto-aim and turrets
+}
+
+//The third ability slot of a vehicle, alongside its two firing groups and repair
+#[derive(Clone, Debug, Deserialize)]
+pub struct VehicleAbilityStats {
+    pub display_name: String,
+    pub ability_type: VehicleAbilityTypes,
+    pub cooldown: f32,
+    pub duration: f32,
+    pub max_velocity_pct: f32,  //extra top speed while active
+    pub accel: f32,             //extra forward thrust while active
+    pub impulse: f32,           //push given once when activated
+    pub shield_overcharge: f32, //shield points above max while active
+}
+
+#[derive(Clone)]
+pub struct VehicleAbility {
+    pub stats: VehicleAbilityStats,
+    pub cooldown_timer: f32,
+    pub active_timer: f32,
+    pub entity: Entity, //HUD indicator
+}
+
+impl Component for VehicleAbility {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl VehicleAbility {
+    pub fn new(stats: VehicleAbilityStats, entity: Entity) -> VehicleAbility {
+        VehicleAbility {
+            stats,
+            cooldown_timer: 0.0,
+            active_timer: 0.0,
+            entity,
+        }
+    }
+
+    pub fn is_active(&self, ability_type: VehicleAbilityTypes) -> bool {
+        self.stats.ability_type == ability_type && self.active_timer > 0.0
+    }
+
+    //Counts down the cooldown and the active time, true on the frame it runs out
+    pub fn update_timers(&mut self, dt: f32) -> bool {
+        if self.cooldown_timer > 0.0 {
+            self.cooldown_timer -= dt;
+        }
+
+        if self.active_timer > 0.0 {
+            self.active_timer -= dt;
+
+            if self.active_timer <= 0.0 {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //Starts the ability if it is off cooldown, cooldown runs alongside the active time
+    pub fn try_activate(&mut self) -> bool {
+        if self.cooldown_timer <= 0.0 {
+            self.cooldown_timer = self.stats.cooldown;
+            self.active_timer = self.stats.duration;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+//Cloaked vehicles can't be picked out by auto-aim or deployables
+pub fn vehicle_is_cloaked(vehicle: &Vehicle) -> bool {
+    match &vehicle.ability {
+        Some(ability) => ability.is_active(VehicleAbilityTypes::Cloak),
+        None => false,
+    }
+}
+
+//Alpha for the vehicle and everything drawn over it, so the overlays fade out with it
+pub fn vehicle_cloak_alpha(vehicle: &Vehicle) -> f32 {
+    if vehicle_is_cloaked(vehicle) {
+        CLOAKED_VEHICLE_ALPHA
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::{Builder, World, WorldExt};
+    use assert_approx_eq::assert_approx_eq;
+
+    fn test_ability(cooldown: f32, duration: f32) -> VehicleAbility {
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+
+        let stats = VehicleAbilityStats {
+            display_name: "Nitro".to_string(),
+            ability_type: VehicleAbilityTypes::NitroBoost,
+            cooldown,
+            duration,
+            max_velocity_pct: 0.5,
+            accel: 20.0,
+            impulse: 0.0,
+            shield_overcharge: 0.0,
+        };
+
+        VehicleAbility::new(stats, entity)
+    }
+
+    #[test]
+    fn activating_starts_the_cooldown_and_active_time() {
+        let mut ability = test_ability(5.0, 2.0);
+
+        assert!(ability.try_activate());
+        assert_approx_eq!(ability.cooldown_timer, 5.0);
+        assert_approx_eq!(ability.active_timer, 2.0);
+        assert!(ability.is_active(VehicleAbilityTypes::NitroBoost));
+        assert!(!ability.is_active(VehicleAbilityTypes::Cloak));
+
+        //can't be used again while cooling down
+        assert!(!ability.try_activate());
+    }
+
+    #[test]
+    fn expires_once_then_cools_down() {
+        let mut ability = test_ability(5.0, 2.0);
+        ability.try_activate();
+
+        assert!(!ability.update_timers(1.5));
+        assert!(ability.is_active(VehicleAbilityTypes::NitroBoost));
+
+        assert!(ability.update_timers(1.0));
+        assert!(!ability.is_active(VehicleAbilityTypes::NitroBoost));
+
+        //only reported on the frame it ran out
+        assert!(!ability.update_timers(1.0));
+        assert!(!ability.try_activate());
+
+        ability.update_timers(2.0);
+        assert!(ability.try_activate());
+    }
+}
@@ -1,13 +1,13 @@
 use amethyst::ecs::prelude::{Component, DenseVecStorage, World};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::components::{Hitbox, HitboxShape, WeaponNames};
 use crate::load_ron_asset;
 use crate::resources::GameModes;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum ArenaNames {
     OpenEmptyMap,
     StandardCombat,
@@ -15,41 +15,43 @@ pub enum ArenaNames {
     StandardRace,
     ChaosCombat,
     LargeCombat,
+    Generated,
+    CustomArena,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum RaceCheckpointType {
     NotCheckpoint,
     Checkpoint,
     Lap,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum ObstacleType {
     Open,
     Wall,
     Zone,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ZoneEffects {
     pub accel_rate: f32,
     pub damage_rate: f32,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum EnemyNames {
     AutoTurret,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct PlayerSpawnPoint {
     pub x: f32,
     pub y: f32,
     pub rotation: f32, //degrees
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct WeaponBoxSpawner {
     pub x: f32,
     pub y: f32,
@@ -59,7 +61,7 @@ pub struct WeaponBoxSpawner {
     pub ammo: Option<u32>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct WeaponBox {
     pub x: f32,
     pub y: f32,
@@ -67,23 +69,24 @@ pub struct WeaponBox {
     pub ammo: Option<u32>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct EnemySpawnPoint {
     pub x: f32,
     pub y: f32,
     pub enemy_name: Option<EnemyNames>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ArenaCircle {
     pub obstacle_type: ObstacleType,
     pub x: f32,
     pub y: f32,
     pub radius: f32,
     pub effect: Option<ZoneEffects>,
+    pub health: Option<f32>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ArenaRectangle {
     pub obstacle_type: ObstacleType,
     pub x: f32,
@@ -92,16 +95,17 @@ pub struct ArenaRectangle {
     pub height: f32,
     pub rotation: f32, //degrees
     pub effects: Option<ZoneEffects>,
+    pub health: Option<f32>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ArenaKingHill {
     pub x: f32,
     pub y: f32,
     pub radius: f32,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ArenaRaceCheckpoint {
     pub x: f32,
     pub y: f32,
@@ -109,7 +113,7 @@ pub struct ArenaRaceCheckpoint {
     pub rotation: f32, //degrees
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ArenaFloor {
     pub x: f32,
     pub y: f32,
@@ -117,7 +121,7 @@ pub struct ArenaFloor {
     pub height: f32,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ArenaElement {
     pub obstacle_type: ObstacleType,
     pub is_hill: bool,
@@ -139,13 +143,14 @@ pub struct ArenaElement {
     pub ammo: Option<u32>,
     pub hitbox: Hitbox,
     pub effects: Option<ZoneEffects>,
+    pub health: Option<f32>, //destructible walls only, None is indestructible
 }
 
 impl Component for ArenaElement {
     type Storage = DenseVecStorage<Self>;
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
 pub struct ArenaProperties {
     pub width: f32,
     pub height: f32,
@@ -173,6 +178,11 @@ pub fn build_arena_store(world: &mut World) {
     });
 }
 
+pub fn arena_properties_to_ron(arena_properties: &ArenaProperties) -> String {
+    ron::ser::to_string_pretty(arena_properties, ron::ser::PrettyConfig::default())
+        .expect("Failed to serialize arena")
+}
+
 pub fn reform_weapon_spawner(spawner: WeaponBoxSpawner) -> ArenaElement {
     ArenaElement {
         obstacle_type: ObstacleType::Open,
@@ -200,5 +210,52 @@ pub fn reform_weapon_spawner(spawner: WeaponBoxSpawner) -> ArenaElement {
             shape: HitboxShape::Rectangle,
         },
         effects: None,
+        health: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_elements_round_trip_through_ron() {
+        let mut weapon_spawner = reform_weapon_spawner(WeaponBoxSpawner {
+            x: 120.0,
+            y: 80.0,
+            weapon_names: None,
+            first_spawn_time: Some(5.0),
+            spawn_time: Some(20.0),
+            ammo: Some(30),
+        });
+        weapon_spawner.weapon_names = Some(vec![(WeaponNames::LaserDoubleGimballed, 2)]);
+
+        let mut damage_zone = reform_weapon_spawner(WeaponBoxSpawner {
+            x: 300.0,
+            y: 200.0,
+            weapon_names: None,
+            first_spawn_time: None,
+            spawn_time: None,
+            ammo: None,
+        });
+        damage_zone.obstacle_type = ObstacleType::Zone;
+        damage_zone.is_weapon_spawn_point = false;
+        damage_zone.effects = Some(ZoneEffects {
+            accel_rate: 0.5,
+            damage_rate: 10.0,
+        });
+        damage_zone.health = Some(100.0);
+
+        let arena_properties = ArenaProperties {
+            width: 600.0,
+            height: 400.0,
+            custom_elements: vec![weapon_spawner, damage_zone],
+            ..ArenaProperties::default()
+        };
+
+        let arena_ron = arena_properties_to_ron(&arena_properties);
+        let loaded: ArenaProperties = ron::de::from_str(&arena_ron).unwrap();
+
+        assert_eq!(loaded, arena_properties);
     }
 }
@@ -0,0 +1,109 @@
+use amethyst::ecs::prelude::World;
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::load_ron_asset;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum BotDifficulty {
+    Easy,
+    Normal,
+    Hard,
+    Expert,
+}
+
+impl BotDifficulty {
+    pub fn next(self) -> BotDifficulty {
+        match self {
+            BotDifficulty::Easy => BotDifficulty::Normal,
+            BotDifficulty::Normal => BotDifficulty::Hard,
+            BotDifficulty::Hard => BotDifficulty::Expert,
+            BotDifficulty::Expert => BotDifficulty::Easy,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum BotPersonality {
+    Balanced,
+    Aggressive,
+    Cautious,
+    Sniper,
+    Scavenger,
+}
+
+impl BotPersonality {
+    pub fn next(self) -> BotPersonality {
+        match self {
+            BotPersonality::Balanced => BotPersonality::Aggressive,
+            BotPersonality::Aggressive => BotPersonality::Cautious,
+            BotPersonality::Cautious => BotPersonality::Sniper,
+            BotPersonality::Sniper => BotPersonality::Scavenger,
+            BotPersonality::Scavenger => BotPersonality::Balanced,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub struct BotProfile {
+    pub aggression: f32, //multiplier on how eagerly the bot engages and hunts enemies
+    pub preferred_range: f32, //distance kept from the target while shooting
+    pub aim_error: f32,  //max aim offset in degrees, re-rolled with every decision
+    pub reaction_delay: f32, //seconds between behavior decisions
+    pub repair_threshold: f32, //health percent below which the bot will retreat to repair
+    pub weapon_box_greed: f32, //multiplier on how far out of the way the bot goes for weapon boxes
+}
+
+impl Default for BotProfile {
+    fn default() -> Self {
+        Self {
+            aggression: 1.0,
+            preferred_range: 80.0,
+            aim_error: 6.0,
+            reaction_delay: 0.25,
+            repair_threshold: 100.0,
+            weapon_box_greed: 1.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub struct BotDifficultyScaling {
+    pub aggression_mult: f32,
+    pub aim_error_mult: f32,
+    pub reaction_delay_mult: f32,
+}
+
+#[derive(Clone)]
+pub struct BotProfileStoreResource {
+    pub personalities: HashMap<BotPersonality, BotProfile>,
+    pub difficulties: HashMap<BotDifficulty, BotDifficultyScaling>,
+}
+
+pub fn build_bot_profile_store(world: &mut World) {
+    world.insert(BotProfileStoreResource {
+        personalities: load_ron_asset(&["game", "bot_personalities.ron"]),
+        difficulties: load_ron_asset(&["game", "bot_difficulties.ron"]),
+    });
+}
+
+//Personality profile with the difficulty scaling applied
+pub fn get_bot_profile(
+    bot_profile_store: &BotProfileStoreResource,
+    difficulty: BotDifficulty,
+    personality: BotPersonality,
+) -> BotProfile {
+    let mut bot_profile = match bot_profile_store.personalities.get(&personality) {
+        Some(personality_profile) => *personality_profile,
+        _ => BotProfile::default(),
+    };
+
+    if let Some(scaling) = bot_profile_store.difficulties.get(&difficulty) {
+        bot_profile.aggression *= scaling.aggression_mult;
+        bot_profile.aim_error *= scaling.aim_error_mult;
+        bot_profile.reaction_delay *= scaling.reaction_delay_mult;
+    }
+
+    bot_profile
+}
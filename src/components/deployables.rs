@@ -0,0 +1,125 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::components::WeaponNames;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum DeployableTypes {
+    Turret, //stays where it was dropped
+    Drone,  //orbits its owner
+}
+
+//What a deploying weapon drops, in place of firing a shot
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct DeployableStats {
+    pub deployable_type: DeployableTypes,
+    pub weapon_name: WeaponNames, //gun the deployable fires with
+    pub health: f32,
+    pub lifetime: f32,
+    pub range: f32,
+    pub radius: f32,
+    pub orbit_radius: f32,
+    pub orbit_speed: f32, //radians per second
+}
+
+//An owned turret or drone, its gun is in a WeaponArray on the same entity
+pub struct Deployable {
+    pub owner_player_id: usize,
+    pub owner_team: i32, //teammates are never targeted
+    pub stats: DeployableStats,
+    pub health: f32,
+    pub lifetime_timer: f32,
+    pub orbit_angle: f32,
+}
+
+impl Component for Deployable {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Deployable {
+    pub fn new(
+        owner_player_id: usize,
+        owner_team: i32,
+        stats: DeployableStats,
+        orbit_angle: f32,
+    ) -> Deployable {
+        Deployable {
+            owner_player_id,
+            owner_team,
+            stats,
+            health: stats.health,
+            lifetime_timer: stats.lifetime,
+            orbit_angle,
+        }
+    }
+
+    //Fire from the owner or their teammates doesn't wear a deployable down,
+    //  shots that lost their owner (bounced) hurt everyone
+    pub fn is_friendly_fire(
+        &self,
+        shot_owner_player_id: Option<usize>,
+        player_teams: &HashMap<usize, i32>,
+    ) -> bool {
+        match shot_owner_player_id {
+            Some(shot_owner_player_id) => {
+                shot_owner_player_id == self.owner_player_id
+                    || player_teams.get(&shot_owner_player_id) == Some(&self.owner_team)
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_deployable(owner_player_id: usize, owner_team: i32) -> Deployable {
+        let stats = DeployableStats {
+            deployable_type: DeployableTypes::Turret,
+            weapon_name: WeaponNames::LaserDoubleGimballed,
+            health: 50.0,
+            lifetime: 20.0,
+            range: 300.0,
+            radius: 8.0,
+            orbit_radius: 0.0,
+            orbit_speed: 0.0,
+        };
+
+        Deployable::new(owner_player_id, owner_team, stats, 0.0)
+    }
+
+    #[test]
+    fn owner_and_teammates_fire_is_friendly() {
+        let deployable = test_deployable(0, 1);
+
+        let mut player_teams = HashMap::new();
+        player_teams.insert(0, 1);
+        player_teams.insert(1, 1);
+        player_teams.insert(2, 2);
+
+        assert!(deployable.is_friendly_fire(Some(0), &player_teams));
+        assert!(deployable.is_friendly_fire(Some(1), &player_teams));
+        assert!(!deployable.is_friendly_fire(Some(2), &player_teams));
+    }
+
+    #[test]
+    fn owner_is_friendly_even_without_a_team() {
+        let deployable = test_deployable(3, 1);
+        let player_teams = HashMap::new();
+
+        assert!(deployable.is_friendly_fire(Some(3), &player_teams));
+        assert!(!deployable.is_friendly_fire(Some(4), &player_teams));
+    }
+
+    #[test]
+    fn ownerless_shots_hurt_everyone() {
+        let deployable = test_deployable(0, 1);
+
+        let mut player_teams = HashMap::new();
+        player_teams.insert(0, 1);
+
+        assert!(!deployable.is_friendly_fire(None, &player_teams));
+    }
+}
@@ -1,30 +1,48 @@
+pub use self::abilities::{
+    vehicle_cloak_alpha, vehicle_is_cloaked, VehicleAbility, VehicleAbilityStats,
+    VehicleAbilityTypes,
+};
 pub use self::arena::{
-    build_arena_store, reform_weapon_spawner, ArenaElement, ArenaNames, ArenaProperties,
-    ArenaStoreResource, ObstacleType, RaceCheckpointType, WeaponBox, WeaponBoxSpawner,
+    arena_properties_to_ron, build_arena_store, reform_weapon_spawner, ArenaCircle, ArenaElement,
+    ArenaFloor, ArenaKingHill, ArenaNames, ArenaProperties, ArenaRaceCheckpoint, ArenaRectangle,
+    ArenaStoreResource, ObstacleType, PlayerSpawnPoint, RaceCheckpointType, WeaponBox,
+    WeaponBoxSpawner,
 };
 pub use self::armor::Armor;
+pub use self::bot_profiles::{
+    build_bot_profile_store, get_bot_profile, BotDifficulty, BotDifficultyScaling, BotPersonality,
+    BotProfile, BotProfileStoreResource,
+};
+pub use self::deployables::{Deployable, DeployableStats, DeployableTypes};
 pub use self::health::Health;
 pub use self::hitbox::{Hitbox, HitboxShape};
-pub use self::particles::{Particles, Shockwave};
-pub use self::players::{BotMode, Player, PlayerWeaponIcon};
+pub use self::particles::{ParticleTrail, Particles, Shockwave};
+pub use self::players::{BotIntent, BotMode, Player, PlayerWeaponIcon};
 pub use self::repair::Repair;
 pub use self::shields::Shield;
 pub use self::vehicles::{
-    build_vehicle_store, check_respawn_vehicle, determine_vehicle_weight,
-    determine_vehicle_weight_stats, get_next_vehicle_name, get_none_vehicle, get_prev_vehicle_name,
-    get_vehicle_sprites, kill_restart_vehicle, vehicle_damage_model, Vehicle, VehicleMovementType,
-    VehicleNames, VehicleState, VehicleStats, VehicleStoreResource, VehicleTypes,
+    apply_vehicle_impulse, build_vehicle_store, calculate_layered_damage, check_respawn_vehicle,
+    determine_vehicle_weight, determine_vehicle_weight_stats, get_next_vehicle_name,
+    get_none_vehicle, get_prev_vehicle_name, get_vehicle_sprites, kill_restart_vehicle,
+    vehicle_damage_model, Vehicle, VehicleMovementType, VehicleNames, VehicleState, VehicleStats,
+    VehicleStoreResource, VehicleTypes,
 };
 pub use self::weapons::{
-    build_named_weapon, build_named_weapon_from_world, build_weapon_store, get_mine_sprite,
-    get_next_gg_weapon_name, get_random_weapon_name, get_random_weapon_name_build_chance,
-    get_trap_sprite, get_weapon_icon, get_weapon_width_height, update_weapon_properties,
-    DurationDamage, Weapon, WeaponArray, WeaponFire, WeaponFireTypes, WeaponInstall,
-    WeaponNameInstall, WeaponNames, WeaponStats, WeaponStoreResource,
+    add_weapon_modifier, build_named_weapon, build_named_weapon_from_world, build_weapon_store,
+    get_next_gg_weapon_name, get_random_weapon_modifier_name, get_random_weapon_name,
+    get_random_weapon_name_build_chance, get_weapon_heat_bar_transform, get_weapon_icon,
+    get_weapon_modifiers_text, reflect_weapon_fire, refresh_weapon_modifiers,
+    update_weapon_properties, weapon_fire_is_projectile, ChainingDamage, DurationDamage, Weapon,
+    WeaponArray, WeaponFire, WeaponFireStats, WeaponFireTypes, WeaponInstall, WeaponModifierNames,
+    WeaponNameInstall, WeaponNames, WeaponStats, WeaponStoreResource, WEAPON_HEAT_BAR_HEIGHT,
+    WEAPON_MAX_HEAT, WEAPON_SPLIT_SHOT_ANGLE,
 };
 
+mod abilities;
 mod arena;
 mod armor;
+mod bot_profiles;
+mod deployables;
 mod health;
 mod hitbox;
 mod particles;
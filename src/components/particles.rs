@@ -1,5 +1,7 @@
 use amethyst::ecs::prelude::{Component, DenseVecStorage};
 
+use crate::resources::WeaponFireTrail;
+
 pub struct Particles {
     pub dx: f32,
     pub dy: f32,
@@ -18,3 +20,22 @@ pub struct Shockwave {
 impl Component for Shockwave {
     type Storage = DenseVecStorage<Self>;
 }
+
+//Leaves a trail of particles behind a moving shot
+pub struct ParticleTrail {
+    pub trail: WeaponFireTrail,
+    pub timer: f32,
+}
+
+impl Component for ParticleTrail {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl ParticleTrail {
+    pub fn new(trail: WeaponFireTrail) -> ParticleTrail {
+        ParticleTrail {
+            trail,
+            timer: trail.rate,
+        }
+    }
+}
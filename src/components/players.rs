@@ -1,6 +1,6 @@
 use amethyst::ecs::prelude::{Component, DenseVecStorage};
 
-use crate::components::WeaponFireTypes;
+use crate::components::{BotProfile, WeaponFireTypes};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum BotMode {
@@ -10,18 +10,31 @@ pub enum BotMode {
     RunBlind,
     //FindEnemy,
     TakeTheHill,
-    //PickUpWeaponBox,
+    GuardTheHill,
+    PickUpWeaponBox,
+    SearchLastSeen,
     Racing,
     StopAim,
     StrafeAim,
-    CollisionTurn,
-    CollisionMove,
     Mining,
     Chasing,
     Swording,
     Repairing,
 }
 
+//Control inputs chosen by the BotAiSystem, consumed the same way as player input
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BotIntent {
+    pub accel: Option<f32>,
+    pub turn: Option<f32>,
+    pub strafe: Option<f32>,
+    pub primary_fire: Option<bool>,
+    pub secondary_fire: Option<bool>,
+    pub repair: Option<bool>,
+    pub reload: Option<bool>,
+    pub ability: Option<bool>,
+}
+
 pub struct Player {
     pub id: usize,
     pub team: i32,
@@ -35,12 +48,29 @@ pub struct Player {
     pub on_hill: bool,
     pub is_bot: bool,
     pub bot_mode: BotMode,
+    pub bot_intent: BotIntent,
+    pub bot_profile: BotProfile,
+    pub bot_reaction_timer: f32,
+    pub bot_aim_offset: f32, //radians
     pub bot_move_cooldown: f32,
     pub bot_move_cooldown_reset: f32,
     pub path_target: Option<(f32, f32, f32)>,
     pub path_plan: Option<Vec<(f32, f32, f32)>>,
+    pub path_plan_index: usize, //next path plan point being driven towards
+    pub path_plan_target: Option<(f32, f32, f32)>, //path target the path plan was built for
+    pub path_replan_timer: f32,
     pub path_cooldown: f32,
     pub path_cooldown_reset: f32,
+    pub bot_stuck_timer: f32,
+    pub bot_stuck_check_position: (f32, f32),
+    pub bot_unstuck_timer: f32,
+    pub bot_unstuck_turn: f32,
+    pub racing_line_index: Option<usize>, //closest racing line point, None when lost
+    pub bot_last_seen_enemy: Option<(f32, f32)>,
+    pub bot_last_seen_timer: f32, //forget the last seen enemy position when this runs out
+    pub bot_attack_target: Option<(f32, f32)>, //where the vehicle being attacked is
+    pub bot_weapon_box: Option<(f32, f32, f32)>, //best weapon box to pick up, (x, y, value)
+    pub bot_weapon_box_timer: f32, //weapon boxes are looked over again when this runs out
     pub last_accel_input: Option<f32>,
     pub last_turn_input: Option<f32>,
     pub last_hit_by_id: Option<usize>,
@@ -67,12 +97,29 @@ impl Player {
             on_hill: false,
             is_bot,
             bot_mode: BotMode::Sleep,
+            bot_intent: BotIntent::default(),
+            bot_profile: BotProfile::default(),
+            bot_reaction_timer: 0.0,
+            bot_aim_offset: 0.0,
             bot_move_cooldown: -1.0,
             bot_move_cooldown_reset: 1.0,
             path_target: None,
             path_plan: None,
+            path_plan_index: 1,
+            path_plan_target: None,
+            path_replan_timer: 0.0,
             path_cooldown: 0.0,
             path_cooldown_reset: 10.0,
+            bot_stuck_timer: 0.0,
+            bot_stuck_check_position: (0.0, 0.0),
+            bot_unstuck_timer: 0.0,
+            bot_unstuck_turn: 0.0,
+            racing_line_index: None,
+            bot_last_seen_enemy: None,
+            bot_last_seen_timer: 0.0,
+            bot_attack_target: None,
+            bot_weapon_box: None,
+            bot_weapon_box_timer: 0.0,
             last_accel_input: Some(0.0),
             last_turn_input: Some(0.0),
             last_hit_by_id: None,
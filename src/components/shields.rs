@@ -10,6 +10,7 @@ pub struct Shield {
     pub repair_timer: f32,
     pub repair_reboot_time: f32,
     pub radius: f32,
+    pub reflect_pct: f32, //chance to deflect a projectile back while shields are up
     pub entity: Entity,
 }
 
@@ -9,13 +9,15 @@ use std::collections::HashMap;
 use std::f32::consts::PI;
 
 use crate::components::{
-    ArenaProperties, Armor, DurationDamage, Health, Player, Repair, Shield, WeaponNameInstall,
-    WeaponNames,
+    ArenaProperties, Armor, DurationDamage, Health, Player, Repair, Shield, VehicleAbility,
+    VehicleAbilityStats, WeaponNameInstall, WeaponNames,
 };
 use crate::entities::ui::PlayerStatusText;
 use crate::load_ron_asset;
 use crate::resources::GameModes;
 
+const VEHICLE_IMPULSE_SPIN_RATE: f32 = 0.05; //how much of a knockback becomes spin, per vehicle width of lever
+
 //VehicleNames correspond to the vehicle_properties.ron
 #[derive(Copy, Clone, Debug, PartialEq, Deserialize, Hash, Eq)]
 pub enum VehicleNames {
@@ -61,13 +63,17 @@ pub struct Vehicle {
     pub dr: f32,
     pub angle_to_closest_vehicle: Option<f32>,
     pub dist_to_closest_vehicle: Option<f32>,
+    pub closest_vehicle_in_sight: bool, //false when only enemies behind walls are left
     pub angle_to_closest_targetable_vehicle: Option<f32>,
     pub dist_to_closest_targetable_vehicle: Option<f32>,
+    pub velocity_of_closest_vehicle: Option<(f32, f32)>,
+    pub velocity_of_closest_targetable_vehicle: Option<(f32, f32)>,
     pub collision_cooldown_timer: f32,
     pub health: Health,
     pub armor: Armor,
     pub shield: Shield,
     pub repair: Repair,
+    pub ability: Option<VehicleAbility>,
     pub heal_pulse_amount: f32,
     pub heal_pulse_rate: f32,
     pub heal_cooldown_timer: f32,
@@ -101,6 +107,7 @@ impl Vehicle {
         armor_entity: Entity,
         shield_entity: Entity,
         repair_entity: Entity,
+        ability_entity: Entity,
         stats: VehicleStats,
         weapon_weight: f32,
     ) -> Vehicle {
@@ -113,8 +120,11 @@ impl Vehicle {
             dr: 0.0,
             angle_to_closest_vehicle: None,
             dist_to_closest_vehicle: None,
+            closest_vehicle_in_sight: false,
             angle_to_closest_targetable_vehicle: None,
             dist_to_closest_targetable_vehicle: None,
+            velocity_of_closest_vehicle: None,
+            velocity_of_closest_targetable_vehicle: None,
             collision_cooldown_timer: 0.0,
             health: Health {
                 value: stats.max_health,
@@ -136,6 +146,7 @@ impl Vehicle {
                 repair_timer: 0.0,
                 repair_reboot_time: stats.shield_repair_reboot_time,
                 radius: stats.shield_radius,
+                reflect_pct: stats.shield_reflect_pct,
                 entity: shield_entity,
             },
             repair: Repair {
@@ -144,6 +155,10 @@ impl Vehicle {
                 init_time_threshold: stats.health_repair_init_time,
                 entity: repair_entity,
             },
+            ability: match stats.ability {
+                Some(ability_stats) => Some(VehicleAbility::new(ability_stats, ability_entity)),
+                None => None,
+            },
             heal_pulse_amount: stats.heal_pulse_amount,
             heal_pulse_rate: stats.heal_pulse_rate,
             heal_cooldown_timer: 0.0,
@@ -230,6 +245,55 @@ pub fn kill_restart_vehicle(
     }
 }
 
+//Push a vehicle with an impulse along (dir_x, dir_y), heavier vehicles are pushed less
+//Hits off the center of the vehicle also set it spinning
+pub fn apply_vehicle_impulse(
+    vehicle: &mut Vehicle,
+    impulse: f32,
+    dir_x: f32,
+    dir_y: f32,
+    offset_x: f32,
+    offset_y: f32,
+) {
+    let (delta_dx, delta_dy, delta_dr) = calc_vehicle_impulse(
+        impulse,
+        determine_vehicle_weight(vehicle),
+        vehicle.width,
+        (dir_x, dir_y),
+        (offset_x, offset_y),
+    );
+
+    vehicle.dx += delta_dx;
+    vehicle.dy += delta_dy;
+    vehicle.dr += delta_dr;
+}
+
+//Change in (dx, dy, dr) an impulse gives a vehicle of this weight and width
+fn calc_vehicle_impulse(
+    impulse: f32,
+    vehicle_weight: f32,
+    vehicle_width: f32,
+    (dir_x, dir_y): (f32, f32),
+    (offset_x, offset_y): (f32, f32),
+) -> (f32, f32, f32) {
+    let dir_length = (dir_x.powi(2) + dir_y.powi(2)).sqrt();
+
+    if impulse.abs() < 0.001 || dir_length < 0.001 {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let delta_v = impulse / vehicle_weight;
+
+    //torque from the hit's offset from the center, counter-clockwise is positive
+    let lever = (offset_x * dir_y - offset_y * dir_x) / dir_length;
+
+    (
+        delta_v * dir_x / dir_length,
+        delta_v * dir_y / dir_length,
+        VEHICLE_IMPULSE_SPIN_RATE * delta_v * lever / vehicle_width.max(1.0),
+    )
+}
+
 pub fn check_respawn_vehicle(
     vehicle: &mut Vehicle,
     transform: &mut Transform,
@@ -268,6 +332,11 @@ pub fn check_respawn_vehicle(
             vehicle.armor.value = vehicle.armor.max;
             vehicle.health.value = vehicle.health.max;
 
+            if let Some(ability) = &mut vehicle.ability {
+                ability.cooldown_timer = 0.0;
+                ability.active_timer = 0.0;
+            }
+
             if game_mode == GameModes::Race {
                 transform.set_rotation_2d(vehicle.death_angle);
                 transform.set_translation_xyz(vehicle.death_x, vehicle.death_y, 0.0);
@@ -289,6 +358,86 @@ pub fn check_respawn_vehicle(
     spawn_index
 }
 
+//Damage taken off each layer by a single hit, and which layers it reached
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LayeredDamage {
+    pub shield: f32,
+    pub armor: f32,
+    pub health: f32, //not capped at the health left, that is up to the caller
+    pub shield_applied: bool,
+    pub armor_applied: bool,
+    pub health_applied: bool,
+}
+
+//Damage goes through shields, then armor, then health,
+//  with piercing damage skipping straight through to health.
+//Shared by vehicle_damage_model and the weapon balance numbers, healing is handled separately
+pub fn calculate_layered_damage(
+    shield: f32,
+    armor: f32,
+    mut damage: f32,
+    piercing_damage_pct: f32,
+    shield_damage_pct: f32,
+    armor_damage_pct: f32,
+    health_damage_pct: f32,
+) -> LayeredDamage {
+    let mut layered_damage = LayeredDamage::default();
+
+    let mut piercing_damage: f32 = 0.0;
+
+    if piercing_damage_pct > 0.0 {
+        piercing_damage = damage * piercing_damage_pct / 100.0;
+        damage -= piercing_damage;
+    }
+
+    if shield > 0.0 {
+        if damage * shield_damage_pct > 0.0 {
+            layered_damage.shield_applied = true;
+
+            let shield_damage = damage * shield_damage_pct / 100.0;
+            damage = 0.0;
+
+            if shield_damage > shield {
+                damage = shield_damage - shield; //over damage on shields, needs taken from armor
+                layered_damage.shield = shield;
+            } else {
+                layered_damage.shield = shield_damage;
+            }
+        } else {
+            damage = 0.0;
+            //This would happen if the vehicle has shields, but is being hit with a weapon that does no damage to shields
+            //Therefore no damage can be done, unless it is piercing damage
+        }
+    }
+
+    if armor > 0.0 {
+        if damage * armor_damage_pct > 0.0 {
+            layered_damage.armor_applied = true;
+
+            let armor_damage = damage * armor_damage_pct / 100.0;
+            damage = 0.0;
+
+            if armor_damage > armor {
+                damage = armor_damage - armor; //over damage on armor, needs taken from health
+                layered_damage.armor = armor;
+            } else {
+                layered_damage.armor = armor_damage;
+            }
+        } else {
+            damage = 0.0;
+            //This would happen if the vehicle has armor, but is being hit with a weapon that does no damage to armor
+            //Therefore no damage can be done, unless it is piercing damage
+        }
+    }
+
+    layered_damage.health = (damage + piercing_damage) * health_damage_pct / 100.0;
+    if layered_damage.health > 0.0 {
+        layered_damage.health_applied = true;
+    }
+
+    layered_damage
+}
+
 pub fn vehicle_damage_model(
     vehicle: &mut Vehicle,
     damager_id: Option<usize>,
@@ -308,56 +457,32 @@ pub fn vehicle_damage_model(
         let mut health_damage_applied: bool = false;
 
         if damage >= 0.0 {
-            let mut piercing_damage: f32 = 0.0;
-
-            if piercing_damage_pct > 0.0 {
-                piercing_damage = damage * piercing_damage_pct / 100.0;
-                damage -= piercing_damage;
-            }
-
-            if vehicle.shield.value > 0.0 {
-                if damage * shield_damage_pct > 0.0 {
-                    shield_damage_applied = true;
-
-                    vehicle.shield.value -= damage * shield_damage_pct / 100.0;
-                    damage = 0.0;
-
-                    if vehicle.shield.value < 0.0 {
-                        damage = vehicle.shield.value.abs(); //over damage on shields, needs taken from armor
-                        vehicle.shield.value = 0.0;
-                    } else {
-                        //take damage to shields, but shields are still alive, reset shield recharge cooldown
-                        vehicle.shield.cooldown_timer = vehicle.shield.cooldown_reset;
-                    }
-                } else {
-                    damage = 0.0;
-                    //This would happen if the vehicle has shields, but is being hit with a weapon that does no damage to shields
-                    //Therefore no damage can be done, unless it is piercing damage
+            let layered_damage = calculate_layered_damage(
+                vehicle.shield.value,
+                vehicle.armor.value,
+                damage,
+                piercing_damage_pct,
+                shield_damage_pct,
+                armor_damage_pct,
+                health_damage_pct,
+            );
+
+            shield_damage_applied = layered_damage.shield_applied;
+            armor_damage_applied = layered_damage.armor_applied;
+            health_damage_applied = layered_damage.health_applied;
+
+            if shield_damage_applied {
+                vehicle.shield.value -= layered_damage.shield;
+
+                if vehicle.shield.value > 0.0 {
+                    //take damage to shields, but shields are still alive, reset shield recharge cooldown
+                    vehicle.shield.cooldown_timer = vehicle.shield.cooldown_reset;
                 }
             }
 
-            if vehicle.armor.value > 0.0 {
-                if damage * armor_damage_pct > 0.0 {
-                    armor_damage_applied = true;
+            vehicle.armor.value -= layered_damage.armor;
 
-                    vehicle.armor.value -= damage * armor_damage_pct / 100.0;
-                    damage = 0.0;
-
-                    if vehicle.armor.value < 0.0 {
-                        damage = vehicle.armor.value.abs(); //over damage on armor, needs taken from health
-                        vehicle.armor.value = 0.0;
-                    }
-                } else {
-                    damage = 0.0;
-                    //This would happen if the vehicle has armor, but is being hit with a weapon that does no damage to armor
-                    //Therefore no damage can be done, unless it is piercing damage
-                }
-            }
-
-            let health_damage: f32 = (damage + piercing_damage) * health_damage_pct / 100.0;
-            if health_damage > 0.0 {
-                health_damage_applied = true;
-            }
+            let health_damage = layered_damage.health;
 
             if vehicle.health.value > 0.0 {
                 //only destroy once
@@ -474,8 +599,10 @@ pub fn get_none_vehicle() -> VehicleStats {
         shield_cooldown: 0.0,
         shield_repair_reboot_time: 0.0,
         shield_radius: 0.0,
+        shield_reflect_pct: 0.0,
         heal_pulse_amount: 0.0,
         heal_pulse_rate: 0.0,
+        ability: None,
         weapons_installed: vec![],
     }
 }
@@ -543,7 +670,71 @@ pub struct VehicleStats {
     pub shield_cooldown: f32,
     pub shield_repair_reboot_time: f32,
     pub shield_radius: f32,
+    pub shield_reflect_pct: f32,
     pub heal_pulse_amount: f32,
     pub heal_pulse_rate: f32,
+    pub ability: Option<VehicleAbilityStats>,
     pub weapons_installed: Vec<WeaponNameInstall>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn heavier_vehicles_are_pushed_less() {
+        let (light_dx, light_dy, _) =
+            calc_vehicle_impulse(1000.0, 50.0, 10.0, (3.0, 4.0), (0.0, 0.0));
+        let (heavy_dx, heavy_dy, _) =
+            calc_vehicle_impulse(1000.0, 100.0, 10.0, (3.0, 4.0), (0.0, 0.0));
+
+        //pushed along the direction, however long it is
+        assert_approx_eq!(light_dx, 12.0);
+        assert_approx_eq!(light_dy, 16.0);
+        assert_approx_eq!(heavy_dx, 6.0);
+        assert_approx_eq!(heavy_dy, 8.0);
+    }
+
+    #[test]
+    fn off_center_hits_spin_the_vehicle() {
+        //pushed up from the right hand side turns counter-clockwise
+        let (_, _, dr) = calc_vehicle_impulse(1000.0, 100.0, 10.0, (0.0, 1.0), (5.0, 0.0));
+        assert!(dr > 0.0);
+
+        let (_, _, dr) = calc_vehicle_impulse(1000.0, 100.0, 10.0, (0.0, 1.0), (-5.0, 0.0));
+        assert!(dr < 0.0);
+
+        let (_, _, dr) = calc_vehicle_impulse(1000.0, 100.0, 10.0, (0.0, 1.0), (0.0, 0.0));
+        assert_eq!(dr, 0.0);
+    }
+
+    #[test]
+    fn no_push_without_a_direction() {
+        let delta = calc_vehicle_impulse(1000.0, 100.0, 10.0, (0.0, 0.0), (5.0, 0.0));
+        assert_eq!(delta, (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn damage_spills_over_from_shields_to_armor_to_health() {
+        let layered_damage = calculate_layered_damage(10.0, 20.0, 50.0, 0.0, 100.0, 100.0, 100.0);
+
+        assert_eq!(layered_damage.shield, 10.0);
+        assert_eq!(layered_damage.armor, 20.0);
+        assert_eq!(layered_damage.health, 20.0);
+        assert!(layered_damage.shield_applied);
+        assert!(layered_damage.armor_applied);
+        assert!(layered_damage.health_applied);
+    }
+
+    #[test]
+    fn only_piercing_damage_gets_past_shields_it_cannot_hurt() {
+        let layered_damage = calculate_layered_damage(10.0, 20.0, 50.0, 20.0, 0.0, 100.0, 100.0);
+
+        assert_eq!(layered_damage.shield, 0.0);
+        assert_eq!(layered_damage.armor, 0.0);
+        assert_eq!(layered_damage.health, 10.0);
+        assert!(!layered_damage.shield_applied);
+        assert!(!layered_damage.armor_applied);
+    }
+}
@@ -6,16 +6,22 @@ use amethyst::{
 };
 
 use rand::Rng;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use log::info;
 
-use crate::components::PlayerWeaponIcon;
+use crate::components::{DeployableStats, PlayerWeaponIcon};
 use crate::load_ron_asset;
 use crate::resources::{GameWeaponSelectionMode, GameWeaponSetup, WeaponFireResource};
+use crate::weapon_balance::calculate_weapon_dps;
 
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Hash, Eq)]
+pub const WEAPON_MAX_HEAT: f32 = 100.0;
+pub const WEAPON_HEAT_BAR_WIDTH: f32 = 3.0;
+pub const WEAPON_HEAT_BAR_HEIGHT: f32 = 20.0;
+pub const WEAPON_SPLIT_SHOT_ANGLE: f32 = 0.15; //angle between split shots, in radians
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, Hash, Eq)]
 pub enum WeaponNames {
     LaserBeam,
     LaserPulse,
@@ -42,9 +48,11 @@ pub enum WeaponNames {
     BioSpiker,
     StormGun,
     SlimeLauncher,
+    TurretDeployer,
+    DroneDeployer,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Hash, Eq)]
 pub enum WeaponFireTypes {
     LaserBeam,
     LaserPulse,
@@ -125,6 +133,39 @@ pub fn get_random_weapon_name_build_chance(
     weapon_selector
 }
 
+//Roll whether a weapon box holds a modifier, and which one, from the modifier roll table
+pub fn get_random_weapon_modifier_name(
+    modifier_table: &WeaponModifierTable,
+) -> Option<WeaponModifierNames> {
+    let mut rng = rand::thread_rng();
+
+    if rng.gen_range(0, 100) >= modifier_table.box_chance {
+        return None;
+    }
+
+    let mut chance_total: u32 = 0;
+
+    for (_modifier_name, modifier) in modifier_table.modifiers.iter() {
+        chance_total += modifier.spawn_chance;
+    }
+
+    if chance_total == 0 {
+        return None;
+    }
+
+    let mut chance_selector = rng.gen_range(0, chance_total);
+
+    for (modifier_name, modifier) in modifier_table.modifiers.iter() {
+        if chance_selector < modifier.spawn_chance {
+            return Some(*modifier_name);
+        } else {
+            chance_selector -= modifier.spawn_chance;
+        }
+    }
+
+    None
+}
+
 pub fn get_next_gg_weapon_name(
     weapon_name: Option<WeaponNames>,
     weapon_store_resource: &WeaponStoreResource,
@@ -237,6 +278,12 @@ pub struct WeaponStats {
     pub charge_timer_decrease: f32,
     pub charge_timer_decrease_min: f32,
     pub spin_up_timer_reset: f32,
+    pub heat_per_shot: f32, //out of WEAPON_MAX_HEAT, 0.0 never overheats
+    pub heat_dissipation_rate: f32, //heat lost per second
+    pub overheat_lockout: f32, //seconds the weapon can't fire after reaching max heat
+    pub magazine_size: u32, //0 fires straight from the reserve, never reloading
+    pub reload_time: f32,
+    pub reserve_ammo: Option<u32>, //rounds to reload magazines from, None is unlimited
     pub weight: f32,
     pub fire_stats: WeaponFireStats,
 }
@@ -262,9 +309,44 @@ pub struct WeaponFireStats {
     pub ion_malfunction_pct: f32,
     pub duration_damage: DurationDamage,
     pub bounces: u32,
+    pub beam_range: f32, //0.0 fires projectiles, otherwise a continuous beam this long doing damage per second
     pub chaining_damage: ChainingDamage,
     pub slow_down_effect: SlowDownEffect,
     pub stuck_accel_effect_timer: f32,
+    pub knockback: f32,      //impulse on the vehicle hit, radial for explosions
+    pub interceptable: bool, //can be shot down by enemy fire
+    pub reflects: bool,      //deflects enemy shots back, handing them over to this shot's owner
+    pub deployable: Option<DeployableStats>, //drops a turret or drone instead of firing a shot
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Hash, Eq)]
+pub enum WeaponModifierNames {
+    Bouncing,
+    SplitShot,
+    Homing,
+    IonCoating,
+    ExtendedRange,
+    Incendiary,
+}
+
+//Picked up from weapon boxes, applied on top of the fire stats of every installed weapon
+#[derive(Clone, Debug, Deserialize)]
+pub struct WeaponModifier {
+    pub display_name: String,
+    pub spawn_chance: u32,
+    pub max_stacks: u32,
+    pub bounces: u32,
+    pub split_shots: u32,
+    pub heat_seeking_agility: f32, //above 0.0 makes shots heat seeking
+    pub ion_malfunction_pct: f32,
+    pub range_pct: f32,
+    pub duration_damage: DurationDamage,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WeaponModifierTable {
+    pub box_chance: u32, //percent of weapon boxes holding a modifier instead of a weapon
+    pub modifiers: HashMap<WeaponModifierNames, WeaponModifier>,
 }
 
 #[derive(Clone)]
@@ -274,6 +356,7 @@ pub struct WeaponStoreResource {
     pub gun_game_order: Vec<WeaponNames>,
     pub gun_game_random_order: Vec<WeaponNames>,
     pub selection_order: Vec<WeaponNames>,
+    pub modifier_table: WeaponModifierTable,
 }
 
 pub fn build_weapon_store(world: &mut World) {
@@ -283,6 +366,7 @@ pub fn build_weapon_store(world: &mut World) {
         gun_game_order: load_ron_asset(&["game", "weapon_gun_game_order.ron"]),
         gun_game_random_order: load_ron_asset(&["game", "weapon_gun_game_order.ron"]),
         selection_order: load_ron_asset(&["game", "weapon_selection_order.ron"]),
+        modifier_table: load_ron_asset(&["game", "weapon_modifiers.ron"]),
     });
 }
 
@@ -303,9 +387,78 @@ pub struct Weapon {
     pub deployed: bool,
     pub deploy_timer: f32,
     pub ammo: Option<u32>,
+    pub heat: f32,
+    pub overheat_timer: f32,
+    pub heat_bar_entity: Option<Entity>,
+    pub magazine: u32,
+    pub reserve_ammo: Option<u32>,
+    pub reload_timer: f32,
+    pub beam: Option<(f32, f32, f32)>, //(x, y, angle) a beam is fired from this frame
+    pub split_shots: u32,              //extra shots fanned out either side of each shot
 }
 
 impl Weapon {
+    //Heat up from a shot, locking the weapon out once it overheats
+    pub fn add_shot_heat(&mut self, dt: f32) {
+        if self.stats.heat_per_shot <= 0.0 {
+            return;
+        }
+
+        //beams fire every frame, so their heat is per second
+        if self.stats.fire_stats.beam_range > 0.0 {
+            self.heat += self.stats.heat_per_shot * dt;
+        } else {
+            self.heat += self.stats.heat_per_shot;
+        }
+
+        if self.heat >= WEAPON_MAX_HEAT {
+            self.heat = WEAPON_MAX_HEAT;
+            self.overheat_timer = self.stats.overheat_lockout;
+        }
+    }
+
+    pub fn cool_down(&mut self, dt: f32) {
+        self.heat = (self.heat - self.stats.heat_dissipation_rate * dt).max(0.0);
+        self.overheat_timer = (self.overheat_timer - dt).max(0.0);
+    }
+
+    //Top the magazine back up from the reserve
+    pub fn finish_reload(&mut self) {
+        let needed = self.stats.magazine_size - self.magazine;
+
+        let loaded = match self.reserve_ammo {
+            Some(reserve_ammo) => {
+                let loaded = needed.min(reserve_ammo);
+                self.reserve_ammo = Some(reserve_ammo - loaded);
+                loaded
+            }
+            None => needed,
+        };
+
+        self.magazine += loaded;
+    }
+
+    pub fn can_reload(&self) -> bool {
+        self.stats.magazine_size > 0
+            && self.magazine < self.stats.magazine_size
+            && self.reload_timer <= 0.0
+            && self
+                .reserve_ammo
+                .map_or(true, |reserve_ammo| reserve_ammo > 0)
+    }
+
+    //Out of shots for good, either no ammo left or a dry magazine with nothing to reload it from
+    pub fn is_out_of_ammo(&self) -> bool {
+        if self.ammo == Some(0) {
+            return true;
+        }
+
+        self.stats.magazine_size > 0
+            && self.magazine == 0
+            && self.reload_timer <= 0.0
+            && !self.can_reload()
+    }
+
     pub fn new(
         name: WeaponNames,
         icon_entity: Entity,
@@ -328,6 +481,14 @@ impl Weapon {
             deployed: false,
             deploy_timer: 0.0,
             ammo: ammo,
+            heat: 0.0,
+            overheat_timer: 0.0,
+            heat_bar_entity: None,
+            magazine: stats.magazine_size,
+            reserve_ammo: stats.reserve_ammo,
+            reload_timer: 0.0,
+            beam: None,
+            split_shots: 0,
         }
     }
 }
@@ -355,34 +516,26 @@ pub struct WeaponNameInstall {
 #[derive(Clone)]
 pub struct WeaponArray {
     pub installed: Vec<WeaponInstall>,
+    pub modifiers: Vec<(WeaponModifierNames, u32)>, //(modifier, stacks)
+    pub modifier_text_entity: Option<Entity>,
 }
 
 impl Component for WeaponArray {
     type Storage = DenseVecStorage<Self>;
 }
 
+//Sustained DPS against bare health, see weapon_balance for shields, armor and time-to-kill
 pub fn calculate_dps(stats: WeaponStats) -> f32 {
-    let base_damage: f32 = stats.fire_stats.damage; //damage per shot
-    let fire_rate: f32; //shots per second
-
-    if stats.burst_shot_limit > 0 {
-        fire_rate = ((stats.burst_shot_limit + 1) as f32)
-            / (stats.cooldown_reset
-                + ((stats.burst_shot_limit + 1) as f32) * stats.burst_cooldown_reset);
-    } else {
-        fire_rate = 1.0 / stats.cooldown_reset
-    }
-
-    //let duration_damage: f32 = stats.duration_damage.damage_per_second * stats.duration_damage.timer;
-
-    let dps = base_damage * fire_rate;
-
-    dps
+    calculate_weapon_dps(&stats).health
 }
 
 pub fn calculate_range(stats: WeaponFireStats) -> f32 {
     let range;
-    if stats.shot_speed <= 0.0 {
+    if let Some(deployable) = stats.deployable {
+        range = deployable.range;
+    } else if stats.beam_range > 0.0 {
+        range = stats.beam_range;
+    } else if stats.shot_speed <= 0.0 {
         range = 0.0;
     } else if stats.shot_life_limit <= 0.0 {
         range = 10000.0;
@@ -422,9 +575,8 @@ impl WeaponFire {
         weapon_fire_type: WeaponFireTypes,
         weapon_angle_offset: f32,
         stats: WeaponFireStats,
+        (width, height): (f32, f32),
     ) -> WeaponFire {
-        let (width, height) = get_weapon_width_height(weapon_fire_type.clone());
-
         WeaponFire {
             weapon_array_id,
             weapon_name,
@@ -444,6 +596,43 @@ impl WeaponFire {
     }
 }
 
+//Only free flying shots can be shot down or deflected, not mines, traps, swords or explosions
+pub fn weapon_fire_is_projectile(weapon_fire: &WeaponFire) -> bool {
+    !weapon_fire.stats.attached
+        && !weapon_fire.stats.trigger_immediately
+        && weapon_fire.stats.shot_speed > 0.0
+}
+
+//Send a shot back off a surface facing (normal_x, normal_y), handing it over to a new owner
+pub fn reflect_weapon_fire(
+    weapon_fire: &mut WeaponFire,
+    owner_player_id: Option<usize>,
+    normal_x: f32,
+    normal_y: f32,
+) {
+    let normal_length = (normal_x.powi(2) + normal_y.powi(2)).sqrt();
+
+    if normal_length < 0.001 {
+        weapon_fire.dx = -weapon_fire.dx;
+        weapon_fire.dy = -weapon_fire.dy;
+    } else {
+        let normal_x = normal_x / normal_length;
+        let normal_y = normal_y / normal_length;
+
+        //only shots heading into the surface are turned around
+        let dot = weapon_fire.dx * normal_x + weapon_fire.dy * normal_y;
+
+        if dot < 0.0 {
+            weapon_fire.dx -= 2.0 * dot * normal_x;
+            weapon_fire.dy -= 2.0 * dot * normal_y;
+        }
+    }
+
+    weapon_fire.owner_player_id = owner_player_id;
+    weapon_fire.shot_life_timer = 0.0;
+    weapon_fire.chain_hit_ids.clear();
+}
+
 pub fn update_weapon_properties(
     weapon_array: &mut WeaponArray,
     weapon_array_id: usize,
@@ -477,6 +666,7 @@ pub fn update_weapon_properties(
 
         let (icon_scale, weapon_sprite) = get_weapon_icon(
             player_id,
+            weapon_name.clone(),
             new_weapon_stats.weapon_fire_type,
             &weapon_fire_resource,
         );
@@ -489,7 +679,8 @@ pub fn update_weapon_properties(
             _ => (0.0),
         };
 
-        let (width, height) = get_weapon_width_height(weapon_fire_type.clone());
+        let (width, height) =
+            weapon_fire_resource.get_weapon_fire_size(weapon_name, weapon_fire_type);
 
         let icon_weapon_transform = UiTransform::new(
             "P1_WeaponIcon".to_string(),
@@ -516,8 +707,37 @@ pub fn update_weapon_properties(
         lazy_update.insert(icon_entity, Transparent);
         lazy_update.insert(icon_entity, Removal::new(0 as u32));
 
+        //heat bar next to the icon, for weapons that can overheat
+        let heat_bar_entity;
+
+        if new_weapon_stats.heat_per_shot > 0.0 {
+            let entity: Entity = entities.create();
+
+            lazy_update.insert(
+                entity,
+                PlayerWeaponIcon::new(player_id_sub, weapon_array_id, weapon_fire_type),
+            );
+            lazy_update.insert(entity, UiImage::SolidColor([1.0, 1.0, 1.0, 1.0]));
+            lazy_update.insert(entity, get_weapon_heat_bar_transform(starting_x, y));
+            lazy_update.insert(entity, Tint(Srgba::new(1.0, 0.6, 0.1, 1.0)));
+            lazy_update.insert(entity, Transparent);
+            lazy_update.insert(entity, Removal::new(0 as u32));
+
+            heat_bar_entity = Some(entity);
+        } else {
+            heat_bar_entity = None;
+        }
+
         //update Weapon
-        let new_weapon = Weapon::new(weapon_name, icon_entity, new_weapon_stats, ammo);
+        let mut new_weapon = Weapon::new(weapon_name, icon_entity, new_weapon_stats.clone(), ammo);
+        new_weapon.heat_bar_entity = heat_bar_entity;
+
+        apply_weapon_modifiers(
+            &mut new_weapon,
+            new_weapon_stats,
+            &weapon_array.modifiers,
+            &weapon_store.modifier_table,
+        );
 
         info!(
             "{:?} {:?} {:?}",
@@ -551,6 +771,159 @@ pub fn update_weapon_properties(
     }
 }
 
+//Stack a modifier onto every installed weapon, false when it is already stacked to its limit
+pub fn add_weapon_modifier(
+    weapon_array: &mut WeaponArray,
+    modifier_name: WeaponModifierNames,
+    weapon_store: &WeaponStoreResource,
+) -> bool {
+    let max_stacks = match weapon_store.modifier_table.modifiers.get(&modifier_name) {
+        Some(modifier) => modifier.max_stacks,
+        None => return false,
+    };
+
+    if let Some((_, stacks)) = weapon_array
+        .modifiers
+        .iter_mut()
+        .find(|(name, _)| *name == modifier_name)
+    {
+        if *stacks >= max_stacks {
+            return false;
+        }
+
+        *stacks += 1;
+    } else if max_stacks > 0 {
+        weapon_array.modifiers.push((modifier_name, 1));
+    } else {
+        return false;
+    }
+
+    refresh_weapon_modifiers(weapon_array, weapon_store);
+
+    true
+}
+
+//Re-apply the modifiers to every installed weapon, such as after they change
+pub fn refresh_weapon_modifiers(
+    weapon_array: &mut WeaponArray,
+    weapon_store: &WeaponStoreResource,
+) {
+    for weapon_install in weapon_array.installed.iter_mut() {
+        let base_stats = build_named_weapon(weapon_install.weapon.name, weapon_store);
+
+        apply_weapon_modifiers(
+            &mut weapon_install.weapon,
+            base_stats,
+            &weapon_array.modifiers,
+            &weapon_store.modifier_table,
+        );
+    }
+}
+
+//Rebuild a weapon's stats from its base stats with the stacked modifiers on top
+pub fn apply_weapon_modifiers(
+    weapon: &mut Weapon,
+    base_stats: WeaponStats,
+    modifiers: &Vec<(WeaponModifierNames, u32)>,
+    modifier_table: &WeaponModifierTable,
+) {
+    let mut stats = base_stats;
+    let mut split_shots = 0;
+
+    let is_beam = stats.fire_stats.beam_range > 0.0;
+
+    //mines, traps and swords stay put, so moving shot modifiers do nothing for them
+    let is_moving_shot =
+        !is_beam && stats.fire_stats.shot_speed > 0.0 && !stats.fire_stats.attached;
+
+    for (modifier_name, stacks) in modifiers.iter() {
+        if let Some(modifier) = modifier_table.modifiers.get(modifier_name) {
+            let fire_stats = &mut stats.fire_stats;
+            let stacks_pct = (*stacks as f32) / 100.0;
+
+            if is_moving_shot || is_beam {
+                fire_stats.bounces += modifier.bounces * stacks;
+            }
+
+            if is_moving_shot {
+                split_shots += modifier.split_shots * stacks;
+
+                if modifier.heat_seeking_agility > 0.0 {
+                    fire_stats.heat_seeking = true;
+                    fire_stats.heat_seeking_agility +=
+                        modifier.heat_seeking_agility * (*stacks as f32);
+                }
+
+                if fire_stats.shot_life_limit > 0.0 {
+                    fire_stats.shot_life_limit *= 1.0 + modifier.range_pct * stacks_pct;
+                }
+            } else if is_beam {
+                fire_stats.beam_range *= 1.0 + modifier.range_pct * stacks_pct;
+            }
+
+            fire_stats.ion_malfunction_pct = (fire_stats.ion_malfunction_pct
+                + modifier.ion_malfunction_pct * (*stacks as f32))
+                .min(100.0);
+
+            if modifier.duration_damage.timer > 0.0 {
+                let duration_damage = &mut fire_stats.duration_damage;
+
+                //weapons without their own damage over time take on the modifier's damage split
+                if duration_damage.damage_per_second <= 0.0 {
+                    duration_damage.shield_damage_pct = modifier.duration_damage.shield_damage_pct;
+                    duration_damage.armor_damage_pct = modifier.duration_damage.armor_damage_pct;
+                    duration_damage.piercing_damage_pct =
+                        modifier.duration_damage.piercing_damage_pct;
+                    duration_damage.health_damage_pct = modifier.duration_damage.health_damage_pct;
+                }
+
+                duration_damage.timer = duration_damage.timer.max(modifier.duration_damage.timer);
+                duration_damage.damage_per_second +=
+                    modifier.duration_damage.damage_per_second * (*stacks as f32);
+            }
+        }
+    }
+
+    weapon.dps_calc = calculate_dps(stats.clone());
+    weapon.range_calc = calculate_range(stats.fire_stats.clone());
+    weapon.stats = stats;
+    weapon.split_shots = split_shots;
+}
+
+//Short HUD line listing the stacked modifiers
+pub fn get_weapon_modifiers_text(
+    modifiers: &Vec<(WeaponModifierNames, u32)>,
+    modifier_table: &WeaponModifierTable,
+) -> String {
+    let mut modifier_texts: Vec<String> = Vec::new();
+
+    for (modifier_name, stacks) in modifiers.iter() {
+        if let Some(modifier) = modifier_table.modifiers.get(modifier_name) {
+            if *stacks > 1 {
+                modifier_texts.push(format!("{} x{}", modifier.display_name, stacks));
+            } else {
+                modifier_texts.push(modifier.display_name.clone());
+            }
+        }
+    }
+
+    modifier_texts.join(" ")
+}
+
+//Thin bar just right of a weapon icon, filled from the bottom as the weapon heats up
+pub fn get_weapon_heat_bar_transform(icon_x: f32, icon_y: f32) -> UiTransform {
+    UiTransform::new(
+        "PWeaponHeat".to_string(),
+        Anchor::BottomMiddle,
+        Anchor::BottomMiddle,
+        icon_x + 12.0,
+        icon_y,
+        0.3,
+        WEAPON_HEAT_BAR_WIDTH,
+        0.0,
+    )
+}
+
 pub fn build_named_weapon(
     weapon_name: WeaponNames,
     weapon_store: &WeaponStoreResource,
@@ -570,6 +943,12 @@ pub fn build_named_weapon(
             charge_timer_decrease: 0.0,
             charge_timer_decrease_min: 0.0,
             spin_up_timer_reset: 0.0,
+            heat_per_shot: 0.0,
+            heat_dissipation_rate: 0.0,
+            overheat_lockout: 0.0,
+            magazine_size: 0,
+            reload_time: 0.0,
+            reserve_ammo: None,
             burst_shot_limit: 0,
             burst_cooldown_reset: 0.0,
             weight: 0.0,
@@ -585,32 +964,9 @@ pub fn build_named_weapon_from_world(weapon_name: WeaponNames, world: &mut World
     weapon_stats
 }
 
-pub fn get_weapon_width_height(weapon_fire_type: WeaponFireTypes) -> (f32, f32) {
-    let (width, height) = match weapon_fire_type {
-        WeaponFireTypes::LaserDouble => (3.0, 6.0),
-        WeaponFireTypes::LaserBeam => (1.0, 12.0),
-        WeaponFireTypes::LaserPulse => (2.0, 5.0),
-        WeaponFireTypes::ProjectileMedium => (1.0, 4.0),
-        WeaponFireTypes::ProjectileSmall => (1.0, 2.0),
-        WeaponFireTypes::ProjectileLarge => (3.0, 3.0),
-        WeaponFireTypes::Missile => (5.0, 6.0),
-        WeaponFireTypes::Rockets => (5.0, 4.0),
-        WeaponFireTypes::Mine => (4.0, 4.0),
-        WeaponFireTypes::Grenade => (4.0, 4.0),
-        WeaponFireTypes::Trap => (2.0, 4.0),
-        WeaponFireTypes::LaserSword => (3.0, 15.0),
-        WeaponFireTypes::Flame => (6.0, 4.0),
-        WeaponFireTypes::Ion => (5.0, 5.0),
-        WeaponFireTypes::BioSpike => (3.0, 9.0),
-        WeaponFireTypes::LightBolt => (3.0, 7.0),
-        WeaponFireTypes::SlimeBall => (6.0, 5.0),
-    };
-
-    (width, height)
-}
-
 pub fn get_weapon_icon(
     player_id: Option<usize>,
+    weapon_name: WeaponNames,
     weapon_fire_type: WeaponFireTypes,
     weapon_fire_resource: &WeaponFireResource,
 ) -> (f32, SpriteRender) {
@@ -621,71 +977,239 @@ pub fn get_weapon_icon(
         player_id_sub = player_id.unwrap();
     }
 
-    let (icon_scale, mut weapon_sprite) = match weapon_fire_type {
-        WeaponFireTypes::LaserDouble => {
-            (1.5, weapon_fire_resource.laser_double_sprite_render.clone())
-        }
-        WeaponFireTypes::LaserBeam => (0.5, weapon_fire_resource.laser_beam_sprite_render.clone()),
-        WeaponFireTypes::LaserPulse => {
-            (1.5, weapon_fire_resource.laser_burst_sprite_render.clone())
-        }
-        WeaponFireTypes::ProjectileMedium => {
-            (1.5, weapon_fire_resource.projectile_burst_render.clone())
+    let icon_scale;
+    let sprite_number;
+    if let Some(weapon_fire_visual) =
+        weapon_fire_resource.get_weapon_fire_visual(weapon_name, weapon_fire_type)
+    {
+        icon_scale = weapon_fire_visual.icon_scale;
+
+        //Player colored weapons
+        if let Some(player_sprite_number) =
+            weapon_fire_visual.player_sprite_numbers.get(player_id_sub)
+        {
+            sprite_number = *player_sprite_number;
+        } else {
+            sprite_number = weapon_fire_visual.sprite_number;
         }
-        WeaponFireTypes::ProjectileSmall => {
-            (1.5, weapon_fire_resource.projectile_rapid_render.clone())
+    } else {
+        //no visual defined in weapon_fire_visuals.ron, fall back to the basic laser
+        icon_scale = 1.0;
+        sprite_number = 4;
+    }
+
+    (
+        icon_scale * 3.0,
+        weapon_fire_resource.get_sprite_render(sprite_number),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::{Builder, WorldExt};
+
+    fn test_weapon(magazine_size: u32, reserve_ammo: Option<u32>) -> Weapon {
+        let mut world = World::new();
+        let icon_entity = world.create_entity().build();
+
+        let stats = WeaponStats {
+            display_name: "Test Gun".to_string(),
+            weapon_fire_type: WeaponFireTypes::ProjectileMedium,
+            tracking_angle: 0.0,
+            tracking_range: 0.0,
+            spread_angle: 0.0,
+            cooldown_reset: 0.5,
+            burst_shot_limit: 0,
+            burst_cooldown_reset: 0.0,
+            charge_timer_reset: 0.0,
+            charge_timer_decrease: 0.0,
+            charge_timer_decrease_min: 0.0,
+            spin_up_timer_reset: 0.0,
+            heat_per_shot: 0.0,
+            heat_dissipation_rate: 0.0,
+            overheat_lockout: 0.0,
+            magazine_size,
+            reload_time: 1.0,
+            reserve_ammo,
+            weight: 0.0,
+            fire_stats: WeaponFireStats::default(),
+        };
+
+        Weapon::new(WeaponNames::LaserDoubleGimballed, icon_entity, stats, None)
+    }
+
+    fn test_modifier(split_shots: u32, range_pct: f32) -> WeaponModifier {
+        WeaponModifier {
+            display_name: "Test Mod".to_string(),
+            spawn_chance: 1,
+            max_stacks: 3,
+            bounces: 0,
+            split_shots,
+            heat_seeking_agility: 0.0,
+            ion_malfunction_pct: 0.0,
+            range_pct,
+            duration_damage: DurationDamage::default(),
         }
-        WeaponFireTypes::ProjectileLarge => (
-            1.5,
-            weapon_fire_resource.projectile_cannon_sprite_render.clone(),
-        ),
-        WeaponFireTypes::Missile => (1.0, weapon_fire_resource.missile_sprite_render.clone()),
-        WeaponFireTypes::Rockets => (1.0, weapon_fire_resource.rockets_sprite_render.clone()),
-        WeaponFireTypes::Mine => (1.0, weapon_fire_resource.mine_p1_sprite_render.clone()),
-        WeaponFireTypes::Trap => (1.5, weapon_fire_resource.trap_p1_sprite_render.clone()),
-        WeaponFireTypes::LaserSword => {
-            (0.5, weapon_fire_resource.laser_sword_sprite_render.clone())
+    }
+
+    fn test_modifier_table() -> WeaponModifierTable {
+        let mut modifiers = HashMap::new();
+        modifiers.insert(WeaponModifierNames::SplitShot, test_modifier(1, 0.0));
+        modifiers.insert(WeaponModifierNames::ExtendedRange, test_modifier(0, 50.0));
+
+        WeaponModifierTable {
+            box_chance: 0,
+            modifiers,
         }
-        WeaponFireTypes::Flame => (1.0, weapon_fire_resource.flame_sprite_render.clone()),
-        WeaponFireTypes::Grenade => (1.0, weapon_fire_resource.grenade_sprite_render.clone()),
-        WeaponFireTypes::Ion => (1.0, weapon_fire_resource.ion_sprite_render.clone()),
-        WeaponFireTypes::BioSpike => (0.75, weapon_fire_resource.bio_spike_sprite_render.clone()),
-        WeaponFireTypes::LightBolt => (1.0, weapon_fire_resource.light_bolt_sprite_render.clone()),
-        WeaponFireTypes::SlimeBall => (1.0, weapon_fire_resource.slime_ball_sprite_render.clone()),
-    };
+    }
 
-    //Player colored weapons
-    if weapon_fire_type == WeaponFireTypes::Mine {
-        weapon_sprite = get_mine_sprite(player_id_sub, weapon_fire_resource);
-    } else if weapon_fire_type == WeaponFireTypes::Trap {
-        weapon_sprite = get_trap_sprite(player_id_sub, weapon_fire_resource);
+    #[test]
+    fn modifiers_stack_onto_moving_shots() {
+        let mut weapon = test_weapon(0, None);
+        let mut base_stats = weapon.stats.clone();
+        base_stats.fire_stats.shot_speed = 200.0;
+        base_stats.fire_stats.shot_life_limit = 1.0;
+
+        let modifiers = vec![
+            (WeaponModifierNames::SplitShot, 2),
+            (WeaponModifierNames::ExtendedRange, 1),
+        ];
+
+        apply_weapon_modifiers(
+            &mut weapon,
+            base_stats.clone(),
+            &modifiers,
+            &test_modifier_table(),
+        );
+        assert_eq!(weapon.split_shots, 2);
+        assert_eq!(weapon.stats.fire_stats.shot_life_limit, 1.5);
+
+        //applying again starts over from the base stats rather than stacking twice
+        apply_weapon_modifiers(&mut weapon, base_stats, &modifiers, &test_modifier_table());
+        assert_eq!(weapon.split_shots, 2);
+        assert_eq!(weapon.stats.fire_stats.shot_life_limit, 1.5);
     }
 
-    (icon_scale * 3.0, weapon_sprite)
-}
+    #[test]
+    fn beams_only_take_the_modifiers_that_fit_them() {
+        let mut weapon = test_weapon(0, None);
+        let mut base_stats = weapon.stats.clone();
+        base_stats.fire_stats.beam_range = 100.0;
 
-pub fn get_mine_sprite(
-    player_id: usize,
-    weapon_fire_resource: &WeaponFireResource,
-) -> SpriteRender {
-    match player_id {
-        0 => weapon_fire_resource.mine_p1_sprite_render.clone(),
-        1 => weapon_fire_resource.mine_p2_sprite_render.clone(),
-        2 => weapon_fire_resource.mine_p3_sprite_render.clone(),
-        3 => weapon_fire_resource.mine_p4_sprite_render.clone(),
-        _ => weapon_fire_resource.mine_p1_sprite_render.clone(),
+        let modifiers = vec![
+            (WeaponModifierNames::SplitShot, 1),
+            (WeaponModifierNames::ExtendedRange, 1),
+        ];
+
+        apply_weapon_modifiers(&mut weapon, base_stats, &modifiers, &test_modifier_table());
+        assert_eq!(weapon.split_shots, 0);
+        assert_eq!(weapon.stats.fire_stats.beam_range, 150.0);
     }
-}
 
-pub fn get_trap_sprite(
-    player_id: usize,
-    weapon_fire_resource: &WeaponFireResource,
-) -> SpriteRender {
-    match player_id {
-        0 => weapon_fire_resource.trap_p1_sprite_render.clone(),
-        1 => weapon_fire_resource.trap_p2_sprite_render.clone(),
-        2 => weapon_fire_resource.trap_p3_sprite_render.clone(),
-        3 => weapon_fire_resource.trap_p4_sprite_render.clone(),
-        _ => weapon_fire_resource.trap_p1_sprite_render.clone(),
+    #[test]
+    fn overheating_locks_the_weapon_out_until_it_cools() {
+        let mut weapon = test_weapon(0, None);
+        weapon.stats.heat_per_shot = 40.0;
+        weapon.stats.heat_dissipation_rate = 20.0;
+        weapon.stats.overheat_lockout = 2.0;
+
+        weapon.add_shot_heat(0.1);
+        weapon.add_shot_heat(0.1);
+        assert_eq!(weapon.heat, 80.0);
+        assert_eq!(weapon.overheat_timer, 0.0);
+
+        weapon.add_shot_heat(0.1);
+        assert_eq!(weapon.heat, WEAPON_MAX_HEAT);
+        assert_eq!(weapon.overheat_timer, 2.0);
+
+        weapon.cool_down(1.5);
+        assert_eq!(weapon.heat, WEAPON_MAX_HEAT - 30.0);
+        assert_eq!(weapon.overheat_timer, 0.5);
+
+        weapon.cool_down(10.0);
+        assert_eq!(weapon.heat, 0.0);
+        assert_eq!(weapon.overheat_timer, 0.0);
+    }
+
+    #[test]
+    fn beams_heat_up_per_second() {
+        let mut weapon = test_weapon(0, None);
+        weapon.stats.heat_per_shot = 40.0;
+        weapon.stats.fire_stats.beam_range = 100.0;
+
+        weapon.add_shot_heat(0.5);
+        assert_eq!(weapon.heat, 20.0);
+    }
+
+    #[test]
+    fn reloading_tops_up_the_magazine_from_the_reserve() {
+        let mut weapon = test_weapon(10, Some(14));
+        weapon.magazine = 3;
+
+        assert!(weapon.can_reload());
+        weapon.finish_reload();
+        assert_eq!(weapon.magazine, 10);
+        assert_eq!(weapon.reserve_ammo, Some(7));
+
+        //a full magazine has nothing to reload
+        assert!(!weapon.can_reload());
+
+        //the last few rounds only part fill it
+        weapon.magazine = 0;
+        weapon.finish_reload();
+        assert_eq!(weapon.magazine, 7);
+        assert_eq!(weapon.reserve_ammo, Some(0));
+        assert!(!weapon.can_reload());
+    }
+
+    #[test]
+    fn reloading_from_an_endless_reserve() {
+        let mut weapon = test_weapon(10, None);
+        weapon.magazine = 0;
+
+        assert!(weapon.can_reload());
+        weapon.finish_reload();
+        assert_eq!(weapon.magazine, 10);
+        assert_eq!(weapon.reserve_ammo, None);
+    }
+
+    #[test]
+    fn no_reload_while_reloading_or_without_a_magazine() {
+        let mut weapon = test_weapon(10, None);
+        weapon.magazine = 0;
+        weapon.reload_timer = 0.5;
+        assert!(!weapon.can_reload());
+
+        let weapon = test_weapon(0, None);
+        assert!(!weapon.can_reload());
+    }
+
+    #[test]
+    fn dry_magazine_without_reserve_is_out_of_ammo() {
+        let mut weapon = test_weapon(10, Some(0));
+        assert!(!weapon.is_out_of_ammo());
+
+        weapon.magazine = 0;
+        assert!(weapon.is_out_of_ammo());
+
+        //a reserve to reload from keeps it in the fight
+        weapon.reserve_ammo = Some(5);
+        assert!(!weapon.is_out_of_ammo());
+
+        //so does a reload that is already under way
+        weapon.reserve_ammo = Some(0);
+        weapon.reload_timer = 0.5;
+        assert!(!weapon.is_out_of_ammo());
+    }
+
+    #[test]
+    fn limited_ammo_and_endless_reserve() {
+        let mut weapon = test_weapon(10, None);
+        weapon.magazine = 0;
+        assert!(!weapon.is_out_of_ammo());
+
+        weapon.ammo = Some(0);
+        assert!(weapon.is_out_of_ammo());
     }
 }
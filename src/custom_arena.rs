@@ -1,19 +1,126 @@
 use amethyst::{
-    ecs::prelude::Entity,
+    core::{math::Point3, transform::Transform},
+    ecs::prelude::{Entity, World},
     input::{is_close_requested, is_key_down},
     prelude::*,
-    ui::{UiCreator, UiEvent, UiEventType, UiFinder},
+    renderer::{
+        debug_drawing::{DebugLines, DebugLinesParams},
+        palette::Srgba,
+        Camera,
+    },
+    ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText},
+    utils::application_dir,
     winit::VirtualKeyCode,
 };
 
+use std::f32::consts::PI;
+use std::fs::File;
+use std::path::PathBuf;
+
+use crate::arena_validation::validate_arena;
+use crate::components::{
+    arena_properties_to_ron, ArenaCircle, ArenaFloor, ArenaKingHill, ArenaNames, ArenaProperties,
+    ArenaRaceCheckpoint, ArenaRectangle, ArenaStoreResource, ObstacleType, PlayerSpawnPoint,
+    WeaponBoxSpawner, ZoneEffects,
+};
 use crate::menu::MainMenu;
+use crate::rally::GameplayState;
+use crate::resources::{
+    build_arena_nav_mesh, get_arena_nav_mesh_obstacles, ArenaNavMesh, GameModeSetup, GameModes,
+};
 
 const BUTTON_BACK_TO_MENU: &str = "back_to_menu";
+const BUTTON_SAVE_ARENA: &str = "save_arena";
+const BUTTON_LOAD_ARENA: &str = "load_arena";
+const BUTTON_TEST_PLAY: &str = "test_play";
+const TEXT_EDITOR_STATUS: &str = "editor_status";
+
+const CUSTOM_ARENA_FILE: &str = "custom_arena.ron";
+
+const GRID_SIZE: f32 = 10.0;
+const ROTATION_STEP: f32 = 15.0; //degrees
+const RESIZE_STEP: f32 = 10.0;
+const MIN_ELEMENT_SIZE: f32 = 5.0;
+const SELECT_RANGE: f32 = 40.0;
+const DESTRUCTIBLE_WALL_HEALTH: f32 = 150.0;
+
+const EDITOR_LINE_Z: f32 = 0.5;
+const EDITOR_VIEW_SCALE: f32 = 1.4; //leave room around the arena for the editor UI
+
+//speed boost, damage, and healing zones, matching the shipped arenas
+const ZONE_EFFECT_PRESETS: [ZoneEffects; 3] = [
+    ZoneEffects {
+        accel_rate: 200.0,
+        damage_rate: 0.0,
+    },
+    ZoneEffects {
+        accel_rate: -20.0,
+        damage_rate: 20.0,
+    },
+    ZoneEffects {
+        accel_rate: 0.0,
+        damage_rate: -20.0,
+    },
+];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum EditorTool {
+    Wall,
+    CircleWall,
+    Zone,
+    Hill,
+    Checkpoint,
+    WeaponSpawner,
+    PlayerSpawn,
+}
+
+impl Default for EditorTool {
+    fn default() -> Self {
+        EditorTool::Wall
+    }
+}
+
+impl EditorTool {
+    fn next(self) -> EditorTool {
+        match self {
+            EditorTool::Wall => EditorTool::CircleWall,
+            EditorTool::CircleWall => EditorTool::Zone,
+            EditorTool::Zone => EditorTool::Hill,
+            EditorTool::Hill => EditorTool::Checkpoint,
+            EditorTool::Checkpoint => EditorTool::WeaponSpawner,
+            EditorTool::WeaponSpawner => EditorTool::PlayerSpawn,
+            EditorTool::PlayerSpawn => EditorTool::Wall,
+        }
+    }
+}
+
+//Index into the matching ArenaProperties list
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum EditorSelection {
+    Rectangle(usize),
+    Circle(usize),
+    Hill(usize),
+    Checkpoint(usize),
+    WeaponSpawner(usize),
+    PlayerSpawn(usize),
+}
 
-#[derive(Default, Debug)]
+#[derive(Default)]
 pub struct CustomArenaMenu {
     ui_root: Option<Entity>,
+    camera: Option<Entity>,
     button_back_to_menu: Option<Entity>,
+    button_save_arena: Option<Entity>,
+    button_load_arena: Option<Entity>,
+    button_test_play: Option<Entity>,
+    text_editor_status: Option<Entity>,
+    arena_properties: ArenaProperties,
+    cursor: (f32, f32),
+    tool: EditorTool,
+    selection: Option<EditorSelection>,
+    show_nav_mesh: bool,
+    nav_mesh_preview: Option<ArenaNavMesh>,
+    message: String,
 }
 
 impl SimpleState for CustomArenaMenu {
@@ -21,6 +128,46 @@ impl SimpleState for CustomArenaMenu {
         // create UI from prefab and save the reference.
         let world = data.world;
 
+        //start editing from the currently selected arena
+        let arena_name;
+        {
+            let fetched_game_mode_setup = world.try_fetch::<GameModeSetup>();
+            if let Some(game_mode_setup) = fetched_game_mode_setup {
+                arena_name = game_mode_setup.arena_name.clone();
+            } else {
+                arena_name = ArenaNames::OpenEmptyMap;
+            }
+        }
+
+        {
+            let fetched_arena_store = world.try_fetch::<ArenaStoreResource>();
+            if let Some(arena_store) = fetched_arena_store {
+                self.arena_properties = match arena_store.properties.get(&arena_name) {
+                    Some(arena_props_get) => (*arena_props_get).clone(),
+                    _ => empty_arena(),
+                };
+            } else {
+                self.arena_properties = empty_arena();
+            }
+        }
+
+        self.cursor = (
+            snap_to_grid(self.arena_properties.width / 2.0),
+            snap_to_grid(self.arena_properties.height / 2.0),
+        );
+        self.tool = EditorTool::Wall;
+        self.selection = None;
+        self.show_nav_mesh = true;
+        self.nav_mesh_preview = None;
+        self.message = format!("Editing {:?}", arena_name);
+
+        if !world.has_value::<DebugLines>() {
+            world.insert(DebugLines::new());
+            world.insert(DebugLinesParams { line_width: 2.0 });
+        }
+
+        self.camera = Some(initialize_editor_camera(world, &self.arena_properties));
+
         self.ui_root = Some(
             world.exec(|mut creator: UiCreator<'_>| creator.create("ui/custom_arena.ron", ())),
         );
@@ -30,18 +177,60 @@ impl SimpleState for CustomArenaMenu {
         // only search for buttons if they have not been found yet
         let StateData { world, .. } = state_data;
 
-        if self.button_back_to_menu.is_none() {
+        if self.button_back_to_menu.is_none()
+            || self.button_save_arena.is_none()
+            || self.button_load_arena.is_none()
+            || self.button_test_play.is_none()
+            || self.text_editor_status.is_none()
+        {
             world.exec(|ui_finder: UiFinder<'_>| {
                 self.button_back_to_menu = ui_finder.find(BUTTON_BACK_TO_MENU);
+                self.button_save_arena = ui_finder.find(BUTTON_SAVE_ARENA);
+                self.button_load_arena = ui_finder.find(BUTTON_LOAD_ARENA);
+                self.button_test_play = ui_finder.find(BUTTON_TEST_PLAY);
+                self.text_editor_status = ui_finder.find(TEXT_EDITOR_STATUS);
             });
         }
 
+        {
+            let mut ui_text = world.write_storage::<UiText>();
+
+            if let Some(status) = self
+                .text_editor_status
+                .and_then(|entity| ui_text.get_mut(entity))
+            {
+                status.text = format!(
+                    "Tool: {:?}   Selected: {}   Cursor: ({}, {})   {}",
+                    self.tool,
+                    match self.selection {
+                        Some(selection) => format!("{:?}", selection),
+                        None => "None".to_string(),
+                    },
+                    self.cursor.0,
+                    self.cursor.1,
+                    self.message,
+                );
+            }
+        }
+
+        if self.show_nav_mesh && self.nav_mesh_preview.is_none() {
+            self.nav_mesh_preview = Some(build_arena_nav_mesh(
+                self.arena_properties.width,
+                self.arena_properties.height,
+                &self.arena_properties.floor,
+                &get_arena_nav_mesh_obstacles(&self.arena_properties),
+            ));
+        }
+
+        let mut debug_lines_resource = world.write_resource::<DebugLines>();
+        self.draw_arena(&mut debug_lines_resource);
+
         Trans::None
     }
 
     fn handle_event(
         &mut self,
-        _data: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
         match event {
@@ -50,8 +239,67 @@ impl SimpleState for CustomArenaMenu {
                     log::info!("[Trans::Quit] Quitting Application!");
                     Trans::Quit
                 } else if is_key_down(&event, VirtualKeyCode::Escape) {
-                    log::info!("[Trans::Switch] Switching back to MainMenu!");
-                    Trans::Switch(Box::new(MainMenu::default()))
+                    if self.selection.is_some() {
+                        self.selection = None;
+                        Trans::None
+                    } else {
+                        log::info!("[Trans::Switch] Switching back to MainMenu!");
+                        Trans::Switch(Box::new(MainMenu::default()))
+                    }
+                } else if is_key_down(&event, VirtualKeyCode::Up) {
+                    self.move_cursor(0.0, GRID_SIZE);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Down) {
+                    self.move_cursor(0.0, -GRID_SIZE);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Left) {
+                    self.move_cursor(-GRID_SIZE, 0.0);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Right) {
+                    self.move_cursor(GRID_SIZE, 0.0);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Tab) {
+                    self.tool = self.tool.next();
+                    self.selection = None;
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Space) {
+                    self.place_element();
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Return) {
+                    self.select_nearest();
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Q) {
+                    self.rotate_selected(ROTATION_STEP);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::E) {
+                    self.rotate_selected(-ROTATION_STEP);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Equals) {
+                    self.resize_selected(RESIZE_STEP, 0.0);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Minus) {
+                    self.resize_selected(-RESIZE_STEP, 0.0);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::RBracket) {
+                    self.resize_selected(0.0, RESIZE_STEP);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::LBracket) {
+                    self.resize_selected(0.0, -RESIZE_STEP);
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Delete)
+                    || is_key_down(&event, VirtualKeyCode::Back)
+                {
+                    self.delete_selected();
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::Z) {
+                    self.cycle_zone_effect();
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::H) {
+                    self.toggle_destructible();
+                    Trans::None
+                } else if is_key_down(&event, VirtualKeyCode::N) {
+                    self.show_nav_mesh = !self.show_nav_mesh;
+                    Trans::None
                 } else {
                     Trans::None
                 }
@@ -63,6 +311,16 @@ impl SimpleState for CustomArenaMenu {
                 if Some(target) == self.button_back_to_menu {
                     log::info!("[Trans::Switch] Switching back to MainMenu!");
                     return Trans::Switch(Box::new(MainMenu::default()));
+                } else if Some(target) == self.button_save_arena {
+                    self.save_arena();
+                } else if Some(target) == self.button_load_arena {
+                    self.load_arena(data.world);
+                } else if Some(target) == self.button_test_play {
+                    self.report_validation();
+                    install_custom_arena(data.world, &self.arena_properties);
+
+                    log::info!("[Trans::Switch] Switching to GameplayState!");
+                    return Trans::Switch(Box::new(GameplayState::default()));
                 }
 
                 Trans::None
@@ -79,8 +337,665 @@ impl SimpleState for CustomArenaMenu {
                 .expect("Failed to remove CustomArenaMenu");
         }
 
+        if let Some(camera) = self.camera {
+            data.world
+                .delete_entity(camera)
+                .expect("Failed to remove editor camera");
+        }
+
         self.ui_root = None;
+        self.camera = None;
 
         self.button_back_to_menu = None;
+        self.button_save_arena = None;
+        self.button_load_arena = None;
+        self.button_test_play = None;
+        self.text_editor_status = None;
+    }
+}
+
+impl CustomArenaMenu {
+    fn move_cursor(&mut self, dx: f32, dy: f32) {
+        self.cursor.0 = (self.cursor.0 + dx)
+            .max(0.0)
+            .min(snap_to_grid(self.arena_properties.width));
+        self.cursor.1 = (self.cursor.1 + dy)
+            .max(0.0)
+            .min(snap_to_grid(self.arena_properties.height));
+
+        //selected elements are dragged along with the cursor
+        let (x, y) = self.cursor;
+        let arena = &mut self.arena_properties;
+
+        match self.selection {
+            Some(EditorSelection::Rectangle(idx)) => {
+                arena.arena_rectangles[idx].x = x;
+                arena.arena_rectangles[idx].y = y;
+            }
+            Some(EditorSelection::Circle(idx)) => {
+                arena.arena_circles[idx].x = x;
+                arena.arena_circles[idx].y = y;
+            }
+            Some(EditorSelection::Hill(idx)) => {
+                arena.king_hills[idx].x = x;
+                arena.king_hills[idx].y = y;
+            }
+            Some(EditorSelection::Checkpoint(idx)) => {
+                arena.race_checkpoints[idx].x = x;
+                arena.race_checkpoints[idx].y = y;
+            }
+            Some(EditorSelection::WeaponSpawner(idx)) => {
+                arena.weapon_spawners[idx].x = x;
+                arena.weapon_spawners[idx].y = y;
+            }
+            Some(EditorSelection::PlayerSpawn(idx)) => {
+                arena.player_spawn_points[idx].x = x;
+                arena.player_spawn_points[idx].y = y;
+            }
+            None => {}
+        }
+
+        self.element_changed();
+    }
+
+    fn place_element(&mut self) {
+        let (x, y) = self.cursor;
+        let arena = &mut self.arena_properties;
+
+        self.selection = Some(match self.tool {
+            EditorTool::Wall | EditorTool::Zone => {
+                let is_zone = self.tool == EditorTool::Zone;
+
+                arena.arena_rectangles.push(ArenaRectangle {
+                    obstacle_type: if is_zone {
+                        ObstacleType::Zone
+                    } else {
+                        ObstacleType::Wall
+                    },
+                    x,
+                    y,
+                    width: if is_zone { 60.0 } else { 40.0 },
+                    height: if is_zone { 60.0 } else { 20.0 },
+                    rotation: 0.0,
+                    effects: if is_zone {
+                        Some(ZONE_EFFECT_PRESETS[0])
+                    } else {
+                        None
+                    },
+                    health: None,
+                });
+                EditorSelection::Rectangle(arena.arena_rectangles.len() - 1)
+            }
+            EditorTool::CircleWall => {
+                arena.arena_circles.push(ArenaCircle {
+                    obstacle_type: ObstacleType::Wall,
+                    x,
+                    y,
+                    radius: 15.0,
+                    effect: None,
+                    health: None,
+                });
+                EditorSelection::Circle(arena.arena_circles.len() - 1)
+            }
+            EditorTool::Hill => {
+                arena.king_hills.push(ArenaKingHill { x, y, radius: 30.0 });
+                EditorSelection::Hill(arena.king_hills.len() - 1)
+            }
+            EditorTool::Checkpoint => {
+                //the first checkpoint placed is the finish line
+                arena.race_checkpoints.push(ArenaRaceCheckpoint {
+                    x,
+                    y,
+                    length: 60.0,
+                    rotation: 0.0,
+                });
+                EditorSelection::Checkpoint(arena.race_checkpoints.len() - 1)
+            }
+            EditorTool::WeaponSpawner => {
+                arena.weapon_spawners.push(WeaponBoxSpawner {
+                    x,
+                    y,
+                    weapon_names: None,
+                    first_spawn_time: None,
+                    spawn_time: None,
+                    ammo: None,
+                });
+                EditorSelection::WeaponSpawner(arena.weapon_spawners.len() - 1)
+            }
+            EditorTool::PlayerSpawn => {
+                arena.player_spawn_points.push(PlayerSpawnPoint {
+                    x,
+                    y,
+                    rotation: 0.0,
+                });
+                EditorSelection::PlayerSpawn(arena.player_spawn_points.len() - 1)
+            }
+        });
+
+        self.element_changed();
+    }
+
+    fn select_nearest(&mut self) {
+        if self.selection.is_some() {
+            self.selection = None;
+            return;
+        }
+
+        let (x, y) = self.cursor;
+        let arena = &self.arena_properties;
+
+        let mut candidates: Vec<(EditorSelection, f32, f32)> = Vec::new();
+
+        for (idx, rect) in arena.arena_rectangles.iter().enumerate() {
+            candidates.push((EditorSelection::Rectangle(idx), rect.x, rect.y));
+        }
+        for (idx, circle) in arena.arena_circles.iter().enumerate() {
+            candidates.push((EditorSelection::Circle(idx), circle.x, circle.y));
+        }
+        for (idx, hill) in arena.king_hills.iter().enumerate() {
+            candidates.push((EditorSelection::Hill(idx), hill.x, hill.y));
+        }
+        for (idx, checkpoint) in arena.race_checkpoints.iter().enumerate() {
+            candidates.push((EditorSelection::Checkpoint(idx), checkpoint.x, checkpoint.y));
+        }
+        for (idx, spawner) in arena.weapon_spawners.iter().enumerate() {
+            candidates.push((EditorSelection::WeaponSpawner(idx), spawner.x, spawner.y));
+        }
+        for (idx, spawn) in arena.player_spawn_points.iter().enumerate() {
+            candidates.push((EditorSelection::PlayerSpawn(idx), spawn.x, spawn.y));
+        }
+
+        let mut closest_dist = SELECT_RANGE;
+        for (selection, element_x, element_y) in candidates.iter() {
+            let dist = (element_x - x).hypot(element_y - y);
+
+            if dist < closest_dist {
+                closest_dist = dist;
+                self.selection = Some(*selection);
+            }
+        }
+
+        //snap the cursor onto the selected element so that moving does not jump it
+        if let Some(selection) = self.selection {
+            for (candidate, element_x, element_y) in candidates.iter() {
+                if *candidate == selection {
+                    self.cursor = (*element_x, *element_y);
+                }
+            }
+        }
+    }
+
+    fn rotate_selected(&mut self, degrees: f32) {
+        let arena = &mut self.arena_properties;
+
+        match self.selection {
+            Some(EditorSelection::Rectangle(idx)) => {
+                arena.arena_rectangles[idx].rotation =
+                    (arena.arena_rectangles[idx].rotation + degrees) % 360.0;
+            }
+            Some(EditorSelection::Checkpoint(idx)) => {
+                arena.race_checkpoints[idx].rotation =
+                    (arena.race_checkpoints[idx].rotation + degrees) % 360.0;
+            }
+            Some(EditorSelection::PlayerSpawn(idx)) => {
+                arena.player_spawn_points[idx].rotation =
+                    (arena.player_spawn_points[idx].rotation + degrees) % 360.0;
+            }
+            _ => {}
+        }
+
+        self.element_changed();
+    }
+
+    fn resize_selected(&mut self, d_width: f32, d_height: f32) {
+        let arena = &mut self.arena_properties;
+
+        match self.selection {
+            Some(EditorSelection::Rectangle(idx)) => {
+                let rect = &mut arena.arena_rectangles[idx];
+                rect.width = (rect.width + d_width).max(MIN_ELEMENT_SIZE);
+                rect.height = (rect.height + d_height).max(MIN_ELEMENT_SIZE);
+            }
+            Some(EditorSelection::Circle(idx)) => {
+                let circle = &mut arena.arena_circles[idx];
+                circle.radius = (circle.radius + (d_width + d_height) / 2.0).max(MIN_ELEMENT_SIZE);
+            }
+            Some(EditorSelection::Hill(idx)) => {
+                let hill = &mut arena.king_hills[idx];
+                hill.radius = (hill.radius + (d_width + d_height) / 2.0).max(MIN_ELEMENT_SIZE);
+            }
+            Some(EditorSelection::Checkpoint(idx)) => {
+                let checkpoint = &mut arena.race_checkpoints[idx];
+                checkpoint.length = (checkpoint.length + d_width + d_height).max(MIN_ELEMENT_SIZE);
+            }
+            _ => {}
+        }
+
+        self.element_changed();
+    }
+
+    fn delete_selected(&mut self) {
+        let arena = &mut self.arena_properties;
+
+        match self.selection {
+            Some(EditorSelection::Rectangle(idx)) => {
+                arena.arena_rectangles.remove(idx);
+            }
+            Some(EditorSelection::Circle(idx)) => {
+                arena.arena_circles.remove(idx);
+            }
+            Some(EditorSelection::Hill(idx)) => {
+                arena.king_hills.remove(idx);
+            }
+            Some(EditorSelection::Checkpoint(idx)) => {
+                arena.race_checkpoints.remove(idx);
+            }
+            Some(EditorSelection::WeaponSpawner(idx)) => {
+                arena.weapon_spawners.remove(idx);
+            }
+            Some(EditorSelection::PlayerSpawn(idx)) => {
+                arena.player_spawn_points.remove(idx);
+            }
+            None => {}
+        }
+
+        self.selection = None;
+        self.element_changed();
+    }
+
+    fn cycle_zone_effect(&mut self) {
+        if let Some(EditorSelection::Rectangle(idx)) = self.selection {
+            let rect = &mut self.arena_properties.arena_rectangles[idx];
+
+            if rect.obstacle_type == ObstacleType::Zone {
+                let current = ZONE_EFFECT_PRESETS
+                    .iter()
+                    .position(|preset| Some(*preset) == rect.effects)
+                    .unwrap_or(ZONE_EFFECT_PRESETS.len() - 1);
+
+                rect.effects = Some(ZONE_EFFECT_PRESETS[(current + 1) % ZONE_EFFECT_PRESETS.len()]);
+            }
+        }
+    }
+
+    fn toggle_destructible(&mut self) {
+        let health = match self.selection {
+            Some(EditorSelection::Rectangle(idx)) => {
+                &mut self.arena_properties.arena_rectangles[idx].health
+            }
+            Some(EditorSelection::Circle(idx)) => {
+                &mut self.arena_properties.arena_circles[idx].health
+            }
+            _ => return,
+        };
+
+        *health = match health {
+            Some(_) => None,
+            None => Some(DESTRUCTIBLE_WALL_HEALTH),
+        };
+    }
+
+    fn element_changed(&mut self) {
+        self.nav_mesh_preview = None;
+    }
+
+    fn save_arena(&mut self) {
+        self.report_validation();
+
+        let arena_ron = arena_properties_to_ron(&self.arena_properties);
+
+        self.message = match std::fs::write(custom_arena_path(), arena_ron) {
+            Ok(_) => format!("Saved {}", CUSTOM_ARENA_FILE),
+            Err(error) => {
+                log::error!("Failed to save {}: {}", CUSTOM_ARENA_FILE, error);
+                format!("Failed to save {}", CUSTOM_ARENA_FILE)
+            }
+        };
+    }
+
+    fn load_arena(&mut self, world: &mut World) {
+        let loaded: Option<ArenaProperties> = match File::open(custom_arena_path()) {
+            Ok(file) => match ron::de::from_reader(file) {
+                Ok(arena_properties) => Some(arena_properties),
+                Err(error) => {
+                    log::error!("Failed to parse {}: {}", CUSTOM_ARENA_FILE, error);
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+
+        if let Some(arena_properties) = loaded {
+            self.arena_properties = arena_properties;
+            self.selection = None;
+            self.element_changed();
+            self.move_cursor(0.0, 0.0);
+            self.message = format!("Loaded {}", CUSTOM_ARENA_FILE);
+
+            //arena size may have changed
+            if let Some(camera) = self.camera {
+                world
+                    .delete_entity(camera)
+                    .expect("Failed to remove editor camera");
+            }
+            self.camera = Some(initialize_editor_camera(world, &self.arena_properties));
+        } else {
+            self.message = format!("Could not load {}", CUSTOM_ARENA_FILE);
+        }
+    }
+
+    fn report_validation(&mut self) {
+        let errors = validate_arena(ArenaNames::CustomArena, &self.arena_properties, None);
+
+        for arena_error in errors.iter() {
+            log::warn!("{}", arena_error);
+        }
+
+        self.message = match errors.first() {
+            Some(first_error) => format!("{} issue(s): {}", errors.len(), first_error),
+            None => "Arena is valid".to_string(),
+        };
+    }
+
+    fn draw_arena(&self, debug_lines_resource: &mut DebugLines) {
+        let arena = &self.arena_properties;
+
+        let border_color = Srgba::new(0.8, 0.8, 0.8, 1.0);
+        let grid_color = Srgba::new(0.3, 0.3, 0.3, 0.2);
+        let wall_color = Srgba::new(0.9, 0.5, 0.1, 1.0);
+        let destructible_color = Srgba::new(0.6, 0.4, 0.2, 1.0);
+        let zone_color = Srgba::new(0.2, 0.4, 1.0, 1.0);
+        let hill_color = Srgba::new(0.9, 0.9, 0.2, 1.0);
+        let finish_color = Srgba::new(1.0, 1.0, 1.0, 1.0);
+        let checkpoint_color = Srgba::new(0.7, 0.7, 0.7, 1.0);
+        let spawner_color = Srgba::new(1.0, 0.3, 0.8, 1.0);
+        let spawn_color = Srgba::new(0.2, 1.0, 0.4, 1.0);
+        let selected_color = Srgba::new(1.0, 0.1, 0.1, 1.0);
+        let cursor_color = Srgba::new(0.2, 1.0, 1.0, 1.0);
+
+        //nav mesh preview, drawn first so it sits under everything else
+        if self.show_nav_mesh {
+            if let Some(nav_mesh) = &self.nav_mesh_preview {
+                for (v1_index, v2_index, v3_index) in nav_mesh.triangles.iter() {
+                    let v1 = nav_mesh.vertices[*v1_index];
+                    let v2 = nav_mesh.vertices[*v2_index];
+                    let v3 = nav_mesh.vertices[*v3_index];
+
+                    draw_segment(debug_lines_resource, (v1.0, v1.1), (v2.0, v2.1), grid_color);
+                    draw_segment(debug_lines_resource, (v2.0, v2.1), (v3.0, v3.1), grid_color);
+                    draw_segment(debug_lines_resource, (v3.0, v3.1), (v1.0, v1.1), grid_color);
+                }
+            }
+        }
+
+        draw_rectangle(
+            debug_lines_resource,
+            arena.width / 2.0,
+            arena.height / 2.0,
+            arena.width,
+            arena.height,
+            0.0,
+            border_color,
+        );
+
+        for floor in arena.floor.iter() {
+            draw_rectangle(
+                debug_lines_resource,
+                floor.x,
+                floor.y,
+                floor.width,
+                floor.height,
+                0.0,
+                grid_color,
+            );
+        }
+
+        for (idx, rect) in arena.arena_rectangles.iter().enumerate() {
+            let color = if self.selection == Some(EditorSelection::Rectangle(idx)) {
+                selected_color
+            } else if rect.obstacle_type == ObstacleType::Zone {
+                zone_color
+            } else if rect.health.is_some() {
+                destructible_color
+            } else {
+                wall_color
+            };
+
+            draw_rectangle(
+                debug_lines_resource,
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                rect.rotation,
+                color,
+            );
+        }
+
+        for (idx, circle) in arena.arena_circles.iter().enumerate() {
+            let color = if self.selection == Some(EditorSelection::Circle(idx)) {
+                selected_color
+            } else if circle.obstacle_type == ObstacleType::Zone {
+                zone_color
+            } else if circle.health.is_some() {
+                destructible_color
+            } else {
+                wall_color
+            };
+
+            debug_lines_resource.draw_circle(
+                Point3::new(circle.x, circle.y, EDITOR_LINE_Z),
+                circle.radius,
+                24,
+                color,
+            );
+        }
+
+        for (idx, hill) in arena.king_hills.iter().enumerate() {
+            let color = if self.selection == Some(EditorSelection::Hill(idx)) {
+                selected_color
+            } else {
+                hill_color
+            };
+
+            debug_lines_resource.draw_circle(
+                Point3::new(hill.x, hill.y, EDITOR_LINE_Z),
+                hill.radius,
+                24,
+                color,
+            );
+        }
+
+        for (idx, checkpoint) in arena.race_checkpoints.iter().enumerate() {
+            let color = if self.selection == Some(EditorSelection::Checkpoint(idx)) {
+                selected_color
+            } else if idx == 0 {
+                finish_color
+            } else {
+                checkpoint_color
+            };
+
+            let angle = checkpoint.rotation / 180.0 * PI;
+            let half_length = checkpoint.length / 2.0;
+
+            draw_segment(
+                debug_lines_resource,
+                (
+                    checkpoint.x - half_length * angle.cos(),
+                    checkpoint.y - half_length * angle.sin(),
+                ),
+                (
+                    checkpoint.x + half_length * angle.cos(),
+                    checkpoint.y + half_length * angle.sin(),
+                ),
+                color,
+            );
+        }
+
+        for (idx, spawner) in arena.weapon_spawners.iter().enumerate() {
+            let color = if self.selection == Some(EditorSelection::WeaponSpawner(idx)) {
+                selected_color
+            } else {
+                spawner_color
+            };
+
+            draw_rectangle(
+                debug_lines_resource,
+                spawner.x,
+                spawner.y,
+                11.0,
+                11.0,
+                45.0,
+                color,
+            );
+        }
+
+        for (idx, spawn) in arena.player_spawn_points.iter().enumerate() {
+            let color = if self.selection == Some(EditorSelection::PlayerSpawn(idx)) {
+                selected_color
+            } else {
+                spawn_color
+            };
+
+            debug_lines_resource.draw_circle(
+                Point3::new(spawn.x, spawn.y, EDITOR_LINE_Z),
+                6.0,
+                12,
+                color,
+            );
+
+            //heading the vehicle will face when spawned
+            let angle = spawn.rotation / 180.0 * PI;
+            draw_segment(
+                debug_lines_resource,
+                (spawn.x, spawn.y),
+                (spawn.x - 14.0 * angle.sin(), spawn.y + 14.0 * angle.cos()),
+                color,
+            );
+        }
+
+        let (x, y) = self.cursor;
+        draw_segment(
+            debug_lines_resource,
+            (x - GRID_SIZE / 2.0, y),
+            (x + GRID_SIZE / 2.0, y),
+            cursor_color,
+        );
+        draw_segment(
+            debug_lines_resource,
+            (x, y - GRID_SIZE / 2.0),
+            (x, y + GRID_SIZE / 2.0),
+            cursor_color,
+        );
+    }
+}
+
+fn snap_to_grid(value: f32) -> f32 {
+    (value / GRID_SIZE).round() * GRID_SIZE
+}
+
+fn empty_arena() -> ArenaProperties {
+    ArenaProperties {
+        width: 400.0,
+        height: 400.0,
+        floor: vec![ArenaFloor {
+            x: 200.0,
+            y: 200.0,
+            width: 400.0,
+            height: 400.0,
+        }],
+        ..ArenaProperties::default()
+    }
+}
+
+fn custom_arena_path() -> PathBuf {
+    let mut path_buf = PathBuf::from("assets");
+    path_buf.extend(&["game", CUSTOM_ARENA_FILE]);
+
+    application_dir(path_buf).expect("Failed to find application directory")
+}
+
+//Make the edited arena playable by storing it as the CustomArena and selecting it
+fn install_custom_arena(world: &mut World, arena_properties: &ArenaProperties) {
+    {
+        let fetched_arena_store = world.try_fetch_mut::<ArenaStoreResource>();
+        if let Some(mut arena_store) = fetched_arena_store {
+            arena_store
+                .properties
+                .insert(ArenaNames::CustomArena, arena_properties.clone());
+        }
+    }
+
+    {
+        let fetched_game_mode_setup = world.try_fetch_mut::<GameModeSetup>();
+        if let Some(mut game_mode_setup) = fetched_game_mode_setup {
+            game_mode_setup.arena_name = ArenaNames::CustomArena;
+
+            if game_mode_setup.game_mode == GameModes::Race {
+                game_mode_setup.checkpoint_count = arena_properties.race_checkpoints.len() as i32;
+            }
+        }
+    }
+}
+
+fn initialize_editor_camera(world: &mut World, arena_properties: &ArenaProperties) -> Entity {
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(
+        arena_properties.width * 0.5,
+        arena_properties.height * 0.5,
+        1.0,
+    );
+
+    world
+        .create_entity()
+        .with(Camera::standard_2d(
+            arena_properties.width * EDITOR_VIEW_SCALE,
+            arena_properties.height * EDITOR_VIEW_SCALE,
+        ))
+        .with(transform)
+        .build()
+}
+
+fn draw_segment(
+    debug_lines_resource: &mut DebugLines,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: Srgba,
+) {
+    debug_lines_resource.draw_line(
+        Point3::new(start.0, start.1, EDITOR_LINE_Z),
+        Point3::new(end.0, end.1, EDITOR_LINE_Z),
+        color,
+    );
+}
+
+fn draw_rectangle(
+    debug_lines_resource: &mut DebugLines,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    rotation: f32,
+    color: Srgba,
+) {
+    let angle = rotation / 180.0 * PI;
+    let (sin, cos) = angle.sin_cos();
+
+    let corners: Vec<(f32, f32)> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .iter()
+        .map(|(sx, sy)| {
+            let dx = sx * width / 2.0;
+            let dy = sy * height / 2.0;
+            (x + dx * cos - dy * sin, y + dx * sin + dy * cos)
+        })
+        .collect();
+
+    for idx in 0..corners.len() {
+        draw_segment(
+            debug_lines_resource,
+            corners[idx],
+            corners[(idx + 1) % corners.len()],
+            color,
+        );
     }
 }
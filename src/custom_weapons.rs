@@ -10,7 +10,8 @@ use amethyst::{
 
 use crate::menu::MainMenu;
 
-use crate::components::{WeaponNames, WeaponStoreResource};
+use crate::components::{VehicleStoreResource, WeaponNames, WeaponStoreResource};
+use crate::weapon_balance::{calculate_weapon_balance, format_time_to_kill, WeaponBalance};
 
 const BUTTON_BACK_TO_MENU: &str = "back_to_menu";
 
@@ -21,6 +22,8 @@ pub struct CustomWeaponsMenu {
 
     weapon_names_list_display: Vec<(WeaponNames, String)>,
     text_weapon_names: Vec<Entity>,
+    weapon_balances: Vec<Option<WeaponBalance>>,
+    text_weapon_stats: Vec<Entity>,
 }
 
 impl SimpleState for CustomWeaponsMenu {
@@ -45,6 +48,7 @@ impl SimpleState for CustomWeaponsMenu {
         {
             //Build list of weapon display names
             let fetched_weapon_store = world.fetch::<WeaponStoreResource>();
+            let fetched_vehicle_store = world.fetch::<VehicleStoreResource>();
 
             for weapon_name in fetched_weapon_store.selection_order.iter() {
                 let weapon_properties = fetched_weapon_store.properties.get(&weapon_name);
@@ -54,6 +58,12 @@ impl SimpleState for CustomWeaponsMenu {
 
                     self.weapon_names_list_display
                         .push((*weapon_name, weapon_display_name));
+
+                    self.weapon_balances.push(calculate_weapon_balance(
+                        *weapon_name,
+                        &fetched_weapon_store,
+                        &fetched_vehicle_store,
+                    ));
                 }
             }
         }
@@ -89,6 +99,81 @@ impl SimpleState for CustomWeaponsMenu {
                 .build();
 
             self.text_weapon_names.push(weapon_display_entity);
+
+            //Damage per second against shields/armor/health, and the fastest to slowest kill
+            let (dps_text, time_to_kill_text) = match &self.weapon_balances[idx] {
+                Some(weapon_balance) => {
+                    let mut fastest_kill: Option<f32> = None;
+                    let mut slowest_kill: Option<f32> = None;
+                    let mut always_kills = true;
+
+                    for (_vehicle_name, time_to_kill) in weapon_balance.time_to_kill.iter() {
+                        if let Some(time_to_kill) = time_to_kill {
+                            fastest_kill = Some(
+                                fastest_kill
+                                    .map_or(*time_to_kill, |fastest| fastest.min(*time_to_kill)),
+                            );
+                            slowest_kill = Some(
+                                slowest_kill
+                                    .map_or(*time_to_kill, |slowest| slowest.max(*time_to_kill)),
+                            );
+                        } else {
+                            always_kills = false;
+                        }
+                    }
+
+                    if !always_kills {
+                        slowest_kill = None;
+                    }
+
+                    (
+                        format!(
+                            "{:.0}/{:.0}/{:.0}",
+                            weapon_balance.shield_dps,
+                            weapon_balance.armor_dps,
+                            weapon_balance.health_dps
+                        ),
+                        format!(
+                            "{} - {}",
+                            format_time_to_kill(fastest_kill),
+                            format_time_to_kill(slowest_kill)
+                        ),
+                    )
+                }
+                None => ("-".to_string(), "-".to_string()),
+            };
+
+            for (stat_x, stat_width, stat_text) in [
+                (1010.0, 180.0, dps_text),
+                (1210.0, 160.0, time_to_kill_text),
+            ]
+            .iter()
+            {
+                let stat_transform = UiTransform::new(
+                    format!("{}_{}", weapon_display_name, stat_x),
+                    Anchor::TopLeft,
+                    Anchor::TopLeft,
+                    *stat_x,
+                    -140.0 - ((idx as f32) * 25.0),
+                    0.3,
+                    *stat_width,
+                    18.0,
+                );
+
+                let mut ui_text =
+                    UiText::new(font.clone(), stat_text.clone(), [1., 1., 1., 1.], 18.);
+
+                ui_text.align = Anchor::Middle;
+
+                let weapon_stat_entity = world
+                    .create_entity()
+                    .with(stat_transform)
+                    .with(ui_text)
+                    .with(Removal::new(0 as u32))
+                    .build();
+
+                self.text_weapon_stats.push(weapon_stat_entity);
+            }
         }
     }
 
@@ -152,5 +237,7 @@ impl SimpleState for CustomWeaponsMenu {
         self.button_back_to_menu = None;
 
         self.text_weapon_names = Vec::new();
+        self.weapon_balances = Vec::new();
+        self.text_weapon_stats = Vec::new();
     }
 }
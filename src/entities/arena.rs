@@ -16,9 +16,10 @@ use crate::components::{
     HitboxShape, ObstacleType, RaceCheckpointType,
 };
 
-use crate::resources::{ArenaNavMesh, ArenaNavMeshFinal, GameModeSetup};
-
-use navmesh::{NavMesh, NavTriangle, NavVec3};
+use crate::resources::{
+    build_arena_nav_mesh, build_nav_mesh_final, build_racing_line, get_arena_nav_mesh_obstacles,
+    ArenaNavMesh, ArenaNavMeshFinal, GameModeSetup, RacingLine,
+};
 
 pub fn intialize_arena(
     world: &mut World,
@@ -51,24 +52,6 @@ pub fn intialize_arena(
         }
     }
 
-    //Initialize Nav Mesh Grid
-    let debug_line_z = 0.0;
-    let nav_mesh_offset = 7.0;
-
-    let mut nav_mesh_grid_xs: Vec<f32> = Vec::new();
-    nav_mesh_grid_xs.push(0.0 + nav_mesh_offset);
-    nav_mesh_grid_xs.push(0.0 + 3.0 * nav_mesh_offset);
-    nav_mesh_grid_xs.push(arena_properties.width - nav_mesh_offset);
-    nav_mesh_grid_xs.push(arena_properties.width - 3.0 * nav_mesh_offset);
-
-    let mut nav_mesh_grid_ys: Vec<f32> = Vec::new();
-    nav_mesh_grid_ys.push(0.0 + nav_mesh_offset);
-    nav_mesh_grid_ys.push(0.0 + 3.0 * nav_mesh_offset);
-    nav_mesh_grid_ys.push(arena_properties.height - nav_mesh_offset);
-    nav_mesh_grid_ys.push(arena_properties.height - 3.0 * nav_mesh_offset);
-
-    let mut nav_mesh_grid_drop: Vec<(f32, f32, f32, f32)> = Vec::new();
-
     //Build Arena from properties
 
     //Arena Floor
@@ -103,7 +86,10 @@ pub fn intialize_arena(
         //add visual sprite
         let mut transform = Transform::default();
 
-        transform.set_rotation_2d(arena_rect.rotation / 180.0 * PI);
+        //hitboxes are rotated along with the sprite
+        let rect_angle = arena_rect.rotation / 180.0 * PI;
+
+        transform.set_rotation_2d(rect_angle);
         if arena_rect.obstacle_type == ObstacleType::Wall {
             transform.set_translation_xyz(arena_rect.x, arena_rect.y, 0.38);
         } else {
@@ -144,10 +130,11 @@ pub fn intialize_arena(
                     hitbox: Hitbox::new(
                         2.0 * sprite_scale_mult * x_scale,
                         2.0 * sprite_scale_mult * y_scale,
-                        0.0,
+                        rect_angle,
                         HitboxShape::Rectangle,
                     ),
                     effects: None,
+                    health: arena_rect.health,
                 })
                 .build();
         } else {
@@ -187,10 +174,11 @@ pub fn intialize_arena(
                             hitbox: Hitbox::new(
                                 2.0 * sprite_scale_mult * x_scale,
                                 2.0 * sprite_scale_mult * y_scale,
-                                0.0,
+                                rect_angle,
                                 HitboxShape::Rectangle,
                             ),
                             effects: Some(arena_rect_effects),
+                            health: None,
                         })
                         .build();
                 } else if arena_rect.obstacle_type == ObstacleType::Zone
@@ -229,10 +217,11 @@ pub fn intialize_arena(
                             hitbox: Hitbox::new(
                                 2.0 * sprite_scale_mult * x_scale,
                                 2.0 * sprite_scale_mult * y_scale,
-                                0.0,
+                                rect_angle,
                                 HitboxShape::Rectangle,
                             ),
                             effects: Some(arena_rect_effects),
+                            health: None,
                         })
                         .build();
                 } else if arena_rect.obstacle_type == ObstacleType::Zone
@@ -270,49 +259,16 @@ pub fn intialize_arena(
                             hitbox: Hitbox::new(
                                 2.0 * sprite_scale_mult * x_scale,
                                 2.0 * sprite_scale_mult * y_scale,
-                                0.0,
+                                rect_angle,
                                 HitboxShape::Rectangle,
                             ),
                             effects: Some(arena_rect_effects),
+                            health: None,
                         })
                         .build();
                 }
             }
         }
-
-        if arena_rect.obstacle_type == ObstacleType::Wall {
-            //setup nav mesh grid
-            let x_offset = sprite_scale_mult * x_scale + nav_mesh_offset;
-            let xr_minus = (arena_rect.x - x_offset).round();
-            let xr_plus = (arena_rect.x + x_offset).round();
-
-            let xr_minus_find_index = nav_mesh_grid_xs.iter().position(|&r| r == xr_minus);
-            let xr_plus_find_index = nav_mesh_grid_xs.iter().position(|&r| r == xr_plus);
-
-            if xr_minus_find_index.is_none() {
-                nav_mesh_grid_xs.push(xr_minus);
-            }
-            if xr_plus_find_index.is_none() {
-                nav_mesh_grid_xs.push(xr_plus);
-            }
-
-            let y_offset = sprite_scale_mult * y_scale + nav_mesh_offset;
-
-            let yr_minus = (arena_rect.y - y_offset).round();
-            let yr_plus = (arena_rect.y + y_offset).round();
-
-            let yr_minus_find_index = nav_mesh_grid_ys.iter().position(|&r| r == yr_minus);
-            let yr_plus_find_index = nav_mesh_grid_ys.iter().position(|&r| r == yr_plus);
-
-            if yr_minus_find_index.is_none() {
-                nav_mesh_grid_ys.push(yr_minus);
-            }
-            if yr_plus_find_index.is_none() {
-                nav_mesh_grid_ys.push(yr_plus);
-            }
-
-            nav_mesh_grid_drop.push((xr_minus, xr_plus, yr_minus, yr_plus));
-        }
     }
 
     //Arena Circles
@@ -361,40 +317,13 @@ pub fn intialize_arena(
                     HitboxShape::Circle,
                 ),
                 effects: None,
+                health: if arena_circle.obstacle_type == ObstacleType::Wall {
+                    arena_circle.health
+                } else {
+                    None
+                },
             })
             .build();
-        if arena_circle.obstacle_type == ObstacleType::Wall {
-            //setup nav mesh grid
-            let offset = sprite_scale_mult * scale + nav_mesh_offset;
-
-            let xr_minus = (arena_circle.x - offset).round();
-            let xr_plus = (arena_circle.x + offset).round();
-
-            let xr_minus_find_index = nav_mesh_grid_xs.iter().position(|&r| r == xr_minus);
-            let xr_plus_find_index = nav_mesh_grid_xs.iter().position(|&r| r == xr_plus);
-
-            if xr_minus_find_index.is_none() {
-                nav_mesh_grid_xs.push(xr_minus);
-            }
-            if xr_plus_find_index.is_none() {
-                nav_mesh_grid_xs.push(xr_plus);
-            }
-
-            let yr_minus = (arena_circle.y - offset).round();
-            let yr_plus = (arena_circle.y + offset).round();
-
-            let yr_minus_find_index = nav_mesh_grid_ys.iter().position(|&r| r == yr_minus);
-            let yr_plus_find_index = nav_mesh_grid_ys.iter().position(|&r| r == yr_plus);
-
-            if yr_minus_find_index.is_none() {
-                nav_mesh_grid_ys.push(yr_minus);
-            }
-            if yr_plus_find_index.is_none() {
-                nav_mesh_grid_ys.push(yr_plus);
-            }
-
-            nav_mesh_grid_drop.push((xr_minus, xr_plus, yr_minus, yr_plus));
-        }
     }
 
     //Arena King Hill
@@ -442,6 +371,7 @@ pub fn intialize_arena(
                 ammo: None,
                 hitbox: Hitbox::new(20.0 * scale, 20.0 * scale, 0.0, HitboxShape::Circle),
                 effects: None,
+                health: None,
             })
             .with(Transparent)
             .with(king_tint)
@@ -509,6 +439,7 @@ pub fn intialize_arena(
                 ammo: None,
                 hitbox: Hitbox::new(width, height, 0.0, HitboxShape::Rectangle),
                 effects: None,
+                health: None,
             })
             .build();
     }
@@ -523,97 +454,38 @@ pub fn intialize_arena(
             .build();
     }
 
-    //Build navigation mesh from grid
-    let mut nav_mesh_vertices: Vec<(f32, f32, f32)> = Vec::new();
-    let mut nav_mesh_triangles: Vec<(usize, usize, usize)> = Vec::new();
-
-    //Filter and sort
-    nav_mesh_grid_xs.retain(|&x| x >= 0.0 && x <= arena_properties.width);
-    nav_mesh_grid_xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    nav_mesh_grid_ys.retain(|&y| y >= 0.0 && y <= arena_properties.height);
-    nav_mesh_grid_ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    let xs_len = nav_mesh_grid_xs.len();
-    let ys_len = nav_mesh_grid_xs.len();
-
-    log::info!("{} {}", xs_len, ys_len);
-    log::info!("{:?} {:?}", nav_mesh_grid_xs, nav_mesh_grid_ys);
-    log::info!("{:?}", nav_mesh_grid_drop);
-
-    //if *x >= 0.0 && *x <= arena_properties.width {
-    //if *y >= 0.0 && *y <= arena_properties.height {
-
-    for (y_idx, y) in nav_mesh_grid_ys.iter().enumerate() {
-        for (x_idx, x) in nav_mesh_grid_xs.iter().enumerate() {
-            nav_mesh_vertices.push((*x, *y, debug_line_z));
-
-            let vertex_idx = nav_mesh_vertices.len() - 1;
-
-            if (x_idx > 0) && (y_idx > 0) {
-                //don't evaluate first index, otherwise underflow
-                let xr_minus = nav_mesh_vertices[vertex_idx - 1].0; //bottom-left x
-                let xr_plus = nav_mesh_vertices[vertex_idx - xs_len].0; //top-right x
-                let yr_minus = nav_mesh_vertices[vertex_idx - 1].1; //bottom-left y
-                let yr_plus = nav_mesh_vertices[vertex_idx - xs_len].1; //top-right y
-
-                let mut dropped = false;
-                for (drop_x_minus, drop_x_plus, drop_y_minus, drop_y_plus) in
-                    nav_mesh_grid_drop.iter()
-                {
-                    if xr_minus >= *drop_x_minus
-                        && xr_minus <= *drop_x_plus
-                        && xr_plus >= *drop_x_minus
-                        && xr_plus <= *drop_x_plus
-                        && yr_minus >= *drop_y_minus
-                        && yr_minus <= *drop_y_plus
-                        && yr_plus >= *drop_y_minus
-                        && yr_plus <= *drop_y_plus
-                    {
-                        dropped = true;
-                        break;
-                    }
-                }
-
-                if !dropped {
-                    nav_mesh_triangles.push((
-                        vertex_idx - 1,
-                        vertex_idx - xs_len - 1,
-                        vertex_idx - xs_len,
-                    ));
-                    nav_mesh_triangles.push((vertex_idx - 1, vertex_idx, vertex_idx - xs_len));
-                }
-            }
-        }
-    }
-
-    //Store navigation mesh
+    //Build and store navigation mesh
     let fetched_arena_nav_mesh = world.try_fetch_mut::<ArenaNavMesh>();
 
     if let Some(mut arena_nav_mesh) = fetched_arena_nav_mesh {
-        arena_nav_mesh.vertices = nav_mesh_vertices.clone();
-        arena_nav_mesh.triangles = nav_mesh_triangles.clone();
+        *arena_nav_mesh = build_arena_nav_mesh(
+            arena_properties.width,
+            arena_properties.height,
+            &arena_properties.floor,
+            &get_arena_nav_mesh_obstacles(&arena_properties),
+        );
 
         let fetched_arena_nav_mesh_final = world.try_fetch_mut::<ArenaNavMeshFinal>();
 
         if let Some(mut arena_nav_mesh_final) = fetched_arena_nav_mesh_final {
-            let mut nav_vecs: Vec<NavVec3> = Vec::new();
-            let mut nav_triangles: Vec<NavTriangle> = Vec::new();
-
-            for (x, y, z) in arena_nav_mesh.vertices.iter() {
-                nav_vecs.push(NavVec3::new(*x, *y, *z));
+            match build_nav_mesh_final(&arena_nav_mesh) {
+                Ok(mesh) => arena_nav_mesh_final.mesh = Some(mesh),
+                Err(error) => {
+                    log::warn!("Nav mesh could not be built: {}", error);
+                    arena_nav_mesh_final.mesh = None;
+                }
             }
 
-            for (v1, v2, v3) in arena_nav_mesh.triangles.iter() {
-                nav_triangles.push(NavTriangle {
-                    first: *v1 as u32,
-                    second: *v2 as u32,
-                    third: *v3 as u32,
-                });
-            }
+            //Race bots drive a line precomputed from the checkpoints
+            let fetched_racing_line = world.try_fetch_mut::<RacingLine>();
 
-            arena_nav_mesh_final.mesh =
-                Some(NavMesh::new(nav_vecs.clone(), nav_triangles.clone()).unwrap());
+            if let Some(mut racing_line) = fetched_racing_line {
+                *racing_line = build_racing_line(
+                    &arena_properties,
+                    &arena_nav_mesh,
+                    &arena_nav_mesh_final.mesh,
+                );
+            }
         }
     }
 }
@@ -0,0 +1,68 @@
+use amethyst::{
+    core::{math::Vector3, transform::Transform},
+    ecs::prelude::{Entities, Entity, LazyUpdate, ReadExpect},
+    utils::removal::Removal,
+};
+
+use crate::components::{
+    build_named_weapon, get_weapon_icon, Deployable, DeployableStats, Weapon, WeaponArray,
+    WeaponInstall, WeaponStoreResource,
+};
+use crate::resources::WeaponFireResource;
+
+pub const DEPLOYABLE_Z: f32 = 0.3;
+
+//Drop a turret or drone for a player, armed with the gun named in its stats
+pub fn deploy_weapon(
+    entities: &Entities,
+    weapon_fire_resource: &ReadExpect<WeaponFireResource>,
+    weapon_store: &WeaponStoreResource,
+    weapon: &Weapon,
+    deployable_stats: DeployableStats,
+    position: Vector3<f32>,
+    angle: f32,
+    player_id: usize,
+    player_team: i32,
+    lazy_update: &ReadExpect<LazyUpdate>,
+) {
+    let deployable_entity: Entity = entities.create();
+
+    let mut local_transform = Transform::default();
+    local_transform.set_translation_xyz(position.x, position.y, DEPLOYABLE_Z);
+    local_transform.set_rotation_2d(angle);
+
+    //the gun's shots carry the deploying weapon's name, so its kills count towards gun-game
+    //deployables have no HUD icon, so the icon entity points back at the deployable itself
+    let gun_stats = build_named_weapon(deployable_stats.weapon_name, weapon_store);
+    let gun = Weapon::new(weapon.name, deployable_entity, gun_stats, None);
+
+    let (_icon_scale, deployable_sprite) = get_weapon_icon(
+        Some(player_id),
+        weapon.name,
+        weapon.stats.weapon_fire_type,
+        weapon_fire_resource,
+    );
+
+    lazy_update.insert(
+        deployable_entity,
+        Deployable::new(player_id, player_team, deployable_stats, angle),
+    );
+    lazy_update.insert(
+        deployable_entity,
+        WeaponArray {
+            installed: vec![WeaponInstall {
+                weapon: gun,
+                firing_group: 0,
+                ammo: None,
+                mounted_angle: None,
+                x_offset: None,
+                y_offset: None,
+            }],
+            modifiers: Vec::new(),
+            modifier_text_entity: None,
+        },
+    );
+    lazy_update.insert(deployable_entity, deployable_sprite);
+    lazy_update.insert(deployable_entity, local_transform);
+    lazy_update.insert(deployable_entity, Removal::new(0 as u32));
+}
@@ -1,7 +1,8 @@
 pub use self::arena::intialize_arena;
 pub use self::camera::{initialize_camera, initialize_camera_to_player};
+pub use self::deployables::deploy_weapon;
 pub use self::particles::{
-    acceleration_spray, explosion_shockwave, hit_spray, malfunction_sparking,
+    acceleration_spray, explosion_shockwave, hit_spray, malfunction_sparking, trail_particle,
 };
 pub use self::player::intialize_player;
 pub use self::ui::{connect_players_to_ui, initialize_timer_ui, PlayerStatusText};
@@ -10,6 +11,7 @@ pub use self::weapon_fire::{chain_fire_weapon, fire_weapon};
 
 mod arena;
 mod camera;
+mod deployables;
 mod particles;
 mod player;
 pub mod ui;
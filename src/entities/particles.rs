@@ -9,7 +9,7 @@ use amethyst::{
 use rand::Rng;
 use std::f32::consts::PI;
 
-use crate::resources::WeaponFireResource;
+use crate::resources::{WeaponFireResource, WeaponFireTrail};
 
 use crate::components::{Particles, Shockwave};
 
@@ -181,3 +181,42 @@ pub fn explosion_shockwave(
 
     lazy_update.insert(shockwave_entity, Removal::new(0 as u32));
 }
+
+pub fn trail_particle(
+    entities: &Entities,
+    weapon_fire_resource: &ReadExpect<WeaponFireResource>,
+    trail: &WeaponFireTrail,
+    position: Vector3<f32>,
+    lazy_update: &ReadExpect<LazyUpdate>,
+) {
+    let particles_entity: Entity = entities.create();
+
+    let particles_sprite = weapon_fire_resource.get_sprite_render(trail.sprite_number);
+
+    let mut local_transform = Transform::default();
+    local_transform.set_translation(position);
+
+    let mut rng = rand::thread_rng();
+    let random_rotation_angle = rng.gen_range(-PI, PI);
+
+    local_transform.set_rotation_2d(random_rotation_angle);
+
+    let random_velocity_angle = rng.gen_range(-PI, PI);
+
+    let x_comp = -random_velocity_angle.sin();
+    let y_comp = random_velocity_angle.cos();
+
+    lazy_update.insert(
+        particles_entity,
+        Particles {
+            dx: trail.drift_speed * x_comp,
+            dy: trail.drift_speed * y_comp,
+            life_timer: trail.life_time,
+        },
+    );
+    lazy_update.insert(particles_entity, particles_sprite);
+    lazy_update.insert(particles_entity, Transparent);
+    lazy_update.insert(particles_entity, local_transform);
+
+    lazy_update.insert(particles_entity, Removal::new(0 as u32));
+}
@@ -1,10 +1,10 @@
 use amethyst::{
-    assets::Handle,
+    assets::{Handle, Loader},
     core::transform::Transform,
     ecs::prelude::{Entity, World},
     prelude::*,
     renderer::{palette::Srgba, resources::Tint, SpriteRender, SpriteSheet, Transparent},
-    ui::{Anchor, UiImage, UiTransform},
+    ui::{Anchor, TtfFormat, UiImage, UiText, UiTransform},
     utils::removal::Removal,
 };
 
@@ -13,9 +13,9 @@ use amethyst::core::math::Vector3;
 use std::f32::consts::PI;
 
 use crate::components::{
-    build_named_weapon_from_world, get_vehicle_sprites, get_weapon_icon, get_weapon_width_height,
-    ArenaNames, ArenaProperties, ArenaStoreResource, Player, PlayerWeaponIcon, Vehicle,
-    VehicleStats, Weapon, WeaponArray, WeaponInstall,
+    build_named_weapon_from_world, get_vehicle_sprites, get_weapon_heat_bar_transform,
+    get_weapon_icon, ArenaNames, ArenaProperties, ArenaStoreResource, Player, PlayerWeaponIcon,
+    Vehicle, VehicleStats, Weapon, WeaponArray, WeaponInstall,
 };
 use crate::resources::{GameModeSetup, WeaponFireResource};
 
@@ -223,6 +223,7 @@ pub fn intialize_player(
 
         let (icon_scale, weapon_sprite) = get_weapon_icon(
             Some(player_index),
+            weapon_name_install.weapon_name.clone(),
             weapon_stats.weapon_fire_type.clone(),
             &weapon_fire_resource,
         );
@@ -235,8 +236,10 @@ pub fn intialize_player(
             _ => (0.0),
         };
 
-        let (weapon_width, weapon_height) =
-            get_weapon_width_height(weapon_stats.weapon_fire_type.clone());
+        let (weapon_width, weapon_height) = weapon_fire_resource.get_weapon_fire_size(
+            weapon_name_install.weapon_name,
+            weapon_stats.weapon_fire_type,
+        );
 
         let icon_weapon_transform = UiTransform::new(
             "PWeaponIcon".to_string(),
@@ -267,13 +270,32 @@ pub fn intialize_player(
             .with(Transparent)
             .build();
 
-        let weapon = Weapon::new(
+        let mut weapon = Weapon::new(
             weapon_name_install.weapon_name,
             weapon_icon,
             weapon_stats.clone(),
             weapon_name_install.ammo,
         );
 
+        //heat bar next to the icon, for weapons that can overheat
+        if weapon_stats.heat_per_shot > 0.0 {
+            let heat_bar = world
+                .create_entity()
+                .with(Removal::new(0 as u32))
+                .with(PlayerWeaponIcon::new(
+                    player_index,
+                    0,
+                    weapon_stats.weapon_fire_type,
+                ))
+                .with(UiImage::SolidColor([1.0, 1.0, 1.0, 1.0]))
+                .with(get_weapon_heat_bar_transform(starting_x, y))
+                .with(Tint(Srgba::new(1.0, 0.6, 0.1, 1.0)))
+                .with(Transparent)
+                .build();
+
+            weapon.heat_bar_entity = Some(heat_bar);
+        }
+
         installed_weapons.push(WeaponInstall {
             weapon,
             firing_group: weapon_name_install.firing_group,
@@ -285,23 +307,104 @@ pub fn intialize_player(
         total_weapon_weight += weapon_stats.weight;
     }
 
+    //UI weapon modifier list, under the weapon icons
+    let modifier_text_entity;
+    {
+        let font = world.read_resource::<Loader>().load(
+            "font/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        let x = -290.;
+        let starting_x = match player_index {
+            0 => (x),
+            1 => (x + dx),
+            2 => (x + 2.0 * dx),
+            3 => (x + 3.0 * dx),
+            _ => (0.0),
+        };
+
+        let modifier_text_transform = UiTransform::new(
+            "PWeaponModifiers".to_string(),
+            Anchor::BottomMiddle,
+            Anchor::BottomMiddle,
+            starting_x,
+            15.,
+            0.2,
+            200.,
+            20.,
+        );
+
+        modifier_text_entity = world
+            .create_entity()
+            .with(Removal::new(0 as u32))
+            .with(modifier_text_transform)
+            .with(UiText::new(font, "".to_string(), [1., 0.8, 0.3, 1.], 14.))
+            .build();
+    }
+
+    //UI ability indicator, next to the weapon icons
+    let ability_text_entity;
+    {
+        let font = world.read_resource::<Loader>().load(
+            "font/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        let x = -230.;
+        let starting_x = match player_index {
+            0 => (x),
+            1 => (x + dx),
+            2 => (x + 2.0 * dx),
+            3 => (x + 3.0 * dx),
+            _ => (0.0),
+        };
+
+        let ability_text_transform = UiTransform::new(
+            "PAbility".to_string(),
+            Anchor::BottomMiddle,
+            Anchor::BottomMiddle,
+            starting_x,
+            y,
+            0.2,
+            90.,
+            20.,
+        );
+
+        ability_text_entity = world
+            .create_entity()
+            .with(Removal::new(0 as u32))
+            .with(ability_text_transform)
+            .with(UiText::new(font, "".to_string(), [0.3, 1., 0.3, 1.], 14.))
+            .build();
+    }
+
     //Create actual Player with Vehicle and Weapon
     world
         .create_entity()
         .with(Removal::new(0 as u32))
         .with(vehicle_transform)
         .with(vehicle_sprite_render)
+        .with(Transparent)
+        .with(Tint(Srgba::new(1.0, 1.0, 1.0, 1.0)))
         .with(Vehicle::new(
             player_status_text,
             health_entity,
             armor_entity,
             shield_entity,
             repair_entity,
+            ability_text_entity,
             vehicle_stats,
             total_weapon_weight,
         ))
         .with(WeaponArray {
             installed: installed_weapons,
+            modifiers: Vec::new(),
+            modifier_text_entity: Some(modifier_text_entity),
         })
         .with(Player::new(player_index, team, is_bot))
         .build()
@@ -2,6 +2,7 @@ use amethyst::{
     core::math::Vector3,
     core::transform::Transform,
     ecs::prelude::{Entities, Entity, LazyUpdate, ReadExpect},
+    renderer::{palette::Srgba, resources::Tint, Transparent},
     utils::removal::Removal,
 };
 
@@ -9,7 +10,9 @@ use std::f32::consts::PI;
 
 use crate::resources::WeaponFireResource;
 
-use crate::components::{get_weapon_icon, Weapon, WeaponFire};
+use crate::components::{
+    get_weapon_icon, ParticleTrail, Weapon, WeaponFire, WeaponFireTypes, WeaponNames,
+};
 
 pub fn chain_fire_weapon(
     entities: &Entities,
@@ -32,6 +35,7 @@ pub fn chain_fire_weapon(
         spawner_weapon_fire.weapon_fire_type,
         weapon_angle_offset,
         spawner_weapon_fire.stats,
+        (spawner_weapon_fire.width, spawner_weapon_fire.height),
     );
 
     weapon_fire.chain_hit_ids = spawner_weapon_fire.chain_hit_ids.clone();
@@ -50,17 +54,20 @@ pub fn chain_fire_weapon(
 
         local_transform
     };
+    let weapon_name = weapon_fire.weapon_name;
+    let weapon_fire_type = weapon_fire.weapon_fire_type;
     lazy_update.insert(fire_entity, weapon_fire);
 
-    let (_icon_scale, weapon_sprite) = get_weapon_icon(
+    insert_weapon_fire_visual(
+        fire_entity,
         player_id,
-        spawner_weapon_fire.weapon_fire_type,
+        weapon_name,
+        weapon_fire_type,
+        local_transform,
         weapon_fire_resource,
+        lazy_update,
     );
 
-    lazy_update.insert(fire_entity, weapon_sprite);
-    lazy_update.insert(fire_entity, local_transform);
-
     lazy_update.insert(fire_entity, Removal::new(0 as u32));
 }
 
@@ -84,6 +91,7 @@ pub fn fire_weapon(
         weapon.stats.weapon_fire_type,
         weapon_angle_offset,
         weapon.stats.fire_stats,
+        weapon_fire_resource.get_weapon_fire_hitbox(weapon.name, weapon.stats.weapon_fire_type),
     );
 
     let local_transform = {
@@ -112,14 +120,55 @@ pub fn fire_weapon(
     };
     lazy_update.insert(fire_entity, weapon_fire);
 
-    let (_icon_scale, weapon_sprite) = get_weapon_icon(
+    insert_weapon_fire_visual(
+        fire_entity,
         player_id,
+        weapon.name,
         weapon.stats.weapon_fire_type,
+        local_transform,
         weapon_fire_resource,
+        lazy_update,
     );
 
+    lazy_update.insert(fire_entity, Removal::new(0 as u32));
+}
+
+//Sprite, scale, tint and trail of a shot, as set in weapon_fire_visuals.ron
+fn insert_weapon_fire_visual(
+    fire_entity: Entity,
+    player_id: Option<usize>,
+    weapon_name: WeaponNames,
+    weapon_fire_type: WeaponFireTypes,
+    mut local_transform: Transform,
+    weapon_fire_resource: &ReadExpect<WeaponFireResource>,
+    lazy_update: &ReadExpect<LazyUpdate>,
+) {
+    let (_icon_scale, weapon_sprite) = get_weapon_icon(
+        player_id,
+        weapon_name,
+        weapon_fire_type,
+        weapon_fire_resource,
+    );
+
+    if let Some(weapon_fire_visual) =
+        weapon_fire_resource.get_weapon_fire_visual(weapon_name, weapon_fire_type)
+    {
+        let scale = weapon_fire_visual.scale;
+        local_transform.set_scale(Vector3::new(scale, scale, 1.0));
+
+        if let Some((red, green, blue, alpha)) = weapon_fire_visual.tint {
+            lazy_update.insert(fire_entity, Tint(Srgba::new(red, green, blue, alpha)));
+
+            if alpha < 1.0 {
+                lazy_update.insert(fire_entity, Transparent);
+            }
+        }
+
+        if let Some(trail) = weapon_fire_visual.trail {
+            lazy_update.insert(fire_entity, ParticleTrail::new(trail));
+        }
+    }
+
     lazy_update.insert(fire_entity, weapon_sprite);
     lazy_update.insert(fire_entity, local_transform);
-
-    lazy_update.insert(fire_entity, Removal::new(0 as u32));
 }
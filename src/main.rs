@@ -25,12 +25,18 @@ mod rally;
 mod score_screen;
 mod welcome;
 
+mod arena_generator;
+mod arena_validation;
 mod audio;
 mod components;
 mod entities;
 mod resources;
 mod systems;
+mod weapon_balance;
 
+use crate::arena_generator::generate_arena_command;
+use crate::arena_validation::validate_arenas_command;
+use crate::weapon_balance::weapon_balance_command;
 use crate::welcome::WelcomeScreen;
 use serde::de::DeserializeOwned;
 
@@ -47,6 +53,32 @@ fn load_ron_asset<T: DeserializeOwned>(path: &[&str]) -> T {
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
 
+    //Command line tools, ex: cargo run -- validate-arenas
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 {
+        match args[1].as_str() {
+            "validate-arenas" => {
+                if !validate_arenas_command() {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            "generate-arena" => {
+                if !generate_arena_command(&args[2..]) {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            "weapon-balance" => {
+                if !weapon_balance_command() {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+
     let app_root = application_root_dir()?;
 
     let assets_dir = app_root.join("assets");
@@ -16,14 +16,14 @@ use crate::rally::GameplayState;
 use crate::welcome::WelcomeScreen;
 
 use crate::components::{
-    build_arena_store, build_vehicle_store, build_weapon_store, get_next_gg_weapon_name,
-    get_none_vehicle, ArenaNames, ArenaStoreResource, VehicleNames, VehicleStats,
-    WeaponNameInstall, WeaponNames, WeaponStoreResource,
+    build_arena_store, build_bot_profile_store, build_vehicle_store, build_weapon_store,
+    get_next_gg_weapon_name, get_none_vehicle, ArenaNames, ArenaStoreResource, VehicleNames,
+    VehicleStats, WeaponNameInstall, WeaponNames, WeaponStoreResource,
 };
 
 use crate::resources::{
-    GameEndCondition, GameModeSetup, GameModes, GameScore, GameTeamSetup, GameVehicleSetup,
-    GameWeaponSelectionMode, GameWeaponSetup, TeamSetupTypes,
+    BotSetup, GameEndCondition, GameModeSetup, GameModes, GameScore, GameTeamSetup,
+    GameVehicleSetup, GameWeaponSelectionMode, GameWeaponSetup, StormSetup, TeamSetupTypes,
 };
 
 pub const MAX_PLAYER_COUNT: usize = 4;
@@ -69,6 +69,28 @@ const BUTTON_1V3: &str = "1v3_button";
 const BUTTON_SET_CONTROLS_KEYBOARD: &str = "controls_keyboard";
 const TEXT_CONTROLS_KEYBOARD: &str = "controls_keyboard_result";
 
+const BUTTON_STORM: &str = "storm_toggle";
+const TEXT_STORM: &str = "storm_toggle_result";
+
+const BUTTON_BOT_DIFFICULTY: [&str; MAX_PLAYER_COUNT] = [
+    "bot_1_difficulty",
+    "bot_2_difficulty",
+    "bot_3_difficulty",
+    "bot_4_difficulty",
+];
+const BUTTON_BOT_PERSONALITY: [&str; MAX_PLAYER_COUNT] = [
+    "bot_1_personality",
+    "bot_2_personality",
+    "bot_3_personality",
+    "bot_4_personality",
+];
+const TEXT_BOT_PROFILE: [&str; MAX_PLAYER_COUNT] = [
+    "bot_1_profile_text",
+    "bot_2_profile_text",
+    "bot_3_profile_text",
+    "bot_4_profile_text",
+];
+
 #[derive(Default, Debug)]
 pub struct MainMenu {
     ui_root: Option<Entity>,
@@ -101,6 +123,11 @@ pub struct MainMenu {
     button_1v3: Option<Entity>,
     button_set_controls_keyboard: Option<Entity>,
     controls_keyboard_result: Option<Entity>,
+    button_storm: Option<Entity>,
+    text_storm: Option<Entity>,
+    button_bot_difficulty: [Option<Entity>; MAX_PLAYER_COUNT],
+    button_bot_personality: [Option<Entity>; MAX_PLAYER_COUNT],
+    text_bot_profile: [Option<Entity>; MAX_PLAYER_COUNT],
 
     init_base_rules: bool,
     init_vehicle_weapons: bool,
@@ -115,6 +142,8 @@ impl SimpleState for MainMenu {
 
         build_weapon_store(world);
 
+        build_bot_profile_store(world);
+
         let mut weapon_spawn_chances: Vec<(WeaponNames, f32)> = Vec::new();
         {
             let weapon_store_resource = world.fetch::<WeaponStoreResource>();
@@ -163,9 +192,11 @@ impl SimpleState for MainMenu {
                 game_end_condition: GameEndCondition::First,
                 max_players: INIT_PLAYER_COUNT,
                 bot_players: INIT_BOT_COUNT,
+                bot_setups: [BotSetup::default(); MAX_PLAYER_COUNT],
                 last_hit_threshold: 5.0,
                 arena_name: ArenaNames::StandardCombat,
                 p1_keyboard: true,
+                storm: None,
             });
 
             //these are only defaults if a game-mode is not selected
@@ -265,6 +296,8 @@ impl SimpleState for MainMenu {
             || self.button_1v3.is_none()
             || self.button_set_controls_keyboard.is_none()
             || self.controls_keyboard_result.is_none()
+            || self.button_storm.is_none()
+            || self.text_storm.is_none()
             || self.text_player_teams.is_none()
         {
             world.exec(|ui_finder: UiFinder<'_>| {
@@ -290,6 +323,8 @@ impl SimpleState for MainMenu {
                 self.button_1v3 = ui_finder.find(BUTTON_1V3);
                 self.button_set_controls_keyboard = ui_finder.find(BUTTON_SET_CONTROLS_KEYBOARD);
                 self.controls_keyboard_result = ui_finder.find(TEXT_CONTROLS_KEYBOARD);
+                self.button_storm = ui_finder.find(BUTTON_STORM);
+                self.text_storm = ui_finder.find(TEXT_STORM);
                 self.text_player_teams = ui_finder.find(TEXT_PLAYER_TEAMS);
             });
         }
@@ -328,6 +363,18 @@ impl SimpleState for MainMenu {
             });
         }
 
+        if self.text_bot_profile.iter().any(|entity| entity.is_none()) {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                for bot_slot in 0..MAX_PLAYER_COUNT {
+                    self.button_bot_difficulty[bot_slot] =
+                        ui_finder.find(BUTTON_BOT_DIFFICULTY[bot_slot]);
+                    self.button_bot_personality[bot_slot] =
+                        ui_finder.find(BUTTON_BOT_PERSONALITY[bot_slot]);
+                    self.text_bot_profile[bot_slot] = ui_finder.find(TEXT_BOT_PROFILE[bot_slot]);
+                }
+            });
+        }
+
         if self.text_rules.is_none() {
             world.exec(|ui_finder: UiFinder<'_>| {
                 self.text_rules = ui_finder.find(TEXT_RULES);
@@ -511,6 +558,14 @@ impl SimpleState for MainMenu {
                 }
             }
 
+            if let Some(storm_text) = self.text_storm.and_then(|entity| ui_text.get_mut(entity)) {
+                if game_mode_setup.storm.is_some() {
+                    storm_text.text = "ON".to_string();
+                } else {
+                    storm_text.text = "OFF".to_string();
+                }
+            }
+
             //Set game mode to match user input after intialization has been completed
             if let Some(player_count) = self
                 .edit_text_player_count
@@ -554,6 +609,24 @@ impl SimpleState for MainMenu {
                 }
             }
 
+            for bot_slot in 0..MAX_PLAYER_COUNT {
+                if let Some(bot_profile_text) =
+                    self.text_bot_profile[bot_slot].and_then(|entity| ui_text.get_mut(entity))
+                {
+                    if bot_slot < game_mode_setup.bot_players {
+                        let bot_setup = game_mode_setup.bot_setups[bot_slot];
+                        bot_profile_text.text = format!(
+                            "Bot {}: {:?} {:?}",
+                            bot_slot + 1,
+                            bot_setup.difficulty,
+                            bot_setup.personality
+                        );
+                    } else {
+                        bot_profile_text.text = format!("Bot {}: -", bot_slot + 1);
+                    }
+                }
+            }
+
             if let Some(game_rules) = self.text_rules.and_then(|entity| ui_text.get_mut(entity)) {
                 game_rules.text = get_game_rules_text(game_mode_setup.game_mode.clone());
             }
@@ -705,14 +778,32 @@ impl SimpleState for MainMenu {
                 let fetched_arena_store = world.try_fetch::<ArenaStoreResource>();
 
                 if let Some(mut game_mode_setup) = fetched_game_mode_setup {
+                    for bot_slot in 0..MAX_PLAYER_COUNT {
+                        let bot_setup = &mut game_mode_setup.bot_setups[bot_slot];
+
+                        if Some(target) == self.button_bot_difficulty[bot_slot] {
+                            bot_setup.difficulty = bot_setup.difficulty.next();
+                        } else if Some(target) == self.button_bot_personality[bot_slot] {
+                            bot_setup.personality = bot_setup.personality.next();
+                        }
+                    }
+
                     if Some(target) == self.button_set_controls_keyboard {
                         game_mode_setup.p1_keyboard = !game_mode_setup.p1_keyboard;
+                    } else if Some(target) == self.button_storm {
+                        //each game mode picks its own default, this overrides it for the match
+                        if game_mode_setup.storm.is_some() {
+                            game_mode_setup.storm = None;
+                        } else {
+                            game_mode_setup.storm = Some(StormSetup::default());
+                        }
                     } else if Some(target) == self.button_classic_gun_game {
                         game_mode_setup.game_mode = GameModes::ClassicGunGame;
                         game_mode_setup.match_time_limit = -1.0;
                         game_mode_setup.points_to_win = 14;
                         game_mode_setup.stock_lives = -1;
                         game_mode_setup.game_end_condition = GameEndCondition::First;
+                        game_mode_setup.storm = None;
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_deathmatch_kills {
                         game_mode_setup.game_mode = GameModes::DeathmatchKills;
@@ -720,6 +811,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = 10;
                         game_mode_setup.stock_lives = -1;
                         game_mode_setup.game_end_condition = GameEndCondition::First;
+                        game_mode_setup.storm = None;
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_deathmatch_stock {
                         game_mode_setup.game_mode = GameModes::DeathmatchStock;
@@ -727,6 +819,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = -1;
                         game_mode_setup.stock_lives = 5;
                         game_mode_setup.game_end_condition = GameEndCondition::AllButOne;
+                        game_mode_setup.storm = Some(StormSetup::default());
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_deathmatch_time {
                         game_mode_setup.game_mode = GameModes::DeathmatchTimedKD;
@@ -734,6 +827,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = -1;
                         game_mode_setup.stock_lives = -1;
                         game_mode_setup.game_end_condition = GameEndCondition::AllButOne; //but usually just ends by time
+                        game_mode_setup.storm = None;
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_king_of_the_hill {
                         game_mode_setup.game_mode = GameModes::KingOfTheHill;
@@ -741,6 +835,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = 50;
                         game_mode_setup.stock_lives = -1;
                         game_mode_setup.game_end_condition = GameEndCondition::First;
+                        game_mode_setup.storm = None;
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_combat_race {
                         game_mode_setup.game_mode = GameModes::Race;
@@ -748,6 +843,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = 10;
                         game_mode_setup.stock_lives = -1;
                         game_mode_setup.game_end_condition = GameEndCondition::AllButOneExtended; //extended for a few seconds after
+                        game_mode_setup.storm = None;
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_capture_the_flag {
                         game_mode_setup.game_mode = GameModes::CaptureTheFlag;
@@ -755,6 +851,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = 10;
                         game_mode_setup.stock_lives = -1;
                         game_mode_setup.game_end_condition = GameEndCondition::First; //extended for a few seconds after
+                        game_mode_setup.storm = None;
                         self.init_base_rules = true;
                     } else if Some(target) == self.button_survival_waves {
                         game_mode_setup.game_mode = GameModes::SurvivalWaves;
@@ -762,6 +859,7 @@ impl SimpleState for MainMenu {
                         game_mode_setup.points_to_win = 10;
                         game_mode_setup.stock_lives = 3;
                         game_mode_setup.game_end_condition = GameEndCondition::AllButOneExtended; //extended for a few seconds after
+                        game_mode_setup.storm = Some(StormSetup::default());
                         self.init_base_rules = true;
                     }
 
@@ -956,7 +1054,12 @@ impl SimpleState for MainMenu {
         self.button_2v2 = None;
         self.button_1v3 = None;
         self.button_set_controls_keyboard = None;
+        self.button_storm = None;
+        self.text_storm = None;
         self.text_player_teams = None;
+        self.button_bot_difficulty = [None; MAX_PLAYER_COUNT];
+        self.button_bot_personality = [None; MAX_PLAYER_COUNT];
+        self.text_bot_profile = [None; MAX_PLAYER_COUNT];
     }
 }
 
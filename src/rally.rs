@@ -19,12 +19,14 @@ use amethyst::{
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
+use crate::arena_validation::validate_arena;
 use crate::pause::PauseMenuState;
 use crate::score_screen::ScoreScreen;
 
 use crate::resources::{
-    initialize_weapon_fire_resource, ArenaNavMesh, ArenaNavMeshFinal, GameModeSetup, GameScore,
-    GameTeamSetup, GameVehicleSetup, WeaponFireResource,
+    initialize_weapon_fire_resource, ArenaNavMesh, ArenaNavMeshFinal, ArenaStorm, BotDebugOverlay,
+    BotSetup, GameModeSetup, GameModes, GameScore, GameTeamSetup, GameVehicleSetup, RacingLine,
+    WeaponFireResource,
 };
 
 use crate::entities::{
@@ -33,15 +35,17 @@ use crate::entities::{
 };
 
 use crate::components::{
-    get_none_vehicle, ArenaElement, ArenaNames, ArenaProperties, ArenaStoreResource, Armor, Health,
-    Hitbox, Particles, Player, PlayerWeaponIcon, Repair, Shield, Vehicle, WeaponArray, WeaponFire,
+    get_bot_profile, get_none_vehicle, ArenaElement, ArenaNames, ArenaProperties,
+    ArenaStoreResource, Armor, BotProfile, BotProfileStoreResource, Deployable, Health, Hitbox,
+    Particles, Player, PlayerWeaponIcon, Repair, Shield, Vehicle, WeaponArray, WeaponFire,
 };
 
 use crate::systems::{
-    CameraTrackingSystem, CollisionVehToVehSystem, CollisionWeaponFireHitboxSystem,
-    MoveParticlesSystem, MoveWeaponFireSystem, PathingLinesSystem, VehicleMoveSystem,
-    VehicleShieldArmorHealthSystem, VehicleStatusSystem, VehicleTrackingSystem,
-    VehicleWeaponsSystem,
+    ArenaNavMeshSystem, ArenaStormSystem, BotAiSystem, BotDebugOverlaySystem, CameraTrackingSystem,
+    CollisionVehToVehSystem, CollisionWeaponFireHitboxSystem, CollisionWeaponFireWeaponFireSystem,
+    DeployableSystem, MoveParticlesSystem, MoveWeaponFireSystem, PathingLinesSystem,
+    VehicleAbilitySystem, VehicleMoveSystem, VehicleShieldArmorHealthSystem, VehicleStatusSystem,
+    VehicleTrackingSystem, VehicleWeaponsSystem, WeaponBeamSystem,
 };
 
 pub const PLAYER_CAMERA: bool = false;
@@ -87,6 +91,7 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
         world.register::<UiTransform>();
 
         world.register::<Armor>();
+        world.register::<Deployable>();
         world.register::<Health>();
         world.register::<ArenaElement>();
         world.register::<Hitbox>();
@@ -131,10 +136,19 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
         world.insert(ArenaNavMesh {
             vertices: Vec::new(),
             triangles: Vec::new(),
+            dirty: false,
+            obstacles: Vec::new(),
+            blocked_triangles: Vec::new(),
         });
 
         world.insert(ArenaNavMeshFinal { mesh: None });
 
+        world.insert(RacingLine::default());
+
+        world.insert(BotDebugOverlay::default());
+
+        world.insert(ArenaStorm::default());
+
         intialize_arena(
             world,
             self.sprite_sheet_handle.clone().unwrap(),
@@ -143,18 +157,29 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
 
         let max_players;
         let bot_players;
+        let bot_setups;
         let arena_name;
+        let checkpoint_count;
         {
             let fetched_game_mode_setup = world.try_fetch::<GameModeSetup>();
 
             if let Some(game_mode_setup) = fetched_game_mode_setup {
                 max_players = game_mode_setup.max_players;
                 bot_players = game_mode_setup.bot_players;
+                bot_setups = game_mode_setup.bot_setups;
                 arena_name = game_mode_setup.arena_name.clone();
+
+                if game_mode_setup.game_mode == GameModes::Race {
+                    checkpoint_count = Some(game_mode_setup.checkpoint_count);
+                } else {
+                    checkpoint_count = None;
+                }
             } else {
                 max_players = 4;
                 bot_players = 3;
+                bot_setups = [BotSetup::default(); 4];
                 arena_name = ArenaNames::OpenEmptyMap;
+                checkpoint_count = None;
             }
         }
 
@@ -172,6 +197,10 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
             }
         }
 
+        for arena_error in validate_arena(arena_name, &arena_properties, checkpoint_count).iter() {
+            log::warn!("{}", arena_error);
+        }
+
         let player_to_team;
         {
             let fetched_game_team_setup = world.try_fetch::<GameTeamSetup>();
@@ -217,6 +246,29 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
                 vehicle_stats,
             );
 
+            if is_bot {
+                let bot_setup = bot_setups[player_index - (max_players - bot_players)];
+
+                let bot_profile;
+                {
+                    let fetched_bot_profile_store = world.try_fetch::<BotProfileStoreResource>();
+
+                    if let Some(bot_profile_store) = fetched_bot_profile_store {
+                        bot_profile = get_bot_profile(
+                            &bot_profile_store,
+                            bot_setup.difficulty,
+                            bot_setup.personality,
+                        );
+                    } else {
+                        bot_profile = BotProfile::default();
+                    }
+                }
+
+                if let Some(bot_player) = world.write_storage::<Player>().get_mut(player) {
+                    bot_player.bot_profile = bot_profile;
+                }
+            }
+
             if PLAYER_CAMERA && !is_bot {
                 initialize_camera_to_player(world, &arena_properties, player);
             }
@@ -228,15 +280,46 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
 
         // Create the `DispatcherBuilder` and register some `System`s that should only run for this `State`.
         let mut dispatcher_builder = DispatcherBuilder::new();
+        dispatcher_builder.add(ArenaStormSystem::default(), "arena_storm_system", &[]);
         dispatcher_builder.add(VehicleTrackingSystem, "vehicle_tracking_system", &[]);
+        dispatcher_builder.add(BotAiSystem::default(), "bot_ai_system", &[]);
         dispatcher_builder.add(VehicleMoveSystem::default(), "vehicle_move_system", &[]);
+        dispatcher_builder.add(
+            VehicleAbilitySystem::default(),
+            "vehicle_ability_system",
+            &[],
+        );
 
         dispatcher_builder.add(VehicleWeaponsSystem, "vehicle_weapons_system", &[]);
+        dispatcher_builder.add(
+            WeaponBeamSystem::default(),
+            "weapon_beam_system",
+            &["vehicle_weapons_system"],
+        );
         dispatcher_builder.add(
             CollisionWeaponFireHitboxSystem::default(),
             "collision_weapon_fire_hitbox_system",
             &[],
         );
+        dispatcher_builder.add(
+            CollisionWeaponFireWeaponFireSystem::default(),
+            "collision_weapon_fire_weapon_fire_system",
+            &["collision_weapon_fire_hitbox_system"],
+        );
+        dispatcher_builder.add(
+            DeployableSystem::default(),
+            "deployable_system",
+            &[
+                "vehicle_weapons_system",
+                "collision_weapon_fire_hitbox_system",
+                "collision_weapon_fire_weapon_fire_system",
+            ],
+        );
+        dispatcher_builder.add(
+            ArenaNavMeshSystem::default(),
+            "arena_nav_mesh_system",
+            &["collision_weapon_fire_hitbox_system"],
+        );
         dispatcher_builder.add(
             MoveWeaponFireSystem::default(),
             "move_weapon_fire_system",
@@ -259,6 +342,12 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
 
         dispatcher_builder.add(PathingLinesSystem::default(), "pathing_lines_system", &[]);
 
+        dispatcher_builder.add(
+            BotDebugOverlaySystem::default(),
+            "bot_debug_overlay_system",
+            &["pathing_lines_system"],
+        );
+
         dispatcher_builder.add(
             CameraTrackingSystem::default(),
             "camera_tracking_system",
@@ -305,7 +394,7 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
 
     fn handle_event(
         &mut self,
-        _: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
         match &event {
@@ -316,6 +405,10 @@ impl<'a, 'b> SimpleState for GameplayState<'a, 'b> {
                 } else if is_key_down(&event, VirtualKeyCode::Escape) {
                     log::info!("[Trans::Push] Pausing Game!");
                     Trans::Push(Box::new(PauseMenuState::default()))
+                } else if is_key_down(&event, VirtualKeyCode::F3) {
+                    let mut bot_debug_overlay = data.world.write_resource::<BotDebugOverlay>();
+                    bot_debug_overlay.enabled = !bot_debug_overlay.enabled;
+                    Trans::None
                 } else {
                     Trans::None
                 }
@@ -1,12 +1,1073 @@
-use navmesh::NavMesh;
+use navmesh::{NavMesh, NavTriangle, NavVec3};
+
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+
+use crate::components::{ArenaElement, ArenaFloor, ArenaProperties, HitboxShape, ObstacleType};
+
+pub const NAV_MESH_OFFSET: f32 = 7.0; //clearance kept from walls, about a vehicle's half width
+const NAV_MESH_CIRCLE_SEGMENTS: usize = 12;
+const NAV_MESH_MAX_REFINE_PASSES: usize = 24;
+const NAV_MESH_POINT_EPSILON: f32 = 0.01;
+const NAV_MESH_OBSTACLE_MATCH_EPSILON: f32 = 0.1;
 
 //These structures are just for my game, so that I can work with the data in f32 and usize types
 pub struct ArenaNavMesh {
     pub vertices: Vec<(f32, f32, f32)>,        //(x,y,z)
     pub triangles: Vec<(usize, usize, usize)>, //(vertex 1, vertex_2, vertex_3)
+    pub dirty: bool,                           //walls changed, updated by the ArenaNavMeshSystem
+    pub obstacles: Vec<NavMeshObstacle>,       //walls the mesh was triangulated around
+    //floor triangles under walls, with the indices of the obstacles covering them
+    pub blocked_triangles: Vec<((usize, usize, usize), Vec<usize>)>,
 }
 
 //This is the one actually used by the navmesh library, using special nav mesh library types
 pub struct ArenaNavMeshFinal {
     pub mesh: Option<NavMesh>,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NavMeshObstacle {
+    Rectangle {
+        x: f32,
+        y: f32,
+        half_width: f32,
+        half_height: f32,
+        rotation: f32, //radians
+    },
+    Circle {
+        x: f32,
+        y: f32,
+        radius: f32,
+    },
+}
+
+pub fn get_nav_mesh_obstacle(arena_element: &ArenaElement) -> Option<NavMeshObstacle> {
+    if arena_element.obstacle_type == ObstacleType::Wall {
+        if arena_element.hitbox.shape == HitboxShape::Circle {
+            Some(NavMeshObstacle::Circle {
+                x: arena_element.x,
+                y: arena_element.y,
+                radius: arena_element.hitbox.width / 2.0,
+            })
+        } else {
+            Some(NavMeshObstacle::Rectangle {
+                x: arena_element.x,
+                y: arena_element.y,
+                half_width: arena_element.hitbox.width / 2.0,
+                half_height: arena_element.hitbox.height / 2.0,
+                rotation: arena_element.hitbox.angle,
+            })
+        }
+    } else {
+        None
+    }
+}
+
+//Walls as defined by the arena properties, before any have been destroyed
+pub fn get_arena_nav_mesh_obstacles(arena_properties: &ArenaProperties) -> Vec<NavMeshObstacle> {
+    let mut obstacles: Vec<NavMeshObstacle> = Vec::new();
+
+    for arena_rect in arena_properties.arena_rectangles.iter() {
+        if arena_rect.obstacle_type == ObstacleType::Wall {
+            obstacles.push(NavMeshObstacle::Rectangle {
+                x: arena_rect.x,
+                y: arena_rect.y,
+                half_width: arena_rect.width / 2.0,
+                half_height: arena_rect.height / 2.0,
+                rotation: arena_rect.rotation / 180.0 * PI,
+            });
+        }
+    }
+
+    for arena_circle in arena_properties.arena_circles.iter() {
+        if arena_circle.obstacle_type == ObstacleType::Wall {
+            obstacles.push(NavMeshObstacle::Circle {
+                x: arena_circle.x,
+                y: arena_circle.y,
+                radius: arena_circle.radius,
+            });
+        }
+    }
+
+    obstacles
+}
+
+//Triangulates the walkable floor minus the walls, with every outline kept as a triangle edge
+//Outlines are pushed in/out by NAV_MESH_OFFSET so paths keep a vehicle's width from any wall
+//
+//This is a load-time build: the triangulation is roughly quadratic in the number of outline
+//points and may take up to NAV_MESH_MAX_REFINE_PASSES passes to recover every outline edge.
+//Triangles under walls are kept aside so that destroyed walls can be opened up at runtime
+//with remove_nav_mesh_obstacles, without triangulating again.
+pub fn build_arena_nav_mesh(
+    arena_width: f32,
+    arena_height: f32,
+    floors: &[ArenaFloor],
+    obstacles: &[NavMeshObstacle],
+) -> ArenaNavMesh {
+    let debug_line_z = 0.0;
+
+    //Walkable floor pieces, arenas without any floor defined are one open rectangle
+    let mut floor_rects: Vec<ArenaFloor> = floors.to_vec();
+
+    if floor_rects.is_empty() {
+        floor_rects.push(ArenaFloor {
+            x: arena_width / 2.0,
+            y: arena_height / 2.0,
+            width: arena_width,
+            height: arena_height,
+        });
+    }
+
+    let floor_polygons: Vec<Vec<(f32, f32)>> = floor_rects
+        .iter()
+        .map(|floor| {
+            get_rectangle_polygon(floor.x, floor.y, floor.width / 2.0, floor.height / 2.0, 0.0)
+        })
+        .collect();
+
+    //The floor's outer edges act as walls, edges shared between floor pieces do not
+    //They come after the arena's own walls and can never be removed
+    let wall_count = obstacles.len();
+    let mut all_obstacles = obstacles.to_vec();
+    all_obstacles.append(&mut get_floor_edge_obstacles(&floor_rects));
+
+    let obstacle_polygons: Vec<Vec<(f32, f32)>> = all_obstacles
+        .iter()
+        .map(|obstacle| get_obstacle_polygon(obstacle, NAV_MESH_OFFSET))
+        .collect();
+
+    let mut outline_edges: Vec<((f32, f32), (f32, f32))> = Vec::new();
+
+    for polygon in floor_polygons.iter().chain(obstacle_polygons.iter()) {
+        for idx in 0..polygon.len() {
+            outline_edges.push((polygon[idx], polygon[(idx + 1) % polygon.len()]));
+        }
+    }
+
+    let (mut nav_mesh_points, mut constraints) = split_outline_edges(&outline_edges);
+
+    //Delaunay triangulate, splitting any outline edge the triangulation cut across
+    //until every outline edge is an edge of the mesh
+    let mut triangles: Vec<(usize, usize, usize)>;
+    let mut refine_pass = 0;
+
+    loop {
+        triangles = delaunay_triangulate(&nav_mesh_points.points);
+
+        let mut mesh_edges: HashSet<(usize, usize)> = HashSet::new();
+        for (v1, v2, v3) in triangles.iter() {
+            mesh_edges.insert((*v1, *v2));
+            mesh_edges.insert((*v2, *v3));
+            mesh_edges.insert((*v3, *v1));
+        }
+
+        let mut missing_edges = false;
+        let mut new_constraints: Vec<(usize, usize)> = Vec::new();
+
+        for (v1, v2) in constraints.iter() {
+            if mesh_edges.contains(&(*v1, *v2)) || mesh_edges.contains(&(*v2, *v1)) {
+                new_constraints.push((*v1, *v2));
+            } else {
+                missing_edges = true;
+
+                let points = &nav_mesh_points.points;
+                let midpoint = (
+                    (points[*v1].0 + points[*v2].0) / 2.0,
+                    (points[*v1].1 + points[*v2].1) / 2.0,
+                );
+                let mid_idx = nav_mesh_points.add(midpoint);
+
+                new_constraints.push((*v1, mid_idx));
+                new_constraints.push((mid_idx, *v2));
+            }
+        }
+
+        if !missing_edges {
+            break;
+        } else if refine_pass >= NAV_MESH_MAX_REFINE_PASSES {
+            log::warn!("Nav mesh outline edges could not all be recovered");
+            break;
+        }
+
+        constraints = new_constraints;
+        refine_pass += 1;
+    }
+
+    //Keep triangles on the floor and outside of every wall,
+    //triangles under the arena's walls are set aside in case the walls get destroyed
+    let points = &nav_mesh_points.points;
+    let mut nav_mesh_vertices: Vec<(f32, f32, f32)> = Vec::new();
+    let mut nav_mesh_triangles: Vec<(usize, usize, usize)> = Vec::new();
+    let mut blocked_triangles: Vec<((usize, usize, usize), Vec<usize>)> = Vec::new();
+    let mut vertex_map: HashMap<usize, usize> = HashMap::new();
+
+    for (v1, v2, v3) in triangles.iter() {
+        if *v1 >= points.len() || *v2 >= points.len() || *v3 >= points.len() {
+            continue; //attached to the temporary super triangle
+        }
+
+        if orientation(points[*v1], points[*v2], points[*v3]).abs() < 0.0001 {
+            continue; //degenerate sliver
+        }
+
+        let centroid = (
+            (points[*v1].0 + points[*v2].0 + points[*v3].0) / 3.0,
+            (points[*v1].1 + points[*v2].1 + points[*v3].1) / 3.0,
+        );
+
+        let on_floor = floor_polygons
+            .iter()
+            .any(|polygon| point_in_convex_polygon(polygon, centroid));
+
+        if !on_floor {
+            continue;
+        }
+
+        let covering_obstacles: Vec<usize> = obstacle_polygons
+            .iter()
+            .enumerate()
+            .filter(|(_, polygon)| point_in_convex_polygon(polygon, centroid))
+            .map(|(obstacle_idx, _)| obstacle_idx)
+            .collect();
+
+        if covering_obstacles
+            .iter()
+            .any(|obstacle_idx| *obstacle_idx >= wall_count)
+        {
+            continue; //past the floor's outer edges
+        }
+
+        let mut mesh_indices = [0; 3];
+
+        for (idx, point_idx) in [*v1, *v2, *v3].iter().enumerate() {
+            mesh_indices[idx] = *vertex_map.entry(*point_idx).or_insert_with(|| {
+                let point = points[*point_idx];
+                nav_mesh_vertices.push((point.0, point.1, debug_line_z));
+                nav_mesh_vertices.len() - 1
+            });
+        }
+
+        let triangle = (mesh_indices[0], mesh_indices[1], mesh_indices[2]);
+
+        if covering_obstacles.is_empty() {
+            nav_mesh_triangles.push(triangle);
+        } else {
+            blocked_triangles.push((triangle, covering_obstacles));
+        }
+    }
+
+    ArenaNavMesh {
+        vertices: nav_mesh_vertices,
+        triangles: nav_mesh_triangles,
+        dirty: false,
+        obstacles: obstacles.to_vec(),
+        blocked_triangles,
+    }
+}
+
+//Runtime update after walls have been destroyed, cheap enough to run mid-match:
+//every triangle was already cut around every wall, so opening a wall only moves the
+//triangles it covered back into the walkable set. Returns false if a remaining obstacle
+//was never part of the mesh, which needs a full build_arena_nav_mesh instead.
+pub fn remove_nav_mesh_obstacles(
+    arena_nav_mesh: &mut ArenaNavMesh,
+    remaining_obstacles: &[NavMeshObstacle],
+) -> bool {
+    let mut obstacle_removed = vec![true; arena_nav_mesh.obstacles.len()];
+
+    for remaining_obstacle in remaining_obstacles.iter() {
+        let matching_idx = arena_nav_mesh
+            .obstacles
+            .iter()
+            .position(|obstacle| same_nav_mesh_obstacle(obstacle, remaining_obstacle));
+
+        if let Some(matching_idx) = matching_idx {
+            obstacle_removed[matching_idx] = false;
+        } else {
+            return false;
+        }
+    }
+
+    let mut still_blocked: Vec<((usize, usize, usize), Vec<usize>)> = Vec::new();
+
+    for (triangle, covering_obstacles) in arena_nav_mesh.blocked_triangles.drain(..) {
+        if covering_obstacles
+            .iter()
+            .all(|obstacle_idx| obstacle_removed[*obstacle_idx])
+        {
+            arena_nav_mesh.triangles.push(triangle);
+        } else {
+            still_blocked.push((triangle, covering_obstacles));
+        }
+    }
+
+    arena_nav_mesh.blocked_triangles = still_blocked;
+
+    true
+}
+
+//Arena elements rebuild their obstacles from sprite scales, so allow for rounding
+fn same_nav_mesh_obstacle(a: &NavMeshObstacle, b: &NavMeshObstacle) -> bool {
+    let close = |a: f32, b: f32| (a - b).abs() < NAV_MESH_OBSTACLE_MATCH_EPSILON;
+
+    match (a, b) {
+        (
+            NavMeshObstacle::Rectangle {
+                x: a_x,
+                y: a_y,
+                half_width: a_half_width,
+                half_height: a_half_height,
+                rotation: a_rotation,
+            },
+            NavMeshObstacle::Rectangle {
+                x: b_x,
+                y: b_y,
+                half_width: b_half_width,
+                half_height: b_half_height,
+                rotation: b_rotation,
+            },
+        ) => {
+            close(*a_x, *b_x)
+                && close(*a_y, *b_y)
+                && close(*a_half_width, *b_half_width)
+                && close(*a_half_height, *b_half_height)
+                && close(*a_rotation, *b_rotation)
+        }
+        (
+            NavMeshObstacle::Circle {
+                x: a_x,
+                y: a_y,
+                radius: a_radius,
+            },
+            NavMeshObstacle::Circle {
+                x: b_x,
+                y: b_y,
+                radius: b_radius,
+            },
+        ) => close(*a_x, *b_x) && close(*a_y, *b_y) && close(*a_radius, *b_radius),
+        _ => false,
+    }
+}
+
+//Thin walls along the parts of each floor edge that no other floor piece continues past
+fn get_floor_edge_obstacles(floors: &[ArenaFloor]) -> Vec<NavMeshObstacle> {
+    let mut edge_obstacles: Vec<NavMeshObstacle> = Vec::new();
+
+    for floor in floors.iter() {
+        let left = floor.x - floor.width / 2.0;
+        let right = floor.x + floor.width / 2.0;
+        let bottom = floor.y - floor.height / 2.0;
+        let top = floor.y + floor.height / 2.0;
+
+        //(fixed coordinate, outward probe, is vertical edge)
+        let edges = [
+            (left, left - NAV_MESH_POINT_EPSILON, true),
+            (right, right + NAV_MESH_POINT_EPSILON, true),
+            (bottom, bottom - NAV_MESH_POINT_EPSILON, false),
+            (top, top + NAV_MESH_POINT_EPSILON, false),
+        ];
+
+        for (fixed, probe, is_vertical) in edges.iter() {
+            let mut exposed: Vec<(f32, f32)>;
+            if *is_vertical {
+                exposed = vec![(bottom, top)];
+            } else {
+                exposed = vec![(left, right)];
+            }
+
+            //remove the stretches where another floor piece lies just outside this edge
+            for other in floors.iter() {
+                let other_left = other.x - other.width / 2.0;
+                let other_right = other.x + other.width / 2.0;
+                let other_bottom = other.y - other.height / 2.0;
+                let other_top = other.y + other.height / 2.0;
+
+                let covered;
+                if *is_vertical {
+                    if other_left < *probe && *probe < other_right {
+                        covered = Some((other_bottom, other_top));
+                    } else {
+                        covered = None;
+                    }
+                } else {
+                    if other_bottom < *probe && *probe < other_top {
+                        covered = Some((other_left, other_right));
+                    } else {
+                        covered = None;
+                    }
+                }
+
+                if let Some((covered_min, covered_max)) = covered {
+                    let mut remaining: Vec<(f32, f32)> = Vec::new();
+
+                    for (exposed_min, exposed_max) in exposed.iter() {
+                        if covered_min > *exposed_min {
+                            remaining.push((*exposed_min, covered_min.min(*exposed_max)));
+                        }
+                        if covered_max < *exposed_max {
+                            remaining.push((covered_max.max(*exposed_min), *exposed_max));
+                        }
+                    }
+
+                    exposed = remaining
+                        .into_iter()
+                        .filter(|(min, max)| max - min > NAV_MESH_POINT_EPSILON)
+                        .collect();
+                }
+            }
+
+            for (exposed_min, exposed_max) in exposed.iter() {
+                let middle = (exposed_min + exposed_max) / 2.0;
+                let half_length = (exposed_max - exposed_min) / 2.0;
+
+                if *is_vertical {
+                    edge_obstacles.push(NavMeshObstacle::Rectangle {
+                        x: *fixed,
+                        y: middle,
+                        half_width: 0.0,
+                        half_height: half_length,
+                        rotation: 0.0,
+                    });
+                } else {
+                    edge_obstacles.push(NavMeshObstacle::Rectangle {
+                        x: middle,
+                        y: *fixed,
+                        half_width: half_length,
+                        half_height: 0.0,
+                        rotation: 0.0,
+                    });
+                }
+            }
+        }
+    }
+
+    edge_obstacles
+}
+
+//Counter-clockwise corners of a rectangle rotated about its center
+fn get_rectangle_polygon(
+    x: f32,
+    y: f32,
+    half_width: f32,
+    half_height: f32,
+    rotation: f32,
+) -> Vec<(f32, f32)> {
+    let cos = rotation.cos();
+    let sin = rotation.sin();
+
+    [
+        (-half_width, -half_height),
+        (half_width, -half_height),
+        (half_width, half_height),
+        (-half_width, half_height),
+    ]
+    .iter()
+    .map(|(local_x, local_y)| {
+        (
+            x + local_x * cos - local_y * sin,
+            y + local_x * sin + local_y * cos,
+        )
+    })
+    .collect()
+}
+
+//Counter-clockwise outline of an obstacle grown by the clearance
+//Circles become polygons that fully contain the grown circle
+fn get_obstacle_polygon(obstacle: &NavMeshObstacle, clearance: f32) -> Vec<(f32, f32)> {
+    match obstacle {
+        NavMeshObstacle::Rectangle {
+            x,
+            y,
+            half_width,
+            half_height,
+            rotation,
+        } => get_rectangle_polygon(
+            *x,
+            *y,
+            half_width + clearance,
+            half_height + clearance,
+            *rotation,
+        ),
+        NavMeshObstacle::Circle { x, y, radius } => {
+            let segments = NAV_MESH_CIRCLE_SEGMENTS as f32;
+            let outer_radius = (radius + clearance) / (PI / segments).cos();
+
+            (0..NAV_MESH_CIRCLE_SEGMENTS)
+                .map(|idx| {
+                    let angle = 2.0 * PI * (idx as f32) / segments;
+                    (
+                        x + outer_radius * angle.cos(),
+                        y + outer_radius * angle.sin(),
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+//Break outline edges wherever they cross or touch each other, so they can all be mesh edges
+//Every pair of edges is compared, edges whose bounds don't overlap are skipped early
+fn split_outline_edges(
+    outline_edges: &[((f32, f32), (f32, f32))],
+) -> (NavMeshPoints, Vec<(usize, usize)>) {
+    let mut nav_mesh_points = NavMeshPoints::default();
+    let mut constraints: Vec<(usize, usize)> = Vec::new();
+    let mut constraint_set: HashSet<(usize, usize)> = HashSet::new();
+
+    //(min x, min y, max x, max y)
+    let edge_bounds: Vec<(f32, f32, f32, f32)> = outline_edges
+        .iter()
+        .map(|(p1, p2)| {
+            (
+                p1.0.min(p2.0) - NAV_MESH_POINT_EPSILON,
+                p1.1.min(p2.1) - NAV_MESH_POINT_EPSILON,
+                p1.0.max(p2.0) + NAV_MESH_POINT_EPSILON,
+                p1.1.max(p2.1) + NAV_MESH_POINT_EPSILON,
+            )
+        })
+        .collect();
+
+    for (idx, (p1, p2)) in outline_edges.iter().enumerate() {
+        let mut splits: Vec<f32> = vec![0.0, 1.0];
+        let bounds = edge_bounds[idx];
+
+        for (other_idx, (q1, q2)) in outline_edges.iter().enumerate() {
+            let other_bounds = edge_bounds[other_idx];
+
+            if idx == other_idx
+                || other_bounds.0 > bounds.2
+                || other_bounds.2 < bounds.0
+                || other_bounds.1 > bounds.3
+                || other_bounds.3 < bounds.1
+            {
+                continue;
+            }
+
+            if let Some(t) = segment_intersection(*p1, *p2, *q1, *q2) {
+                splits.push(t);
+            }
+
+            for q in [*q1, *q2].iter() {
+                if let Some(t) = point_on_segment(*p1, *p2, *q) {
+                    splits.push(t);
+                }
+            }
+        }
+
+        //degenerate edges can give NaN splits, drop them so sorting can't panic
+        splits.retain(|t| t.is_finite());
+        splits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut prev_idx: Option<usize> = None;
+
+        for t in splits.iter() {
+            let point = (p1.0 + (p2.0 - p1.0) * t, p1.1 + (p2.1 - p1.1) * t);
+            let point_idx = nav_mesh_points.add(point);
+
+            if let Some(prev_idx) = prev_idx {
+                if prev_idx != point_idx
+                    && !constraint_set.contains(&(prev_idx, point_idx))
+                    && !constraint_set.contains(&(point_idx, prev_idx))
+                {
+                    constraints.push((prev_idx, point_idx));
+                    constraint_set.insert((prev_idx, point_idx));
+                }
+            }
+
+            prev_idx = Some(point_idx);
+        }
+    }
+
+    (nav_mesh_points, constraints)
+}
+
+//Parameter t along p1->p2 where it crosses q1->q2
+fn segment_intersection(
+    p1: (f32, f32),
+    p2: (f32, f32),
+    q1: (f32, f32),
+    q2: (f32, f32),
+) -> Option<f32> {
+    let r = (p2.0 - p1.0, p2.1 - p1.1);
+    let s = (q2.0 - q1.0, q2.1 - q1.1);
+
+    let denom = r.0 * s.1 - r.1 * s.0;
+
+    if denom.abs() < 0.000001 {
+        return None; //parallel, touching endpoints are found by point_on_segment
+    }
+
+    let qp = (q1.0 - p1.0, q1.1 - p1.1);
+    let t = (qp.0 * s.1 - qp.1 * s.0) / denom;
+    let u = (qp.0 * r.1 - qp.1 * r.0) / denom;
+
+    if t > 0.0 && t < 1.0 && u >= 0.0 && u <= 1.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+//Parameter t along p1->p2 of a point lying on it
+fn point_on_segment(p1: (f32, f32), p2: (f32, f32), q: (f32, f32)) -> Option<f32> {
+    let r = (p2.0 - p1.0, p2.1 - p1.1);
+    let length_sq = r.0.powi(2) + r.1.powi(2);
+
+    if length_sq < NAV_MESH_POINT_EPSILON.powi(2) {
+        return None;
+    }
+
+    let t = ((q.0 - p1.0) * r.0 + (q.1 - p1.1) * r.1) / length_sq;
+
+    let closest = (p1.0 + r.0 * t, p1.1 + r.1 * t);
+    let dist = (q.0 - closest.0).hypot(q.1 - closest.1);
+
+    if t > 0.0 && t < 1.0 && dist < NAV_MESH_POINT_EPSILON {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+//Triangulation points, bucketed by position so duplicates are found without a full scan
+#[derive(Default)]
+struct NavMeshPoints {
+    points: Vec<(f32, f32)>,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl NavMeshPoints {
+    //Index of the point, reusing any existing point at the same spot
+    fn add(&mut self, point: (f32, f32)) -> usize {
+        let cell_x = (point.0 / NAV_MESH_POINT_EPSILON).floor() as i32;
+        let cell_y = (point.1 / NAV_MESH_POINT_EPSILON).floor() as i32;
+
+        //a matching point can only be in this cell or a neighboring one
+        for neighbor_x in (cell_x - 1)..=(cell_x + 1) {
+            for neighbor_y in (cell_y - 1)..=(cell_y + 1) {
+                if let Some(cell_points) = self.cells.get(&(neighbor_x, neighbor_y)) {
+                    for idx in cell_points.iter() {
+                        let existing = self.points[*idx];
+
+                        if (existing.0 - point.0).abs() < NAV_MESH_POINT_EPSILON
+                            && (existing.1 - point.1).abs() < NAV_MESH_POINT_EPSILON
+                        {
+                            return *idx;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.points.push(point);
+        let idx = self.points.len() - 1;
+        self.cells
+            .entry((cell_x, cell_y))
+            .or_insert_with(Vec::new)
+            .push(idx);
+
+        idx
+    }
+}
+
+//Bowyer-Watson triangulation, all triangles are counter-clockwise
+//Indices at or past points.len() belong to the temporary super triangle
+fn delaunay_triangulate(points: &[(f32, f32)]) -> Vec<(usize, usize, usize)> {
+    let mut min_x = std::f32::MAX;
+    let mut min_y = std::f32::MAX;
+    let mut max_x = std::f32::MIN;
+    let mut max_y = std::f32::MIN;
+
+    for (x, y) in points.iter() {
+        min_x = min_x.min(*x);
+        min_y = min_y.min(*y);
+        max_x = max_x.max(*x);
+        max_y = max_y.max(*y);
+    }
+
+    let extent = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let mid_x = (min_x + max_x) / 2.0;
+    let mid_y = (min_y + max_y) / 2.0;
+
+    let mut vertices = points.to_vec();
+    let super_idx = vertices.len();
+    vertices.push((mid_x - 20.0 * extent, mid_y - extent));
+    vertices.push((mid_x + 20.0 * extent, mid_y - extent));
+    vertices.push((mid_x, mid_y + 20.0 * extent));
+
+    let mut triangles: Vec<(usize, usize, usize)> = vec![(super_idx, super_idx + 1, super_idx + 2)];
+
+    for point_idx in 0..points.len() {
+        let point = vertices[point_idx];
+
+        let containing = triangles.iter().position(|(v1, v2, v3)| {
+            orientation(vertices[*v1], vertices[*v2], point) >= 0.0
+                && orientation(vertices[*v2], vertices[*v3], point) >= 0.0
+                && orientation(vertices[*v3], vertices[*v1], point) >= 0.0
+        });
+
+        let start_idx;
+        if let Some(containing) = containing {
+            start_idx = containing;
+        } else {
+            continue; //only possible through rounding, leave the point out
+        }
+
+        //edge -> triangle for every triangle that could join the cavity,
+        //the triangle across an edge owns the reversed edge
+        let mut edge_owners: HashMap<(usize, usize), usize> = HashMap::new();
+        for (tri_idx, (v1, v2, v3)) in triangles.iter().enumerate() {
+            if tri_idx != start_idx
+                && !in_circumcircle(vertices[*v1], vertices[*v2], vertices[*v3], point)
+            {
+                continue;
+            }
+
+            edge_owners.insert((*v1, *v2), tri_idx);
+            edge_owners.insert((*v2, *v3), tri_idx);
+            edge_owners.insert((*v3, *v1), tri_idx);
+        }
+
+        //grow the cavity outwards from the containing triangle
+        let mut in_cavity = vec![false; triangles.len()];
+        in_cavity[start_idx] = true;
+        let mut to_visit = vec![start_idx];
+
+        while let Some(tri_idx) = to_visit.pop() {
+            let (v1, v2, v3) = triangles[tri_idx];
+
+            for (e1, e2) in [(v1, v2), (v2, v3), (v3, v1)].iter() {
+                if let Some(neighbor_idx) = edge_owners.get(&(*e2, *e1)) {
+                    if !in_cavity[*neighbor_idx] {
+                        in_cavity[*neighbor_idx] = true;
+                        to_visit.push(*neighbor_idx);
+                    }
+                }
+            }
+        }
+
+        //rounding can make the cavity wrap around the point,
+        //drop triangles until every cavity edge faces the new point
+        let mut cavity_changed = true;
+        while cavity_changed {
+            cavity_changed = false;
+
+            for tri_idx in 0..triangles.len() {
+                if !in_cavity[tri_idx] || tri_idx == start_idx {
+                    continue;
+                }
+
+                let (v1, v2, v3) = triangles[tri_idx];
+
+                for (e1, e2) in [(v1, v2), (v2, v3), (v3, v1)].iter() {
+                    let boundary_edge = match edge_owners.get(&(*e2, *e1)) {
+                        Some(neighbor_idx) => !in_cavity[*neighbor_idx],
+                        None => true,
+                    };
+
+                    if boundary_edge && orientation(vertices[*e1], vertices[*e2], point) <= 0.0 {
+                        in_cavity[tri_idx] = false;
+                        cavity_changed = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut kept_triangles: Vec<(usize, usize, usize)> = Vec::new();
+
+        for (tri_idx, (v1, v2, v3)) in triangles.iter().enumerate() {
+            if !in_cavity[tri_idx] {
+                kept_triangles.push((*v1, *v2, *v3));
+                continue;
+            }
+
+            for (e1, e2) in [(*v1, *v2), (*v2, *v3), (*v3, *v1)].iter() {
+                let boundary_edge = match edge_owners.get(&(*e2, *e1)) {
+                    Some(neighbor_idx) => !in_cavity[*neighbor_idx],
+                    None => true,
+                };
+
+                if boundary_edge {
+                    kept_triangles.push((*e1, *e2, point_idx));
+                }
+            }
+        }
+
+        triangles = kept_triangles;
+    }
+
+    triangles
+}
+
+//Twice the signed area, positive for counter-clockwise
+fn orientation(a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f64 {
+    let (ax, ay) = (a.0 as f64, a.1 as f64);
+    let (bx, by) = (b.0 as f64, b.1 as f64);
+    let (cx, cy) = (c.0 as f64, c.1 as f64);
+
+    (bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+}
+
+//Is d strictly inside the circumcircle of the counter-clockwise triangle a, b, c
+fn in_circumcircle(a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)) -> bool {
+    let (adx, ady) = (a.0 as f64 - d.0 as f64, a.1 as f64 - d.1 as f64);
+    let (bdx, bdy) = (b.0 as f64 - d.0 as f64, b.1 as f64 - d.1 as f64);
+    let (cdx, cdy) = (c.0 as f64 - d.0 as f64, c.1 as f64 - d.1 as f64);
+
+    let det = (adx.powi(2) + ady.powi(2)) * (bdx * cdy - cdx * bdy)
+        - (bdx.powi(2) + bdy.powi(2)) * (adx * cdy - cdx * ady)
+        + (cdx.powi(2) + cdy.powi(2)) * (adx * bdy - bdx * ady);
+
+    det > 0.0
+}
+
+fn point_in_convex_polygon(polygon: &[(f32, f32)], point: (f32, f32)) -> bool {
+    for idx in 0..polygon.len() {
+        let next_idx = (idx + 1) % polygon.len();
+
+        if orientation(polygon[idx], polygon[next_idx], point) <= 0.0 {
+            return false;
+        }
+    }
+
+    true
+}
+
+//Only the walkable triangles' vertices are passed on, vertices under walls are left out
+//Fails with the navmesh crate's reason if the triangles can't form a mesh
+pub fn build_nav_mesh_final(arena_nav_mesh: &ArenaNavMesh) -> Result<NavMesh, String> {
+    let mut nav_vecs: Vec<NavVec3> = Vec::new();
+    let mut nav_triangles: Vec<NavTriangle> = Vec::new();
+    let mut vertex_map: HashMap<usize, u32> = HashMap::new();
+
+    for (v1, v2, v3) in arena_nav_mesh.triangles.iter() {
+        let mut nav_indices = [0; 3];
+
+        for (idx, vertex_idx) in [*v1, *v2, *v3].iter().enumerate() {
+            nav_indices[idx] = *vertex_map.entry(*vertex_idx).or_insert_with(|| {
+                let (x, y, z) = arena_nav_mesh.vertices[*vertex_idx];
+                nav_vecs.push(NavVec3::new(x, y, z));
+                (nav_vecs.len() - 1) as u32
+            });
+        }
+
+        nav_triangles.push(NavTriangle {
+            first: nav_indices[0],
+            second: nav_indices[1],
+            third: nav_indices[2],
+        });
+    }
+
+    match NavMesh::new(nav_vecs, nav_triangles) {
+        Ok(mesh) => Ok(mesh),
+        Err(error) => Err(format!("{:?}", error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use navmesh::{NavPathMode, NavQuery};
+
+    fn find_path(mesh: &NavMesh, from: (f32, f32), to: (f32, f32)) -> Vec<(f32, f32)> {
+        mesh.find_path(
+            NavVec3::new(from.0, from.1, 0.0),
+            NavVec3::new(to.0, to.1, 0.0),
+            NavQuery::Accuracy,
+            NavPathMode::Accuracy,
+        )
+        .expect("no path found")
+        .iter()
+        .map(|point| (point.x, point.y))
+        .collect()
+    }
+
+    //Sample along every leg of the path, none of it may touch a wall
+    fn assert_path_clear(path: &[(f32, f32)], obstacles: &[NavMeshObstacle]) {
+        let walls: Vec<Vec<(f32, f32)>> = obstacles
+            .iter()
+            .map(|obstacle| get_obstacle_polygon(obstacle, NAV_MESH_OFFSET / 2.0))
+            .collect();
+
+        for leg in path.windows(2) {
+            for step in 0..=50 {
+                let t = step as f32 / 50.0;
+                let point = (
+                    leg[0].0 + (leg[1].0 - leg[0].0) * t,
+                    leg[0].1 + (leg[1].1 - leg[0].1) * t,
+                );
+
+                for wall in walls.iter() {
+                    assert!(
+                        !point_in_convex_polygon(wall, point),
+                        "path {:?} crosses a wall at {:?}",
+                        path,
+                        point
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn paths_avoid_rotated_rectangles_and_circles() {
+        let obstacles = vec![
+            NavMeshObstacle::Rectangle {
+                x: 200.0,
+                y: 200.0,
+                half_width: 120.0,
+                half_height: 10.0,
+                rotation: PI / 4.0,
+            },
+            NavMeshObstacle::Circle {
+                x: 100.0,
+                y: 300.0,
+                radius: 40.0,
+            },
+            NavMeshObstacle::Rectangle {
+                x: 300.0,
+                y: 100.0,
+                half_width: 20.0,
+                half_height: 60.0,
+                rotation: 0.3,
+            },
+        ];
+
+        let mesh = build_nav_mesh_final(&build_arena_nav_mesh(400.0, 400.0, &[], &obstacles))
+            .expect("nav mesh failed to build");
+
+        let targets = [
+            (30.0, 30.0),
+            (370.0, 370.0),
+            (30.0, 370.0),
+            (370.0, 30.0),
+            (60.0, 300.0),
+            (300.0, 200.0),
+        ];
+
+        for from in targets.iter() {
+            for to in targets.iter() {
+                if from != to {
+                    assert_path_clear(&find_path(&mesh, *from, *to), &obstacles);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn paths_stay_on_joined_floor_pieces() {
+        //an L shaped arena, the floor edges not shared between pieces are walls
+        let floors = vec![
+            ArenaFloor {
+                x: 100.0,
+                y: 200.0,
+                width: 200.0,
+                height: 400.0,
+            },
+            ArenaFloor {
+                x: 300.0,
+                y: 100.0,
+                width: 200.0,
+                height: 200.0,
+            },
+        ];
+        let obstacles = vec![NavMeshObstacle::Circle {
+            x: 200.0,
+            y: 200.0,
+            radius: 30.0,
+        }];
+
+        let nav_mesh = build_arena_nav_mesh(400.0, 400.0, &floors, &obstacles);
+
+        //no triangle may hang off the floor or into the wall
+        for (v1, v2, v3) in nav_mesh.triangles.iter() {
+            let centroid = (
+                (nav_mesh.vertices[*v1].0 + nav_mesh.vertices[*v2].0 + nav_mesh.vertices[*v3].0)
+                    / 3.0,
+                (nav_mesh.vertices[*v1].1 + nav_mesh.vertices[*v2].1 + nav_mesh.vertices[*v3].1)
+                    / 3.0,
+            );
+            assert!(!(centroid.0 > 200.0 && centroid.1 > 200.0));
+        }
+
+        let mesh = build_nav_mesh_final(&nav_mesh).expect("nav mesh failed to build");
+
+        let path = find_path(&mesh, (50.0, 350.0), (350.0, 50.0));
+        assert_path_clear(&path, &obstacles);
+
+        //cutting the corner of the L would leave the floor
+        for leg in path.windows(2) {
+            for step in 0..=50 {
+                let t = step as f32 / 50.0;
+                let x = leg[0].0 + (leg[1].0 - leg[0].0) * t;
+                let y = leg[0].1 + (leg[1].1 - leg[0].1) * t;
+
+                assert!(!(x > 200.0 + NAV_MESH_OFFSET / 2.0 && y > 200.0 + NAV_MESH_OFFSET / 2.0));
+            }
+        }
+    }
+
+    #[test]
+    fn walled_off_areas_are_unreachable() {
+        let obstacles = vec![NavMeshObstacle::Rectangle {
+            x: 200.0,
+            y: 200.0,
+            half_width: 20.0,
+            half_height: 200.0,
+            rotation: 0.0,
+        }];
+
+        let mesh = build_nav_mesh_final(&build_arena_nav_mesh(400.0, 400.0, &[], &obstacles))
+            .expect("nav mesh failed to build");
+
+        let path = mesh.find_path(
+            NavVec3::new(50.0, 200.0, 0.0),
+            NavVec3::new(350.0, 200.0, 0.0),
+            NavQuery::Accuracy,
+            NavPathMode::Accuracy,
+        );
+
+        assert!(path.is_none());
+    }
+
+    #[test]
+    fn removed_walls_open_up_without_rebuilding() {
+        let divider = NavMeshObstacle::Rectangle {
+            x: 200.0,
+            y: 200.0,
+            half_width: 20.0,
+            half_height: 200.0,
+            rotation: 0.0,
+        };
+        let pillar = NavMeshObstacle::Circle {
+            x: 100.0,
+            y: 100.0,
+            radius: 30.0,
+        };
+
+        let mut arena_nav_mesh = build_arena_nav_mesh(400.0, 400.0, &[], &[divider, pillar]);
+
+        //the remaining pillar comes back slightly off, as it would from the arena element
+        let remaining = vec![NavMeshObstacle::Circle {
+            x: 100.0,
+            y: 100.0,
+            radius: 30.001,
+        }];
+        assert!(remove_nav_mesh_obstacles(&mut arena_nav_mesh, &remaining));
+
+        let mesh = build_nav_mesh_final(&arena_nav_mesh).expect("nav mesh failed to build");
+        let path = find_path(&mesh, (50.0, 200.0), (350.0, 200.0));
+        assert_path_clear(&path, &remaining);
+
+        //obstacles the mesh was never built around need a full rebuild
+        let unknown = vec![NavMeshObstacle::Circle {
+            x: 300.0,
+            y: 300.0,
+            radius: 30.0,
+        }];
+        assert!(!remove_nav_mesh_obstacles(&mut arena_nav_mesh, &unknown));
+    }
+}
@@ -0,0 +1,38 @@
+use rand::Rng;
+use std::f32::consts::PI;
+
+//Current state of the shrinking arena boundary, updated by the ArenaStormSystem
+#[derive(Clone, Default)]
+pub struct ArenaStorm {
+    pub active: bool,
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub damage_rate: f32,
+    pub phase_index: usize,
+    pub phase_timer: f32,
+    pub start_radius: f32,
+    pub phase_start_radius: f32,
+}
+
+impl ArenaStorm {
+    //positive when outside of the safe zone, negative when inside
+    pub fn dist_outside(&self, x: f32, y: f32) -> f32 {
+        let dist = ((x - self.x).powi(2) + (y - self.y).powi(2)).sqrt();
+
+        dist - self.radius
+    }
+
+    pub fn is_outside(&self, x: f32, y: f32) -> bool {
+        self.active && self.dist_outside(x, y) > 0.0
+    }
+
+    pub fn random_point_inside(&self) -> (f32, f32) {
+        let mut rng = rand::thread_rng();
+
+        let angle = rng.gen_range(0.0, 2.0 * PI);
+        let dist = self.radius * rng.gen_range(0.0, 0.8_f32).sqrt();
+
+        (self.x + dist * angle.cos(), self.y + dist * angle.sin())
+    }
+}
@@ -0,0 +1,14 @@
+use crate::rally::DEBUG_LINES;
+
+//Runtime toggle for drawing what the bots are thinking, starts on with DEBUG_LINES
+pub struct BotDebugOverlay {
+    pub enabled: bool,
+}
+
+impl Default for BotDebugOverlay {
+    fn default() -> BotDebugOverlay {
+        BotDebugOverlay {
+            enabled: DEBUG_LINES,
+        }
+    }
+}
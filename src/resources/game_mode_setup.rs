@@ -1,6 +1,8 @@
 use serde::Deserialize;
 
-use crate::components::{ArenaNames, VehicleNames, VehicleStats, WeaponNames};
+use crate::components::{
+    ArenaNames, BotDifficulty, BotPersonality, VehicleNames, VehicleStats, WeaponNames,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Hash)]
 pub enum GameModes {
@@ -32,9 +34,74 @@ pub struct GameModeSetup {
     pub game_end_condition: GameEndCondition,
     pub max_players: usize,
     pub bot_players: usize,
+    pub bot_setups: [BotSetup; 4], //Difficulty and personality for each bot slot, first bot is slot 0
     pub last_hit_threshold: f32,
     pub arena_name: ArenaNames,
     pub p1_keyboard: bool,
+    pub storm: Option<StormSetup>, //Shrinking arena boundary. On by default for Stock and Survival, toggled from the menu.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BotSetup {
+    pub difficulty: BotDifficulty,
+    pub personality: BotPersonality,
+}
+
+impl Default for BotSetup {
+    fn default() -> Self {
+        Self {
+            difficulty: BotDifficulty::Normal,
+            personality: BotPersonality::Balanced,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StormCenter {
+    ArenaCenter,
+    RandomPoint,
+}
+
+#[derive(Clone, Debug)]
+pub struct StormPhase {
+    pub wait_time: f32,      //seconds to hold the current radius before shrinking
+    pub shrink_time: f32,    //seconds to shrink down to end_radius_pct
+    pub end_radius_pct: f32, //percent of the starting radius at the end of this phase
+    pub damage_rate: f32,    //damage per second while outside the safe zone
+}
+
+#[derive(Clone, Debug)]
+pub struct StormSetup {
+    pub center: StormCenter,
+    pub phases: Vec<StormPhase>,
+}
+
+impl Default for StormSetup {
+    fn default() -> Self {
+        Self {
+            center: StormCenter::ArenaCenter,
+            phases: vec![
+                StormPhase {
+                    wait_time: 45.0,
+                    shrink_time: 30.0,
+                    end_radius_pct: 70.0,
+                    damage_rate: 5.0,
+                },
+                StormPhase {
+                    wait_time: 30.0,
+                    shrink_time: 30.0,
+                    end_radius_pct: 40.0,
+                    damage_rate: 10.0,
+                },
+                StormPhase {
+                    wait_time: 20.0,
+                    shrink_time: 30.0,
+                    end_radius_pct: 15.0,
+                    damage_rate: 20.0,
+                },
+            ],
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -1,12 +1,26 @@
-pub use self::arena_navmesh::{ArenaNavMesh, ArenaNavMeshFinal};
+pub use self::arena_navmesh::{
+    build_arena_nav_mesh, build_nav_mesh_final, get_arena_nav_mesh_obstacles,
+    get_nav_mesh_obstacle, remove_nav_mesh_obstacles, ArenaNavMesh, ArenaNavMeshFinal,
+    NavMeshObstacle,
+};
+pub use self::arena_storm::ArenaStorm;
+pub use self::bot_debug_overlay::BotDebugOverlay;
 pub use self::game_mode_setup::{
-    GameEndCondition, GameModeSetup, GameModes, GameScore, GameTeamSetup, GameVehicleSetup,
-    GameWeaponSelectionMode, GameWeaponSetup, TeamSetupTypes,
+    BotSetup, GameEndCondition, GameModeSetup, GameModes, GameScore, GameTeamSetup,
+    GameVehicleSetup, GameWeaponSelectionMode, GameWeaponSetup, StormCenter, StormPhase,
+    StormSetup, TeamSetupTypes,
 };
 pub use self::match_timer::MatchTimer;
-pub use self::weapon_fire_resource::{initialize_weapon_fire_resource, WeaponFireResource};
+pub use self::racing_line::{build_racing_line, point_on_nav_mesh, RacingLine};
+pub use self::weapon_fire_resource::{
+    initialize_weapon_fire_resource, WeaponFireResource, WeaponFireTrail, WeaponFireVisual,
+    WeaponFireVisualKey,
+};
 
 mod arena_navmesh;
+mod arena_storm;
+mod bot_debug_overlay;
 mod game_mode_setup;
 mod match_timer;
+mod racing_line;
 mod weapon_fire_resource;
@@ -0,0 +1,315 @@
+use navmesh::{NavMesh, NavPathMode, NavQuery, NavVec3};
+
+use std::f32::consts::PI;
+
+use crate::components::ArenaProperties;
+use crate::resources::ArenaNavMesh;
+
+const RACING_LINE_SPACING: f32 = 10.0;
+const RACING_LINE_SMOOTH_PASSES: usize = 120;
+const RACING_LINE_SMOOTH_RATE: f32 = 0.5;
+const RACING_LINE_GATE_MARGIN: f32 = 10.0; //stay this far inside the ends of a checkpoint line
+const RACING_LINE_CORNER_SPAN: usize = 3; //points either side used to measure a corner
+const RACING_LINE_MAX_CORNER_RADIUS: f32 = 10000.0;
+const RACING_LINE_MAX_CLEARANCE: f32 = 40.0;
+
+//A closed loop through every race checkpoint, smoothed out to cut the corners
+pub struct RacingLine {
+    pub points: Vec<(f32, f32)>,
+    pub corner_radii: Vec<f32>, //radius of the turn at each point, large on straights
+    pub clearance_left: Vec<f32>, //room to the left of each point for overtaking
+    pub clearance_right: Vec<f32>,
+    pub checkpoint_indices: Vec<usize>, //point crossing each race checkpoint
+}
+
+impl Default for RacingLine {
+    fn default() -> RacingLine {
+        RacingLine {
+            points: Vec::new(),
+            corner_radii: Vec::new(),
+            clearance_left: Vec::new(),
+            clearance_right: Vec::new(),
+            checkpoint_indices: Vec::new(),
+        }
+    }
+}
+
+impl RacingLine {
+    //Unit vector along the racing line at a point
+    pub fn direction(&self, idx: usize) -> (f32, f32) {
+        let len = self.points.len();
+        let prev = self.points[(idx + len - 1) % len];
+        let next = self.points[(idx + 1) % len];
+
+        let dx = next.0 - prev.0;
+        let dy = next.1 - prev.1;
+        let length = dx.hypot(dy);
+
+        if length < 0.0001 {
+            (0.0, 1.0)
+        } else {
+            (dx / length, dy / length)
+        }
+    }
+}
+
+pub fn build_racing_line(
+    arena_properties: &ArenaProperties,
+    arena_nav_mesh: &ArenaNavMesh,
+    nav_mesh: &Option<NavMesh>,
+) -> RacingLine {
+    let checkpoints = &arena_properties.race_checkpoints;
+
+    if checkpoints.len() < 2 {
+        return RacingLine::default();
+    }
+
+    //Start from the shortest way around, checkpoint to checkpoint along the nav mesh
+    let mut points: Vec<(f32, f32)> = Vec::new();
+    let mut checkpoint_indices: Vec<usize> = Vec::new();
+
+    for (idx, checkpoint) in checkpoints.iter().enumerate() {
+        let next_checkpoint = checkpoints[(idx + 1) % checkpoints.len()];
+
+        let mut leg: Vec<(f32, f32)> = vec![(checkpoint.x, checkpoint.y)];
+
+        if let Some(nav_mesh) = nav_mesh {
+            let path = nav_mesh.find_path(
+                NavVec3::new(checkpoint.x, checkpoint.y, 0.0),
+                NavVec3::new(next_checkpoint.x, next_checkpoint.y, 0.0),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            );
+
+            if let Some(path) = path {
+                for nav_vector in path.iter().skip(1) {
+                    leg.push((nav_vector.x, nav_vector.y));
+                }
+            }
+        }
+
+        if leg.len() < 2 {
+            leg.push((next_checkpoint.x, next_checkpoint.y));
+        }
+
+        checkpoint_indices.push(points.len());
+
+        //evenly spaced points, without the end point which starts the next leg
+        for leg_idx in 1..leg.len() {
+            let from = leg[leg_idx - 1];
+            let to = leg[leg_idx];
+            let length = (to.0 - from.0).hypot(to.1 - from.1);
+            let steps = (length / RACING_LINE_SPACING).ceil().max(1.0) as usize;
+
+            for step in 0..steps {
+                let t = step as f32 / steps as f32;
+                points.push((from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t));
+            }
+        }
+    }
+
+    //Pull the line tight, checkpoint points may only slide along their checkpoint line
+    let len = points.len();
+
+    for _ in 0..RACING_LINE_SMOOTH_PASSES {
+        for idx in 0..len {
+            let prev = points[(idx + len - 1) % len];
+            let next = points[(idx + 1) % len];
+            let point = points[idx];
+
+            let mut new_point = (
+                point.0 + RACING_LINE_SMOOTH_RATE * ((prev.0 + next.0) / 2.0 - point.0),
+                point.1 + RACING_LINE_SMOOTH_RATE * ((prev.1 + next.1) / 2.0 - point.1),
+            );
+
+            if let Some(checkpoint_idx) = checkpoint_indices.iter().position(|i| *i == idx) {
+                let checkpoint = checkpoints[checkpoint_idx];
+                let angle = checkpoint.rotation / 180.0 * PI;
+                let half_length = (checkpoint.length / 2.0 - RACING_LINE_GATE_MARGIN).max(0.0);
+
+                let along = ((new_point.0 - checkpoint.x) * angle.cos()
+                    + (new_point.1 - checkpoint.y) * angle.sin())
+                .max(-half_length)
+                .min(half_length);
+
+                new_point = (
+                    checkpoint.x + along * angle.cos(),
+                    checkpoint.y + along * angle.sin(),
+                );
+            }
+
+            if point_on_nav_mesh(arena_nav_mesh, new_point.0, new_point.1) {
+                points[idx] = new_point;
+            }
+        }
+    }
+
+    let mut racing_line = RacingLine {
+        points,
+        corner_radii: Vec::new(),
+        clearance_left: Vec::new(),
+        clearance_right: Vec::new(),
+        checkpoint_indices,
+    };
+
+    let span = RACING_LINE_CORNER_SPAN.min(len / 2).max(1);
+
+    for idx in 0..len {
+        let prev = racing_line.points[(idx + len - span) % len];
+        let point = racing_line.points[idx];
+        let next = racing_line.points[(idx + span) % len];
+
+        racing_line
+            .corner_radii
+            .push(calc_corner_radius(prev, point, next));
+
+        //left of the direction of travel is the direction rotated by +90deg
+        let (dir_x, dir_y) = racing_line.direction(idx);
+
+        racing_line
+            .clearance_left
+            .push(calc_clearance(arena_nav_mesh, point, (-dir_y, dir_x)));
+        racing_line
+            .clearance_right
+            .push(calc_clearance(arena_nav_mesh, point, (dir_y, -dir_x)));
+    }
+
+    racing_line
+}
+
+//Radius of the circle through three points
+fn calc_corner_radius(a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f32 {
+    let ab = (b.0 - a.0).hypot(b.1 - a.1);
+    let bc = (c.0 - b.0).hypot(c.1 - b.1);
+    let ca = (a.0 - c.0).hypot(a.1 - c.1);
+
+    let twice_area = ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)).abs();
+
+    if twice_area < 0.0001 {
+        RACING_LINE_MAX_CORNER_RADIUS
+    } else {
+        (ab * bc * ca / (2.0 * twice_area)).min(RACING_LINE_MAX_CORNER_RADIUS)
+    }
+}
+
+//How far the nav mesh reaches from a point in a direction
+fn calc_clearance(arena_nav_mesh: &ArenaNavMesh, point: (f32, f32), dir: (f32, f32)) -> f32 {
+    let step = RACING_LINE_SPACING / 2.0;
+    let mut clearance = 0.0;
+
+    while clearance < RACING_LINE_MAX_CLEARANCE {
+        let x = point.0 + dir.0 * (clearance + step);
+        let y = point.1 + dir.1 * (clearance + step);
+
+        if !point_on_nav_mesh(arena_nav_mesh, x, y) {
+            break;
+        }
+
+        clearance += step;
+    }
+
+    clearance
+}
+
+pub fn point_on_nav_mesh(arena_nav_mesh: &ArenaNavMesh, x: f32, y: f32) -> bool {
+    for (v1, v2, v3) in arena_nav_mesh.triangles.iter() {
+        let a = arena_nav_mesh.vertices[*v1];
+        let b = arena_nav_mesh.vertices[*v2];
+        let c = arena_nav_mesh.vertices[*v3];
+
+        let d1 = (b.0 - a.0) * (y - a.1) - (b.1 - a.1) * (x - a.0);
+        let d2 = (c.0 - b.0) * (y - b.1) - (c.1 - b.1) * (x - b.0);
+        let d3 = (a.0 - c.0) * (y - c.1) - (a.1 - c.1) * (x - c.0);
+
+        let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+
+        if !(has_neg && has_pos) {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::ArenaRaceCheckpoint;
+    use crate::resources::{build_arena_nav_mesh, build_nav_mesh_final, NavMeshObstacle};
+
+    #[test]
+    fn racing_line_stays_on_track_and_crosses_every_checkpoint() {
+        //a square track around a central block
+        let arena_properties = ArenaProperties {
+            width: 400.0,
+            height: 400.0,
+            race_checkpoints: vec![
+                ArenaRaceCheckpoint {
+                    x: 200.0,
+                    y: 50.0,
+                    length: 100.0,
+                    rotation: 90.0,
+                },
+                ArenaRaceCheckpoint {
+                    x: 350.0,
+                    y: 200.0,
+                    length: 100.0,
+                    rotation: 0.0,
+                },
+                ArenaRaceCheckpoint {
+                    x: 200.0,
+                    y: 350.0,
+                    length: 100.0,
+                    rotation: 90.0,
+                },
+                ArenaRaceCheckpoint {
+                    x: 50.0,
+                    y: 200.0,
+                    length: 100.0,
+                    rotation: 0.0,
+                },
+            ],
+            ..ArenaProperties::default()
+        };
+
+        let obstacles = vec![NavMeshObstacle::Rectangle {
+            x: 200.0,
+            y: 200.0,
+            half_width: 100.0,
+            half_height: 100.0,
+            rotation: 0.0,
+        }];
+
+        let arena_nav_mesh = build_arena_nav_mesh(400.0, 400.0, &[], &obstacles);
+        let nav_mesh = build_nav_mesh_final(&arena_nav_mesh).ok();
+
+        let racing_line = build_racing_line(&arena_properties, &arena_nav_mesh, &nav_mesh);
+
+        assert!(racing_line.points.len() > 20);
+        assert_eq!(racing_line.checkpoint_indices.len(), 4);
+
+        for (x, y) in racing_line.points.iter() {
+            assert!(point_on_nav_mesh(&arena_nav_mesh, *x, *y));
+        }
+
+        for (checkpoint, idx) in arena_properties
+            .race_checkpoints
+            .iter()
+            .zip(racing_line.checkpoint_indices.iter())
+        {
+            let (x, y) = racing_line.points[*idx];
+            let dist = (x - checkpoint.x).hypot(y - checkpoint.y);
+
+            assert!(dist <= checkpoint.length / 2.0);
+        }
+
+        //corners of the track are tighter than its straights
+        let min_radius = racing_line
+            .corner_radii
+            .iter()
+            .fold(std::f32::MAX, |a, b| a.min(*b));
+
+        assert!(min_radius < 200.0);
+    }
+}
@@ -4,39 +4,107 @@ use amethyst::{
     renderer::{SpriteRender, SpriteSheet},
 };
 
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::components::{WeaponFireTypes, WeaponNames};
+use crate::load_ron_asset;
+
+//Weapon fire visuals correspond to the weapon_fire_visuals.ron
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Hash, Eq)]
+pub enum WeaponFireVisualKey {
+    FireType(WeaponFireTypes), //shared by every weapon firing this type
+    Weapon(WeaponNames),       //overrides the fire type's look for a single weapon
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WeaponFireVisual {
+    pub sprite_number: usize,
+    pub player_sprite_numbers: Vec<usize>, //player colored variants, empty if everyone shares sprite_number
+    pub icon_scale: f32,                   //size of the HUD weapon icon
+    pub width: f32,                        //unscaled size of the shot
+    pub height: f32,
+    pub scale: f32, //size of the shot in the arena, its hitbox scales along with it
+    pub tint: Option<(f32, f32, f32, f32)>,
+    pub trail: Option<WeaponFireTrail>,
+}
+
+//Particles left behind a shot as it flies
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct WeaponFireTrail {
+    pub sprite_number: usize,
+    pub rate: f32, //seconds between particles
+    pub life_time: f32,
+    pub drift_speed: f32, //particles drift off in a random direction
+}
+
 #[derive(Clone)]
 pub struct WeaponFireResource {
+    pub sprite_sheet_handle: Handle<SpriteSheet>,
+    pub weapon_fire_visuals: HashMap<WeaponFireVisualKey, WeaponFireVisual>,
     /// The render that locates the sprite in a sprite sheet resource
-    pub laser_double_sprite_render: SpriteRender,
-    pub laser_beam_sprite_render: SpriteRender,
-    pub laser_burst_sprite_render: SpriteRender,
-    pub projectile_cannon_sprite_render: SpriteRender,
-    pub projectile_burst_render: SpriteRender,
-    pub projectile_rapid_render: SpriteRender,
-    pub mine_p1_sprite_render: SpriteRender,
-    pub mine_p2_sprite_render: SpriteRender,
-    pub mine_p3_sprite_render: SpriteRender,
-    pub mine_p4_sprite_render: SpriteRender,
-    pub trap_p1_sprite_render: SpriteRender,
-    pub trap_p2_sprite_render: SpriteRender,
-    pub trap_p3_sprite_render: SpriteRender,
-    pub trap_p4_sprite_render: SpriteRender,
-    pub grenade_sprite_render: SpriteRender,
-    pub missile_sprite_render: SpriteRender,
-    pub rockets_sprite_render: SpriteRender,
-    pub laser_sword_sprite_render: SpriteRender,
-    pub flame_sprite_render: SpriteRender,
     pub weapon_box_sprite_render: SpriteRender,
     pub sparking_sprite_render: SpriteRender,
     pub rocket_spray_sprite_render: SpriteRender,
     pub shield_hit_spray_sprite_render: SpriteRender,
     pub hull_hit_spray_sprite_render: SpriteRender,
     pub smoke_spray_sprite_render: SpriteRender,
-    pub ion_sprite_render: SpriteRender,
     pub shockwave_sprite_render: SpriteRender,
-    pub bio_spike_sprite_render: SpriteRender,
-    pub light_bolt_sprite_render: SpriteRender,
-    pub slime_ball_sprite_render: SpriteRender,
+}
+
+impl WeaponFireResource {
+    pub fn get_sprite_render(&self, sprite_number: usize) -> SpriteRender {
+        SpriteRender {
+            sprite_sheet: self.sprite_sheet_handle.clone(),
+            sprite_number,
+        }
+    }
+
+    //A weapon's own look if it has one, otherwise the look of its fire type
+    pub fn get_weapon_fire_visual(
+        &self,
+        weapon_name: WeaponNames,
+        weapon_fire_type: WeaponFireTypes,
+    ) -> Option<&WeaponFireVisual> {
+        match self
+            .weapon_fire_visuals
+            .get(&WeaponFireVisualKey::Weapon(weapon_name))
+        {
+            Some(weapon_fire_visual) => Some(weapon_fire_visual),
+            None => self
+                .weapon_fire_visuals
+                .get(&WeaponFireVisualKey::FireType(weapon_fire_type)),
+        }
+    }
+
+    //Unscaled width and height of a shot, icons are sized from this too
+    pub fn get_weapon_fire_size(
+        &self,
+        weapon_name: WeaponNames,
+        weapon_fire_type: WeaponFireTypes,
+    ) -> (f32, f32) {
+        match self.get_weapon_fire_visual(weapon_name, weapon_fire_type) {
+            Some(weapon_fire_visual) => (weapon_fire_visual.width, weapon_fire_visual.height),
+            None => (3.0, 6.0), //the basic laser
+        }
+    }
+
+    //Width and height of a shot's hitbox, matching the size it is drawn at
+    pub fn get_weapon_fire_hitbox(
+        &self,
+        weapon_name: WeaponNames,
+        weapon_fire_type: WeaponFireTypes,
+    ) -> (f32, f32) {
+        let (width, height) = self.get_weapon_fire_size(weapon_name, weapon_fire_type);
+
+        match self.get_weapon_fire_visual(weapon_name, weapon_fire_type) {
+            Some(weapon_fire_visual) => (
+                width * weapon_fire_visual.scale,
+                height * weapon_fire_visual.scale,
+            ),
+            None => (width, height),
+        }
+    }
 }
 
 pub fn initialize_weapon_fire_resource(
@@ -44,82 +112,8 @@ pub fn initialize_weapon_fire_resource(
     sprite_sheet_handle: Handle<SpriteSheet>,
 ) -> WeaponFireResource {
     let weapon_fire_resource = WeaponFireResource {
-        laser_double_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 4,
-        },
-        laser_beam_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 5,
-        },
-        laser_burst_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 6,
-        },
-        projectile_cannon_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 7,
-        },
-        projectile_burst_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 8,
-        },
-        projectile_rapid_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 9,
-        },
-        mine_p1_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 10,
-        },
-        mine_p2_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 16,
-        },
-        mine_p3_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 17,
-        },
-        mine_p4_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 18,
-        },
-        grenade_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 23,
-        },
-        missile_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 11,
-        },
-        rockets_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 12,
-        },
-        laser_sword_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 15,
-        },
-        trap_p1_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 25,
-        },
-        trap_p2_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 26,
-        },
-        trap_p3_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 27,
-        },
-        trap_p4_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 28,
-        },
-        flame_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 34,
-        },
+        sprite_sheet_handle: sprite_sheet_handle.clone(),
+        weapon_fire_visuals: load_ron_asset(&["game", "weapon_fire_visuals.ron"]),
         weapon_box_sprite_render: SpriteRender {
             sprite_sheet: sprite_sheet_handle.clone(),
             sprite_number: 35,
@@ -144,26 +138,10 @@ pub fn initialize_weapon_fire_resource(
             sprite_sheet: sprite_sheet_handle.clone(),
             sprite_number: 41,
         },
-        ion_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 42,
-        },
         shockwave_sprite_render: SpriteRender {
             sprite_sheet: sprite_sheet_handle.clone(),
             sprite_number: 43,
         },
-        bio_spike_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 75,
-        },
-        light_bolt_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 76,
-        },
-        slime_ball_sprite_render: SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 77,
-        },
     };
     world.insert(weapon_fire_resource.clone());
 
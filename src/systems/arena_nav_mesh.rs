@@ -0,0 +1,86 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::{Join, ReadStorage, System, SystemData, World, WriteExpect},
+};
+
+use crate::components::{ArenaElement, ArenaNames, ArenaProperties, ArenaStoreResource};
+use crate::resources::{
+    build_arena_nav_mesh, build_nav_mesh_final, get_nav_mesh_obstacle, remove_nav_mesh_obstacles,
+    ArenaNavMesh, ArenaNavMeshFinal, GameModeSetup, NavMeshObstacle,
+};
+
+//Updates the nav mesh at most once per frame after walls have been destroyed,
+//so several walls breaking in the same frame only pay for a single update
+#[derive(SystemDesc, Default)]
+pub struct ArenaNavMeshSystem {
+    pub arena_properties: ArenaProperties,
+}
+
+impl<'s> System<'s> for ArenaNavMeshSystem {
+    type SystemData = (
+        ReadStorage<'s, ArenaElement>,
+        WriteExpect<'s, ArenaNavMesh>,
+        WriteExpect<'s, ArenaNavMeshFinal>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        let arena_name;
+        {
+            let fetched_game_mode_setup = world.try_fetch::<GameModeSetup>();
+            if let Some(game_mode_setup) = fetched_game_mode_setup {
+                arena_name = game_mode_setup.arena_name.clone();
+            } else {
+                arena_name = ArenaNames::OpenEmptyMap;
+            }
+        }
+
+        {
+            let fetched_arena_store = world.try_fetch::<ArenaStoreResource>();
+            if let Some(arena_store) = fetched_arena_store {
+                self.arena_properties = match arena_store.properties.get(&arena_name) {
+                    Some(arena_props_get) => (*arena_props_get).clone(),
+                    _ => ArenaProperties::default(),
+                };
+            } else {
+                self.arena_properties = ArenaProperties::default();
+            }
+        }
+    }
+
+    fn run(
+        &mut self,
+        (arena_elements, mut arena_nav_mesh, mut arena_nav_mesh_final): Self::SystemData,
+    ) {
+        if !arena_nav_mesh.dirty {
+            return;
+        }
+
+        let mut nav_mesh_obstacles: Vec<NavMeshObstacle> = Vec::new();
+        for arena_element in (&arena_elements).join() {
+            if let Some(obstacle) = get_nav_mesh_obstacle(arena_element) {
+                nav_mesh_obstacles.push(obstacle);
+            }
+        }
+
+        //open up the destroyed walls in place, only triangulate again for walls the mesh never had
+        if !remove_nav_mesh_obstacles(&mut arena_nav_mesh, &nav_mesh_obstacles) {
+            log::warn!("Nav mesh is missing a wall, rebuilding it");
+
+            *arena_nav_mesh = build_arena_nav_mesh(
+                self.arena_properties.width,
+                self.arena_properties.height,
+                &self.arena_properties.floor,
+                &nav_mesh_obstacles,
+            );
+        }
+
+        arena_nav_mesh.dirty = false;
+        match build_nav_mesh_final(&arena_nav_mesh) {
+            Ok(mesh) => arena_nav_mesh_final.mesh = Some(mesh),
+            Err(error) => {
+                log::warn!("Nav mesh could not be built: {}", error);
+                arena_nav_mesh_final.mesh = None;
+            }
+        }
+    }
+}
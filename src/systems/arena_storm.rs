@@ -0,0 +1,131 @@
+use amethyst::{
+    core::{math::Point3, Time},
+    derive::SystemDesc,
+    ecs::{Read, System, SystemData, World, Write, WriteExpect},
+    renderer::{debug_drawing::DebugLines, palette::Srgba},
+};
+
+use rand::Rng;
+
+use crate::components::{ArenaNames, ArenaProperties, ArenaStoreResource};
+use crate::resources::{ArenaStorm, GameModeSetup, StormCenter, StormSetup};
+
+const STORM_LINE_Z: f32 = 0.5;
+const STORM_LINE_POINTS: u32 = 64;
+
+#[derive(SystemDesc, Default)]
+pub struct ArenaStormSystem {
+    pub arena_properties: ArenaProperties,
+    pub storm_setup: Option<StormSetup>,
+}
+
+impl<'s> System<'s> for ArenaStormSystem {
+    type SystemData = (
+        Read<'s, Time>,
+        WriteExpect<'s, ArenaStorm>,
+        Write<'s, DebugLines>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        let arena_name;
+        {
+            let fetched_game_mode_setup = world.try_fetch::<GameModeSetup>();
+            if let Some(game_mode_setup) = fetched_game_mode_setup {
+                arena_name = game_mode_setup.arena_name.clone();
+                self.storm_setup = game_mode_setup.storm.clone();
+            } else {
+                arena_name = ArenaNames::OpenEmptyMap;
+                self.storm_setup = None;
+            }
+        }
+
+        {
+            let fetched_arena_store = world.try_fetch::<ArenaStoreResource>();
+            if let Some(arena_store) = fetched_arena_store {
+                self.arena_properties = match arena_store.properties.get(&arena_name) {
+                    Some(arena_props_get) => (*arena_props_get).clone(),
+                    _ => ArenaProperties::default(),
+                };
+            } else {
+                self.arena_properties = ArenaProperties::default();
+            }
+        }
+
+        let fetched_arena_storm = world.try_fetch_mut::<ArenaStorm>();
+
+        if let Some(mut arena_storm) = fetched_arena_storm {
+            *arena_storm = ArenaStorm::default();
+
+            if let Some(storm_setup) = &self.storm_setup {
+                if !storm_setup.phases.is_empty() {
+                    let width = self.arena_properties.width;
+                    let height = self.arena_properties.height;
+
+                    let (x, y) = match storm_setup.center {
+                        StormCenter::ArenaCenter => (width / 2.0, height / 2.0),
+                        StormCenter::RandomPoint => {
+                            let mut rng = rand::thread_rng();
+                            (
+                                rng.gen_range(0.3 * width, 0.7 * width),
+                                rng.gen_range(0.3 * height, 0.7 * height),
+                            )
+                        }
+                    };
+
+                    //start large enough to cover the furthest corner of the arena
+                    let start_radius = x.max(width - x).hypot(y.max(height - y));
+
+                    arena_storm.active = true;
+                    arena_storm.x = x;
+                    arena_storm.y = y;
+                    arena_storm.radius = start_radius;
+                    arena_storm.start_radius = start_radius;
+                    arena_storm.phase_start_radius = start_radius;
+                    arena_storm.damage_rate = storm_setup.phases[0].damage_rate;
+                }
+            }
+        }
+    }
+
+    fn run(&mut self, (time, mut arena_storm, mut debug_lines_resource): Self::SystemData) {
+        if !arena_storm.active {
+            return;
+        }
+
+        let dt = time.delta_seconds();
+
+        if let Some(storm_setup) = &self.storm_setup {
+            if arena_storm.phase_index < storm_setup.phases.len() {
+                let phase = &storm_setup.phases[arena_storm.phase_index];
+
+                arena_storm.phase_timer += dt;
+                arena_storm.damage_rate = phase.damage_rate;
+
+                let end_radius = arena_storm.start_radius * phase.end_radius_pct / 100.0;
+
+                if arena_storm.phase_timer <= phase.wait_time {
+                    arena_storm.radius = arena_storm.phase_start_radius;
+                } else if arena_storm.phase_timer < phase.wait_time + phase.shrink_time {
+                    let shrink_pct =
+                        (arena_storm.phase_timer - phase.wait_time) / phase.shrink_time;
+
+                    arena_storm.radius = arena_storm.phase_start_radius
+                        - (arena_storm.phase_start_radius - end_radius) * shrink_pct;
+                } else {
+                    //move on to the next phase, final phase radius is held for the rest of the match
+                    arena_storm.radius = end_radius;
+                    arena_storm.phase_start_radius = end_radius;
+                    arena_storm.phase_timer = 0.0;
+                    arena_storm.phase_index += 1;
+                }
+            }
+        }
+
+        debug_lines_resource.draw_circle(
+            Point3::new(arena_storm.x, arena_storm.y, STORM_LINE_Z),
+            arena_storm.radius,
+            STORM_LINE_POINTS,
+            Srgba::new(0.6, 0.2, 0.9, 1.0),
+        );
+    }
+}
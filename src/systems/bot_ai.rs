@@ -0,0 +1,1519 @@
+use amethyst::{
+    core::{Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+};
+
+use log::debug;
+use navmesh::{NavPathMode, NavQuery};
+use rand::Rng;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use crate::components::{
+    determine_vehicle_weight, ArenaElement, ArenaNames, ArenaProperties, ArenaStoreResource,
+    BotIntent, BotMode, ObstacleType, Player, Vehicle, VehicleMovementType, VehicleState,
+    WeaponArray,
+};
+use crate::resources::{
+    ArenaNavMeshFinal, ArenaStorm, GameModeSetup, GameModes, GameWeaponSelectionMode,
+    GameWeaponSetup, RacingLine,
+};
+use crate::systems::{
+    calc_lead_angle, clean_angle, has_line_of_sight, TANK_TRACK_LATERAL_FRICTION_DECEL_RATE,
+    TIRE_LATERAL_FRICTION_DECEL_RATE,
+};
+
+const BOT_STUCK_CHECK_TIME: f32 = 1.0;
+const BOT_STUCK_DIST: f32 = 8.0; //moving less than this over the check time while driving is stuck
+const BOT_UNSTUCK_TIME: f32 = 0.6;
+const BOT_PATH_LOOKAHEAD_DIST: f32 = 25.0;
+const BOT_PATH_POINT_REACHED_DIST: f32 = 12.0;
+const BOT_NO_HIT_MOVE_COOLDOWN: f32 = 2.0;
+const BOT_NO_HIT_GIVE_UP_TIME: f32 = 3.0;
+const BOT_STORM_EDGE_MARGIN: f32 = 30.0;
+
+//Behavior scores are roughly 0.0 to 1.0, the highest scoring behavior is chosen each frame
+const BOT_WANDER_SCORE: f32 = 0.2;
+const BOT_ENGAGE_SCORE: f32 = 0.9;
+const BOT_ENGAGE_RANGE: f32 = 200.0; //engage score falls off to zero at this distance
+const BOT_REPAIR_SCORE: f32 = 0.6;
+const BOT_REPAIR_SAFE_DISTANCE: f32 = 240.0;
+const BOT_HILL_SCORE: f32 = 0.7;
+const BOT_PICKUP_SCORE: f32 = 0.45;
+const BOT_PICKUP_RANGE: f32 = 150.0; //straight-line distance weapon boxes are considered within
+const BOT_PICKUP_PATH_RANGE: f32 = 250.0; //longest nav mesh path worth driving for a weapon box
+const BOT_PICKUP_ENEMY_CLEARANCE: f32 = 100.0; //enemies closer than this to a box make it risky
+const BOT_PICKUP_EVAL_TIME: f32 = 0.5; //seconds between finding paths to every nearby weapon box
+const BOT_STORM_SCORE: f32 = 0.95;
+const BOT_SEARCH_SCORE: f32 = 0.5;
+const BOT_ENEMY_MEMORY_TIME: f32 = 4.0; //how long bots remember where they last saw an enemy
+const BOT_SEARCH_REACHED_DIST: f32 = 20.0;
+
+const RACE_LINE_SEARCH_AHEAD: usize = 30; //racing line points searched past the last closest point
+const RACE_LINE_LOST_DIST: f32 = 80.0; //further than this from the line, search all of it again
+const RACE_LOOKAHEAD_DIST: f32 = 20.0;
+const RACE_LOOKAHEAD_TIME: f32 = 0.3; //look further along the line the faster the vehicle goes
+const RACE_SPEED_PLAN_DIST: f32 = 300.0; //how far ahead to look for corners to brake for
+const RACE_CAR_CORNER_GRIP: f32 = 4.0; //tire friction to sideways grip while turning
+const RACE_TANK_CORNER_GRIP: f32 = 0.5; //tank tracks grip well, but tanks turn slowly
+const RACE_OVERTAKE_DIST: f32 = 60.0;
+const RACE_OVERTAKE_WIDTH: f32 = 25.0; //vehicles this far either side of the bot are in the way
+const RACE_OVERTAKE_OFFSET: f32 = 30.0;
+const RACE_OVERTAKE_WALL_MARGIN: f32 = 5.0;
+const BOT_FOCUS_FIRE_RANGE: f32 = 300.0; //team focus target is only worth turning to within this range
+const BOT_LINE_OF_FIRE_RANGE: f32 = 250.0;
+const BOT_LINE_OF_FIRE_WIDTH: f32 = 20.0; //half width of a teammate's line of fire to stay out of
+const BOT_HILL_GUARD_DIST: f32 = 40.0; //how far outside the hill guards wait
+pub const BOT_PREFERRED_RANGE_MIN_PCT: f32 = 0.75; //backs off from targets closer than this
+pub const BOT_PREFERRED_RANGE_MAX_PCT: f32 = 1.25; //closes in on targets further than this
+const BOT_BEHAVIOR_STICKINESS: f32 = 0.15; //bonus for the current behavior, prevents flip-flopping
+
+const PRIMARY_WEAPON_INDEX: usize = 0;
+const SECONDARY_WEAPON_INDEX: usize = 1;
+
+#[derive(SystemDesc, Default)]
+pub struct BotAiSystem {
+    pub arena_properties: ArenaProperties,
+}
+
+impl<'s> System<'s> for BotAiSystem {
+    type SystemData = (
+        ReadStorage<'s, ArenaElement>,
+        WriteStorage<'s, Player>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Vehicle>,
+        ReadStorage<'s, WeaponArray>,
+        Read<'s, Time>,
+        ReadExpect<'s, GameModeSetup>,
+        ReadExpect<'s, ArenaStorm>,
+        ReadExpect<'s, GameWeaponSetup>,
+        ReadExpect<'s, ArenaNavMeshFinal>,
+        ReadExpect<'s, RacingLine>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        let arena_name;
+        {
+            let fetched_game_mode_setup = world.try_fetch::<GameModeSetup>();
+            if let Some(game_mode_setup) = fetched_game_mode_setup {
+                arena_name = game_mode_setup.arena_name.clone();
+            } else {
+                arena_name = ArenaNames::OpenEmptyMap;
+            }
+        }
+
+        {
+            let fetched_arena_store = world.try_fetch::<ArenaStoreResource>();
+            if let Some(arena_store) = fetched_arena_store {
+                self.arena_properties = match arena_store.properties.get(&arena_name) {
+                    Some(arena_props_get) => (*arena_props_get).clone(),
+                    _ => ArenaProperties::default(),
+                };
+            } else {
+                self.arena_properties = ArenaProperties::default();
+            }
+        }
+    }
+
+    fn run(
+        &mut self,
+        (
+            arena_elements,
+            mut players,
+            transforms,
+            vehicles,
+            weapon_arrays,
+            time,
+            game_mode_setup,
+            arena_storm,
+            game_weapon_setup,
+            arena_nav_mesh_final,
+            racing_line,
+        ): Self::SystemData,
+    ) {
+        let mut rng = rand::thread_rng();
+        let dt = time.delta_seconds();
+
+        //weapon boxes only matter in modes where weapons can be picked up
+        let pickups_allowed = game_weapon_setup.mode == GameWeaponSelectionMode::StarterAndPickup
+            || game_weapon_setup.mode == GameWeaponSelectionMode::CustomStarterAndPickup;
+
+        let weapon_boxes: Vec<(f32, f32)> = arena_elements
+            .join()
+            .filter(|arena_element| arena_element.is_weapon_box && pickups_allowed)
+            .map(|arena_element| (arena_element.x, arena_element.y))
+            .collect();
+
+        let walls: Vec<&ArenaElement> = arena_elements
+            .join()
+            .filter(|arena_element| arena_element.obstacle_type == ObstacleType::Wall)
+            .collect();
+
+        let mut active_vehicles: Vec<ActiveVehicle> = Vec::new();
+        for (player, vehicle, weapon_array, transform) in
+            (&players, &vehicles, &weapon_arrays, &transforms).join()
+        {
+            if vehicle.state == VehicleState::Active {
+                let (_, _, vehicle_angle) = transform.rotation().euler_angles();
+
+                active_vehicles.push(ActiveVehicle {
+                    id: player.id,
+                    team: player.team,
+                    x: transform.translation().x,
+                    y: transform.translation().y,
+                    dx: vehicle.dx,
+                    dy: vehicle.dy,
+                    fire_angle: vehicle_angle + get_primary_weapon_angle(weapon_array),
+                    health_pct: vehicle.health.value / vehicle.health.max * 100.0,
+                    on_hill: player.on_hill,
+                });
+            }
+        }
+
+        let team_focus_targets = pick_team_focus_targets(&active_vehicles);
+
+        for (player, vehicle, weapon_array, transform) in
+            (&mut players, &vehicles, &weapon_arrays, &transforms).join()
+        {
+            if !player.is_bot {
+                continue;
+            }
+
+            player.bot_move_cooldown -= dt;
+            player.path_cooldown -= dt;
+            player.bot_reaction_timer -= dt;
+            player.bot_weapon_box_timer -= dt;
+
+            if vehicle.state != VehicleState::Active {
+                player.bot_intent = BotIntent::default();
+                continue;
+            }
+
+            let vehicle_x = transform.translation().x;
+            let vehicle_y = transform.translation().y;
+            let (_, _, vehicle_angle) = transform.rotation().euler_angles();
+
+            player.bot_attack_target = None; //only set while attacking
+
+            //Remember where the closest enemy was last seen, for a while
+            if let (true, Some(angle), Some(dist)) = (
+                vehicle.closest_vehicle_in_sight,
+                vehicle.angle_to_closest_vehicle,
+                vehicle.dist_to_closest_vehicle,
+            ) {
+                player.bot_last_seen_enemy = Some((
+                    vehicle_x - angle.sin() * dist,
+                    vehicle_y + angle.cos() * dist,
+                ));
+                player.bot_last_seen_timer = BOT_ENEMY_MEMORY_TIME;
+            } else {
+                player.bot_last_seen_timer -= dt;
+
+                if player.bot_last_seen_timer <= 0.0 {
+                    player.bot_last_seen_enemy = None;
+                }
+            }
+
+            //Getting unstuck takes priority over any scored behavior
+            if player.bot_unstuck_timer > 0.0 {
+                player.bot_unstuck_timer -= dt;
+
+                //back away from whatever is blocking while turning,
+                //cars can only turn while moving so they need the reverse to turn at all
+                player.bot_intent = BotIntent {
+                    accel: Some(-0.6),
+                    turn: Some(player.bot_unstuck_turn),
+                    ..BotIntent::default()
+                };
+
+                if player.bot_unstuck_timer <= 0.0 {
+                    player.path_plan = None; //replan from wherever the bot ended up
+                    player.bot_stuck_check_position = (vehicle_x, vehicle_y);
+                    player.bot_stuck_timer = 0.0;
+                }
+                continue;
+            }
+
+            player.bot_stuck_timer += dt;
+
+            if player.bot_stuck_timer >= BOT_STUCK_CHECK_TIME {
+                let (check_x, check_y) = player.bot_stuck_check_position;
+                let moved = (vehicle_x - check_x).hypot(vehicle_y - check_y);
+
+                let trying_to_move = match player.bot_intent.accel {
+                    Some(accel) => accel.abs() > 0.1,
+                    None => false,
+                };
+
+                if trying_to_move && moved < BOT_STUCK_DIST {
+                    debug!("{} stuck", player.id);
+                    player.bot_unstuck_timer = BOT_UNSTUCK_TIME;
+
+                    if rng.gen::<bool>() {
+                        player.bot_unstuck_turn = 1.0;
+                    } else {
+                        player.bot_unstuck_turn = -1.0;
+                    }
+                }
+
+                player.bot_stuck_check_position = (vehicle_x, vehicle_y);
+                player.bot_stuck_timer = 0.0;
+            }
+
+            //a path is found to every box in range, so only look them over now and then,
+            //or straight away once the chosen box has been picked up
+            let weapon_box_gone = match player.bot_weapon_box {
+                Some((box_x, box_y, _)) => !weapon_boxes.contains(&(box_x, box_y)),
+                None => false,
+            };
+
+            if player.bot_weapon_box_timer <= 0.0 || weapon_box_gone {
+                player.bot_weapon_box_timer = BOT_PICKUP_EVAL_TIME;
+
+                player.bot_weapon_box = evaluate_weapon_boxes(
+                    player,
+                    weapon_array,
+                    vehicle_x,
+                    vehicle_y,
+                    &weapon_boxes,
+                    &active_vehicles,
+                    &arena_nav_mesh_final,
+                );
+            }
+
+            let best_weapon_box = player.bot_weapon_box;
+
+            //bots only reconsider their behavior after their reaction delay
+            if player.bot_reaction_timer <= 0.0 || player.bot_mode == BotMode::Sleep {
+                player.bot_reaction_timer = player.bot_profile.reaction_delay;
+
+                let aim_error = player.bot_profile.aim_error;
+                if aim_error > 0.0 {
+                    player.bot_aim_offset = rng.gen_range(-aim_error, aim_error) / 180.0 * PI;
+                } else {
+                    player.bot_aim_offset = 0.0;
+                }
+
+                let fleeing_storm = arena_storm.active
+                    && arena_storm.dist_outside(vehicle_x, vehicle_y) > -BOT_STORM_EDGE_MARGIN;
+
+                let scores = score_behaviors(
+                    player,
+                    vehicle,
+                    weapon_array,
+                    &game_mode_setup,
+                    fleeing_storm,
+                    best_weapon_box,
+                    get_hill_holder(player, &active_vehicles).is_some(),
+                );
+
+                //pick the best scoring behavior, ties are broken randomly
+                let mut best_score = std::f32::MIN;
+                let mut best_behaviors: Vec<BotMode> = Vec::new();
+
+                for (behavior, score) in scores.iter() {
+                    let score = if *behavior == player.bot_mode {
+                        score + BOT_BEHAVIOR_STICKINESS
+                    } else {
+                        *score
+                    };
+
+                    if score > best_score {
+                        best_score = score;
+                        best_behaviors = vec![behavior.clone()];
+                    } else if (score - best_score).abs() < 0.0001 {
+                        best_behaviors.push(behavior.clone());
+                    }
+                }
+
+                let behavior = best_behaviors[rng.gen_range(0, best_behaviors.len())].clone();
+
+                if behavior != player.bot_mode {
+                    debug!("{} {:?}", player.id, behavior);
+
+                    if is_attacking(&behavior) && !is_attacking(&player.bot_mode) {
+                        player.last_made_hit_timer = 0.0;
+                    }
+
+                    //gave up on an attack that wasn't landing any hits
+                    if is_attacking(&player.bot_mode)
+                        && player.bot_mode != BotMode::Swording
+                        && player.last_made_hit_timer > BOT_NO_HIT_GIVE_UP_TIME
+                    {
+                        player.bot_move_cooldown = BOT_NO_HIT_MOVE_COOLDOWN;
+                    }
+
+                    player.bot_mode = behavior;
+                }
+            }
+
+            let vehicle_weight = determine_vehicle_weight(vehicle);
+            let thrust_friction_decel_rate: f32 = 30.0 * vehicle.engine_force / vehicle_weight;
+
+            player.bot_intent = match player.bot_mode {
+                BotMode::Racing if racing_line.points.len() > 2 => {
+                    let mut intent = follow_racing_line(
+                        player,
+                        vehicle,
+                        vehicle_x,
+                        vehicle_y,
+                        vehicle_angle,
+                        &racing_line,
+                        &active_vehicles,
+                    );
+                    intent.primary_fire = Some(true);
+                    intent.secondary_fire = Some(rng.gen::<bool>());
+                    intent
+                }
+                BotMode::Racing => {
+                    //No racing line, so just path to the next checkpoint
+                    let next_checkpoint;
+                    if player.checkpoint_completed as usize
+                        >= (self.arena_properties.race_checkpoints.len() - 1)
+                    {
+                        //return to finish line
+                        next_checkpoint = self.arena_properties.race_checkpoints[0];
+                    } else {
+                        //go to next checkpoint
+                        next_checkpoint = self.arena_properties.race_checkpoints
+                            [(player.checkpoint_completed + 1) as usize];
+                    }
+
+                    player.path_target = Some((next_checkpoint.x, next_checkpoint.y, 0.0));
+
+                    let mut intent = follow_path(
+                        player,
+                        vehicle,
+                        vehicle_x,
+                        vehicle_y,
+                        vehicle_angle,
+                        thrust_friction_decel_rate,
+                        1.0,
+                    );
+                    intent.primary_fire = Some(true);
+                    intent.secondary_fire = Some(rng.gen::<bool>());
+                    intent
+                }
+                BotMode::StopAim | BotMode::StrafeAim | BotMode::Chasing | BotMode::Swording => {
+                    player.last_made_hit_timer += dt;
+
+                    let target = get_attack_target(
+                        player,
+                        vehicle,
+                        vehicle_x,
+                        vehicle_y,
+                        &team_focus_targets,
+                        &active_vehicles,
+                        &walls,
+                    );
+
+                    player.bot_attack_target = match target {
+                        Some((angle, dist, _)) => Some((
+                            vehicle_x - angle.sin() * dist,
+                            vehicle_y + angle.cos() * dist,
+                        )),
+                        None => None,
+                    };
+
+                    let mut intent = aim_at_vehicle(player, weapon_array, vehicle_angle, target);
+
+                    if player.bot_move_cooldown < 0.0 {
+                        player.bot_move_cooldown = player.bot_move_cooldown_reset;
+
+                        if player.bot_mode == BotMode::StrafeAim {
+                            let left_or_right_strafe = rng.gen::<bool>();
+
+                            if left_or_right_strafe {
+                                intent.strafe = Some(0.8);
+                            } else {
+                                intent.strafe = Some(-0.8);
+                            }
+                        } else {
+                            intent.strafe = Some(0.0);
+                        }
+                    } else {
+                        intent.strafe = player.bot_intent.strafe;
+                    }
+
+                    //don't waste shots on walls
+                    let target_in_sight = match target {
+                        Some((angle, dist, _)) => has_line_of_sight(
+                            &walls,
+                            vehicle_x,
+                            vehicle_y,
+                            vehicle_x - angle.sin() * dist,
+                            vehicle_y + angle.cos() * dist,
+                        ),
+                        None => false,
+                    };
+
+                    //hold fire rather than shoot a teammate in the back
+                    let target_dist = match target {
+                        Some((_, dist, _)) => dist,
+                        None => BOT_LINE_OF_FIRE_RANGE,
+                    };
+
+                    let fire_angle = vehicle_angle + get_primary_weapon_angle(weapon_array);
+
+                    let teammate_in_the_way = active_vehicles.iter().any(|other| {
+                        other.id != player.id
+                            && other.team == player.team
+                            && distance_from_line_of_fire(
+                                vehicle_x,
+                                vehicle_y,
+                                fire_angle,
+                                target_dist,
+                                other.x,
+                                other.y,
+                            )
+                            .is_some()
+                    });
+
+                    let clear_shot = target_in_sight && !teammate_in_the_way;
+
+                    intent.primary_fire = Some(clear_shot);
+                    intent.secondary_fire = Some(clear_shot && rng.gen::<bool>());
+
+                    //save the ability for when the fight turns against us
+                    intent.ability = Some(vehicle.health.value < vehicle.health.max * 0.5);
+                    intent
+                }
+                _ => {
+                    //Movement behaviors, only differ in where they want to go
+                    if player.bot_mode == BotMode::RunTo {
+                        player.path_target = Some((arena_storm.x, arena_storm.y, 0.0));
+                    } else if player.bot_mode == BotMode::TakeTheHill {
+                        if player.on_hill {
+                            player.path_target = None;
+                        } else {
+                            player.path_target =
+                                Some(closest_hill(&self.arena_properties, vehicle_x, vehicle_y));
+                        }
+                    } else if player.bot_mode == BotMode::GuardTheHill {
+                        if let Some(holder) = get_hill_holder(player, &active_vehicles) {
+                            player.path_target = Some(get_hill_guard_point(
+                                &self.arena_properties,
+                                holder,
+                                &active_vehicles,
+                                vehicle_x,
+                                vehicle_y,
+                            ));
+                        } else {
+                            //teammate lost the hill, re-evaluate next frame
+                            player.path_target = None;
+                            player.bot_mode = BotMode::Sleep;
+                        }
+                    } else if player.bot_mode == BotMode::SearchLastSeen {
+                        match player.bot_last_seen_enemy {
+                            Some((last_seen_x, last_seen_y))
+                                if (last_seen_x - vehicle_x).hypot(last_seen_y - vehicle_y)
+                                    > BOT_SEARCH_REACHED_DIST =>
+                            {
+                                player.path_target = Some((last_seen_x, last_seen_y, 0.0));
+                            }
+                            _ => {
+                                //nothing there or forgotten, re-evaluate next frame
+                                player.bot_last_seen_enemy = None;
+                                player.path_target = None;
+                                player.bot_mode = BotMode::Sleep;
+                            }
+                        }
+                    } else if player.bot_mode == BotMode::PickUpWeaponBox {
+                        if let Some((box_x, box_y, _)) = best_weapon_box {
+                            player.path_target = Some((box_x, box_y, 0.0));
+                        } else {
+                            //box was taken or is no longer worth it, re-evaluate next frame
+                            player.path_target = None;
+                            player.bot_mode = BotMode::Sleep;
+                        }
+                    } else if player.path_target.is_none() || player.path_cooldown <= 0.0 {
+                        let random_x;
+                        let random_y;
+
+                        if arena_storm.active {
+                            //only wander within the safe zone
+                            let (safe_x, safe_y) = arena_storm.random_point_inside();
+                            random_x = safe_x;
+                            random_y = safe_y;
+                        } else {
+                            random_x = rng.gen_range(0.0, self.arena_properties.width) as f32;
+                            random_y = rng.gen_range(0.0, self.arena_properties.height) as f32;
+                        }
+
+                        player.path_target = Some((random_x, random_y, 0.0));
+
+                        player.path_cooldown = player.path_cooldown_reset;
+                    }
+
+                    let mut intent = follow_path(
+                        player,
+                        vehicle,
+                        vehicle_x,
+                        vehicle_y,
+                        vehicle_angle,
+                        thrust_friction_decel_rate,
+                        10.0,
+                    );
+
+                    if player.bot_mode == BotMode::RunTo {
+                        intent.strafe = Some(0.0);
+                    } else if player.bot_mode == BotMode::Mining {
+                        intent.primary_fire = Some(true);
+                        intent.secondary_fire = Some(rng.gen::<bool>());
+                    } else if player.bot_mode == BotMode::Repairing {
+                        intent.repair = Some(true);
+                    }
+
+                    //top up half empty magazines while there's nothing to shoot at
+                    if player.bot_mode != BotMode::Mining {
+                        intent.reload = Some(weapon_array.installed.iter().any(|weapon_install| {
+                            let weapon = &weapon_install.weapon;
+                            weapon.can_reload() && weapon.magazine < weapon.stats.magazine_size / 2
+                        }));
+                    }
+                    intent
+                }
+            };
+
+            //step out of a teammate's line of fire
+            if player.bot_mode != BotMode::Racing {
+                if let Some((away_x, away_y)) =
+                    get_teammate_line_of_fire_exit(player, vehicle_x, vehicle_y, &active_vehicles)
+                {
+                    //left of a vehicle at angle a is (-cos a, -sin a), forwards is (-sin a, cos a)
+                    let left_dot = -away_x * vehicle_angle.cos() - away_y * vehicle_angle.sin();
+                    let forward_dot = -away_x * vehicle_angle.sin() + away_y * vehicle_angle.cos();
+
+                    if vehicle.movement_type == VehicleMovementType::Hover {
+                        player.bot_intent.strafe = Some(left_dot.signum() * 0.8);
+                    } else if player
+                        .bot_intent
+                        .accel
+                        .map_or(true, |accel| accel.abs() < 0.1)
+                    {
+                        player.bot_intent.accel = Some(forward_dot.signum() * 0.6);
+                    }
+                }
+            }
+
+            if player.bot_move_cooldown < 0.0 {
+                player.bot_move_cooldown = player.bot_move_cooldown_reset;
+            }
+        }
+    }
+}
+
+//Snapshot of an active vehicle, shared with every bot for this frame
+struct ActiveVehicle {
+    id: usize,
+    team: i32,
+    x: f32,
+    y: f32,
+    dx: f32,
+    dy: f32,
+    fire_angle: f32, //direction the primary weapon is pointed
+    health_pct: f32,
+    on_hill: bool,
+}
+
+fn get_primary_weapon_angle(weapon_array: &WeaponArray) -> f32 {
+    if weapon_array.installed.len() > 0 {
+        let primary_weapon_install = &weapon_array.installed[PRIMARY_WEAPON_INDEX];
+
+        let mounted_angle;
+        if let Some(mounted_angle_from_install) = primary_weapon_install.mounted_angle {
+            mounted_angle = mounted_angle_from_install;
+        } else {
+            mounted_angle = 0.0;
+        }
+
+        mounted_angle
+            + primary_weapon_install
+                .weapon
+                .stats
+                .fire_stats
+                .mount_angle_special_offset
+    } else {
+        0.0
+    }
+}
+
+//Each team with more than one vehicle picks one enemy to gang up on,
+//preferring enemies that are close to the whole team and already damaged
+fn pick_team_focus_targets(active_vehicles: &Vec<ActiveVehicle>) -> HashMap<i32, usize> {
+    let mut team_focus_targets = HashMap::new();
+
+    let mut teams: Vec<i32> = active_vehicles.iter().map(|v| v.team).collect();
+    teams.sort();
+    teams.dedup();
+
+    for team in teams.iter() {
+        let members: Vec<&ActiveVehicle> =
+            active_vehicles.iter().filter(|v| v.team == *team).collect();
+
+        if members.len() < 2 {
+            continue;
+        }
+
+        let mut best_target: Option<(usize, f32)> = None;
+
+        for enemy in active_vehicles.iter().filter(|v| v.team != *team) {
+            let avg_dist = members
+                .iter()
+                .map(|member| (member.x - enemy.x).hypot(member.y - enemy.y))
+                .sum::<f32>()
+                / members.len() as f32;
+
+            let score = avg_dist * (0.5 + 0.5 * enemy.health_pct / 100.0);
+
+            if best_target.map_or(true, |(_, best_score)| score < best_score) {
+                best_target = Some((enemy.id, score));
+            }
+        }
+
+        if let Some((target_id, _)) = best_target {
+            team_focus_targets.insert(*team, target_id);
+        }
+    }
+
+    team_focus_targets
+}
+
+//(angle, distance, velocity) of the vehicle to attack, the team's focus target when
+//it is in range and in sight, otherwise whichever enemy is closest
+fn get_attack_target(
+    player: &Player,
+    vehicle: &Vehicle,
+    vehicle_x: f32,
+    vehicle_y: f32,
+    team_focus_targets: &HashMap<i32, usize>,
+    active_vehicles: &Vec<ActiveVehicle>,
+    walls: &Vec<&ArenaElement>,
+) -> Option<(f32, f32, (f32, f32))> {
+    if let Some(target_id) = team_focus_targets.get(&player.team) {
+        if let Some(target) = active_vehicles.iter().find(|v| v.id == *target_id) {
+            let dist = (target.x - vehicle_x).hypot(target.y - vehicle_y);
+
+            if dist < BOT_FOCUS_FIRE_RANGE
+                && has_line_of_sight(walls, vehicle_x, vehicle_y, target.x, target.y)
+            {
+                let x_diff = vehicle_x - target.x;
+                let y_diff = vehicle_y - target.y;
+
+                let target_angle = y_diff.atan2(x_diff) + (PI / 2.0); //rotate by PI/2 to line up with 0deg is pointed towards top
+
+                return Some((target_angle, dist, (target.dx, target.dy)));
+            }
+        }
+    }
+
+    match (
+        vehicle.angle_to_closest_vehicle,
+        vehicle.dist_to_closest_vehicle,
+    ) {
+        (Some(angle), Some(dist)) => Some((
+            angle,
+            dist,
+            vehicle.velocity_of_closest_vehicle.unwrap_or((0.0, 0.0)),
+        )),
+        _ => None,
+    }
+}
+
+//How far to the left (positive) or right of a line of fire a point is,
+//None if the point is out of the line of fire
+fn distance_from_line_of_fire(
+    from_x: f32,
+    from_y: f32,
+    fire_angle: f32,
+    range: f32,
+    x: f32,
+    y: f32,
+) -> Option<f32> {
+    let (dir_x, dir_y) = (-fire_angle.sin(), fire_angle.cos());
+
+    let along = (x - from_x) * dir_x + (y - from_y) * dir_y;
+    let lateral = (x - from_x) * -dir_y + (y - from_y) * dir_x;
+
+    if along > 0.0 && along < range && lateral.abs() < BOT_LINE_OF_FIRE_WIDTH {
+        Some(lateral)
+    } else {
+        None
+    }
+}
+
+//Direction to move to get out of the closest teammate's line of fire, if in one
+fn get_teammate_line_of_fire_exit(
+    player: &Player,
+    vehicle_x: f32,
+    vehicle_y: f32,
+    active_vehicles: &Vec<ActiveVehicle>,
+) -> Option<(f32, f32)> {
+    let mut exit: Option<((f32, f32), f32)> = None;
+
+    for teammate in active_vehicles.iter() {
+        if teammate.id == player.id || teammate.team != player.team {
+            continue;
+        }
+
+        if let Some(lateral) = distance_from_line_of_fire(
+            teammate.x,
+            teammate.y,
+            teammate.fire_angle,
+            BOT_LINE_OF_FIRE_RANGE,
+            vehicle_x,
+            vehicle_y,
+        ) {
+            let dist = (teammate.x - vehicle_x).hypot(teammate.y - vehicle_y);
+
+            if exit.map_or(true, |(_, closest_dist)| dist < closest_dist) {
+                //left of the line of fire is (-cos a, -sin a), step further to whichever side the bot is on
+                let side = if lateral >= 0.0 { 1.0 } else { -1.0 };
+                let away = (
+                    -teammate.fire_angle.cos() * side,
+                    -teammate.fire_angle.sin() * side,
+                );
+
+                exit = Some((away, dist));
+            }
+        }
+    }
+
+    exit.map(|(away, _)| away)
+}
+
+//Teammate already holding the hill, only one vehicle on the hill scores so the rest guard it
+//Ties between teammates that are both on the hill go to the lowest id
+fn get_hill_holder<'a>(
+    player: &Player,
+    active_vehicles: &'a Vec<ActiveVehicle>,
+) -> Option<&'a ActiveVehicle> {
+    active_vehicles.iter().find(|other| {
+        other.id != player.id
+            && other.team == player.team
+            && other.on_hill
+            && (!player.on_hill || other.id < player.id)
+    })
+}
+
+//Spot just outside the holder's hill, between the hill and the closest enemy
+fn get_hill_guard_point(
+    arena_properties: &ArenaProperties,
+    holder: &ActiveVehicle,
+    active_vehicles: &Vec<ActiveVehicle>,
+    vehicle_x: f32,
+    vehicle_y: f32,
+) -> (f32, f32, f32) {
+    let mut hill = (holder.x, holder.y, 0.0);
+    let mut closest_dist = std::f32::MAX;
+
+    for king_hill in arena_properties.king_hills.iter() {
+        let dist = (king_hill.x - holder.x).hypot(king_hill.y - holder.y);
+
+        if dist < closest_dist {
+            closest_dist = dist;
+            hill = (king_hill.x, king_hill.y, king_hill.radius);
+        }
+    }
+
+    let (hill_x, hill_y, hill_radius) = hill;
+
+    //face the closest threat, or just hold the side of the hill the bot is already on
+    let mut watch = (vehicle_x, vehicle_y);
+    let mut closest_enemy_dist = std::f32::MAX;
+
+    for enemy in active_vehicles.iter() {
+        if enemy.team == holder.team {
+            continue;
+        }
+
+        let dist = (enemy.x - hill_x).hypot(enemy.y - hill_y);
+
+        if dist < closest_enemy_dist {
+            closest_enemy_dist = dist;
+            watch = (enemy.x, enemy.y);
+        }
+    }
+
+    let watch_angle = (watch.1 - hill_y).atan2(watch.0 - hill_x);
+    let guard_dist = hill_radius + BOT_HILL_GUARD_DIST;
+
+    (
+        hill_x + guard_dist * watch_angle.cos(),
+        hill_y + guard_dist * watch_angle.sin(),
+        0.0,
+    )
+}
+
+fn is_attacking(bot_mode: &BotMode) -> bool {
+    *bot_mode == BotMode::StopAim
+        || *bot_mode == BotMode::StrafeAim
+        || *bot_mode == BotMode::Chasing
+        || *bot_mode == BotMode::Swording
+        || *bot_mode == BotMode::Mining
+}
+
+fn score_behaviors(
+    player: &Player,
+    vehicle: &Vehicle,
+    weapon_array: &WeaponArray,
+    game_mode_setup: &GameModeSetup,
+    fleeing_storm: bool,
+    best_weapon_box: Option<(f32, f32, f32)>,
+    teammate_holds_hill: bool,
+) -> Vec<(BotMode, f32)> {
+    let mut scores: Vec<(BotMode, f32)> = Vec::new();
+
+    if game_mode_setup.game_mode == GameModes::Race {
+        scores.push((BotMode::Racing, 1.0));
+        return scores;
+    }
+
+    scores.push((BotMode::RunRandom, BOT_WANDER_SCORE));
+
+    //Head back inside the shrinking arena boundary before doing anything else
+    if fleeing_storm {
+        scores.push((BotMode::RunTo, BOT_STORM_SCORE));
+        return scores;
+    }
+
+    if game_mode_setup.game_mode == GameModes::KingOfTheHill {
+        if teammate_holds_hill {
+            scores.push((BotMode::GuardTheHill, BOT_HILL_SCORE));
+        } else if !player.on_hill {
+            scores.push((BotMode::TakeTheHill, BOT_HILL_SCORE));
+        }
+    }
+
+    let profile = &player.bot_profile;
+
+    let health_pct = vehicle.health.value / vehicle.health.max * 100.0;
+
+    let damaged = health_pct < profile.repair_threshold
+        || (vehicle.shield.max > 0.0 && vehicle.shield.value == 0.0);
+
+    let enemy_far_away = match vehicle.dist_to_closest_vehicle {
+        Some(dist) => dist > BOT_REPAIR_SAFE_DISTANCE,
+        None => true,
+    };
+
+    if damaged && enemy_far_away && player.last_hit_timer > 1.0 {
+        let health_missing_pct = 1.0 - vehicle.health.value / vehicle.health.max;
+        scores.push((
+            BotMode::Repairing,
+            BOT_REPAIR_SCORE + 0.3 * health_missing_pct,
+        ));
+    }
+
+    if let Some((_, _, box_value)) = best_weapon_box {
+        if profile.weapon_box_greed > 0.0 {
+            scores.push((
+                BotMode::PickUpWeaponBox,
+                BOT_PICKUP_SCORE * profile.weapon_box_greed * box_value + 0.1,
+            ));
+        }
+    }
+
+    if weapon_array.installed.len() > 0 {
+        let primary_weapon = &weapon_array.installed[PRIMARY_WEAPON_INDEX].weapon;
+
+        //bots that just gave up on an attack wander for a while before re-engaging
+        let can_engage = is_attacking(&player.bot_mode) || player.bot_move_cooldown < 0.0;
+
+        let gave_up = is_attacking(&player.bot_mode)
+            && player.bot_mode != BotMode::Swording
+            && player.last_made_hit_timer > BOT_NO_HIT_GIVE_UP_TIME;
+
+        if let (Some(dist), true) = (
+            vehicle.dist_to_closest_vehicle,
+            vehicle.closest_vehicle_in_sight,
+        ) {
+            if can_engage && !gave_up {
+                let engage_score =
+                    BOT_ENGAGE_SCORE * profile.aggression * (1.0 - dist / BOT_ENGAGE_RANGE);
+
+                let attack_mode = if primary_weapon.stats.fire_stats.attached {
+                    //Typically just LaserSword
+                    BotMode::Swording
+                } else if primary_weapon.stats.fire_stats.shot_speed <= 0.0 {
+                    //Typically just Mines or Traps
+                    BotMode::Mining
+                } else if dist > primary_weapon.range_calc {
+                    BotMode::Chasing
+                } else if player.bot_mode == BotMode::StrafeAim || player.last_hit_timer < 2.0 {
+                    BotMode::StrafeAim
+                } else {
+                    BotMode::StopAim
+                };
+
+                scores.push((attack_mode, engage_score));
+            }
+
+            //enemies out of engagement range may still be hunted down
+            if dist > BOT_ENGAGE_RANGE && !primary_weapon.stats.fire_stats.attached {
+                scores.push((BotMode::Chasing, BOT_WANDER_SCORE * profile.aggression));
+            }
+        } else if player.bot_last_seen_enemy.is_some() && can_engage {
+            //lost sight of them, go and look where they were last seen
+            scores.push((
+                BotMode::SearchLastSeen,
+                BOT_SEARCH_SCORE * profile.aggression,
+            ));
+        }
+    }
+
+    scores
+}
+
+//Find the most worthwhile weapon box as (x, y, value), value is roughly 0.0 to 1.0
+fn evaluate_weapon_boxes(
+    player: &Player,
+    weapon_array: &WeaponArray,
+    vehicle_x: f32,
+    vehicle_y: f32,
+    weapon_boxes: &Vec<(f32, f32)>,
+    active_vehicles: &Vec<ActiveVehicle>,
+    arena_nav_mesh_final: &ArenaNavMeshFinal,
+) -> Option<(f32, f32, f32)> {
+    //picking up a box replaces the secondary weapon
+    let weapon_need;
+    if weapon_array.installed.len() < 2 {
+        weapon_need = 1.0;
+    } else if weapon_array.installed[SECONDARY_WEAPON_INDEX]
+        .weapon
+        .is_out_of_ammo()
+    {
+        weapon_need = 0.8;
+    } else {
+        //already holding a picked up weapon, a box is just a re-roll
+        weapon_need = 0.2;
+    }
+
+    let pickup_range = BOT_PICKUP_RANGE * player.bot_profile.weapon_box_greed;
+
+    let mut best_box: Option<(f32, f32, f32)> = None;
+
+    for (box_x, box_y) in weapon_boxes.iter() {
+        let straight_dist = (box_x - vehicle_x).hypot(box_y - vehicle_y);
+
+        if straight_dist > pickup_range {
+            continue;
+        }
+
+        //walls may make a nearby box a long drive away
+        let path_dist;
+        if let Some(mesh) = &arena_nav_mesh_final.mesh {
+            let path = mesh.find_path(
+                (vehicle_x, vehicle_y, 0.0).into(),
+                (*box_x, *box_y, 0.0).into(),
+                NavQuery::Accuracy,
+                NavPathMode::Accuracy,
+            );
+
+            if let Some(path) = path {
+                let mut total = 0.0;
+                for index in 1..path.len() {
+                    total += (path[index].x - path[index - 1].x)
+                        .hypot(path[index].y - path[index - 1].y);
+                }
+                path_dist = total;
+            } else {
+                continue; //unreachable
+            }
+        } else {
+            path_dist = straight_dist;
+        }
+
+        if path_dist > BOT_PICKUP_PATH_RANGE {
+            continue;
+        }
+
+        //enemies near the box, or closer to it than this bot, make it less worth the trip
+        let mut enemy_factor = 1.0;
+        for enemy in active_vehicles.iter() {
+            if enemy.team == player.team {
+                continue;
+            }
+
+            let enemy_dist = (box_x - enemy.x).hypot(box_y - enemy.y);
+
+            if enemy_dist < straight_dist {
+                enemy_factor *= 0.5;
+            } else if enemy_dist < BOT_PICKUP_ENEMY_CLEARANCE {
+                enemy_factor *= 0.75;
+            }
+        }
+
+        let value = weapon_need * enemy_factor * (1.0 - path_dist / BOT_PICKUP_PATH_RANGE);
+
+        if best_box.map_or(true, |(_, _, best_value)| value > best_value) {
+            best_box = Some((*box_x, *box_y, value));
+        }
+    }
+
+    best_box
+}
+
+fn closest_hill(arena_properties: &ArenaProperties, x: f32, y: f32) -> (f32, f32, f32) {
+    let mut target = (
+        arena_properties.width / 2.0,
+        arena_properties.height / 2.0,
+        0.0,
+    );
+    let mut closest_dist = std::f32::MAX;
+
+    for king_hill in arena_properties.king_hills.iter() {
+        let dist = (king_hill.x - x).hypot(king_hill.y - y);
+
+        if dist < closest_dist {
+            closest_dist = dist;
+            target = (king_hill.x, king_hill.y, 0.0);
+        }
+    }
+
+    target
+}
+
+//Steer towards a point a little further along the nav mesh path plan
+//The plan is already the shortest, string-pulled path, looking ahead smooths out its corners
+fn follow_path(
+    player: &mut Player,
+    vehicle: &Vehicle,
+    vehicle_x: f32,
+    vehicle_y: f32,
+    vehicle_angle: f32,
+    thrust_friction_decel_rate: f32,
+    velocity_scale: f32,
+) -> BotIntent {
+    let mut rng = rand::thread_rng();
+    let mut intent = BotIntent::default();
+
+    if let Some(path_plan) = player.path_plan.clone() {
+        //pass by any points already reached
+        while player.path_plan_index < path_plan.len() {
+            let (point_x, point_y, _) = path_plan[player.path_plan_index];
+
+            if (point_x - vehicle_x).hypot(point_y - vehicle_y) < BOT_PATH_POINT_REACHED_DIST {
+                player.path_plan_index += 1;
+            } else {
+                break;
+            }
+        }
+
+        if player.path_plan_index < path_plan.len() {
+            //cars can't turn on the spot, so they look further ahead and start turning sooner
+            let lookahead_dist = match vehicle.movement_type {
+                VehicleMovementType::Car => BOT_PATH_LOOKAHEAD_DIST * 2.0,
+                VehicleMovementType::Tank => BOT_PATH_LOOKAHEAD_DIST,
+                VehicleMovementType::Hover => BOT_PATH_LOOKAHEAD_DIST,
+            };
+
+            let (target_x, target_y) = get_lookahead_point(
+                &path_plan,
+                player.path_plan_index,
+                vehicle_x,
+                vehicle_y,
+                lookahead_dist,
+            );
+
+            let dist_to_end =
+                remaining_path_dist(&path_plan, player.path_plan_index, vehicle_x, vehicle_y);
+
+            let sq_vel = vehicle.dx.powi(2) + vehicle.dy.powi(2);
+            let abs_vel = sq_vel.sqrt() * velocity_scale;
+
+            let approx_t_to_arrival = dist_to_end / abs_vel;
+            let approx_t_to_rest = abs_vel / thrust_friction_decel_rate;
+
+            let x_diff = vehicle_x - target_x;
+            let y_diff = vehicle_y - target_y;
+
+            let target_angle = y_diff.atan2(x_diff) + (PI / 2.0); //rotate by PI/2 to line up with 0deg is pointed towards top
+
+            let angle_diff = clean_angle(vehicle_angle - target_angle);
+
+            //turn harder the further off target, rather than a fixed turn rate
+            let turn_value = (angle_diff.abs() * 2.0).max(0.2).min(1.0);
+
+            if angle_diff > 0.001 {
+                intent.turn = Some(-turn_value);
+            } else if angle_diff < -0.001 {
+                intent.turn = Some(turn_value);
+            } else {
+                intent.turn = Some(0.0);
+            }
+
+            let braking = approx_t_to_arrival < approx_t_to_rest;
+
+            match vehicle.movement_type {
+                VehicleMovementType::Hover => {
+                    //hovercraft spin in place, and strafe out any sideways error
+                    if angle_diff.abs() < 0.2 {
+                        intent.accel = Some(if braking { 0.5 } else { 0.8 });
+                    } else {
+                        intent.accel = Some(0.0);
+                    }
+
+                    if angle_diff.abs() < PI / 2.0 {
+                        intent.strafe = Some(-angle_diff.sin() * 0.5);
+                    } else {
+                        intent.strafe = Some(0.0);
+                    }
+                }
+                VehicleMovementType::Tank => {
+                    //tanks spin in place, but slide badly if driven while turning
+                    if angle_diff.abs() < 0.3 {
+                        intent.accel = Some(if braking { 0.5 } else { 0.8 });
+                    } else {
+                        intent.accel = Some(0.0);
+                    }
+                }
+                VehicleMovementType::Car => {
+                    //cars need to keep moving to turn at all, slow down for the sharp corners
+                    if angle_diff.abs() > PI / 2.0 {
+                        intent.accel = Some(0.3);
+                    } else if braking || angle_diff.abs() > PI / 4.0 {
+                        intent.accel = Some(0.5);
+                    } else {
+                        intent.accel = Some(0.8);
+                    }
+                }
+            }
+        } else {
+            intent.accel = Some(0.0);
+            intent.turn = Some(0.0);
+        }
+    } else {
+        //random movement input when no path is specified
+        if player.bot_move_cooldown < 0.0 {
+            //issue new move command
+            intent.accel = Some(rng.gen_range(0.3, 0.5) as f32);
+            intent.turn = Some(rng.gen_range(-0.3, 0.3) as f32);
+        } else {
+            //hold previous random move
+            intent.accel = player.last_accel_input;
+            intent.turn = player.last_turn_input;
+        }
+    }
+
+    player.last_accel_input = intent.accel;
+    player.last_turn_input = intent.turn;
+
+    intent
+}
+
+//Drive the precomputed racing line, braking in time for the corners ahead
+fn follow_racing_line(
+    player: &mut Player,
+    vehicle: &Vehicle,
+    vehicle_x: f32,
+    vehicle_y: f32,
+    vehicle_angle: f32,
+    racing_line: &RacingLine,
+    active_vehicles: &Vec<ActiveVehicle>,
+) -> BotIntent {
+    let mut intent = BotIntent::default();
+    let len = racing_line.points.len();
+
+    //find the closest point, only searching a little ahead of the last one so
+    //the bot doesn't jump to a nearby part of the track it hasn't driven yet
+    let search: Vec<usize> = match player.racing_line_index {
+        Some(last_index) => (0..RACE_LINE_SEARCH_AHEAD)
+            .map(|offset| (last_index + offset) % len)
+            .collect(),
+        None => (0..len).collect(),
+    };
+
+    let mut closest_index = search[0];
+    let mut closest_dist = std::f32::MAX;
+
+    for idx in search.iter() {
+        let (x, y) = racing_line.points[*idx];
+        let dist = (x - vehicle_x).hypot(y - vehicle_y);
+
+        if dist < closest_dist {
+            closest_dist = dist;
+            closest_index = *idx;
+        }
+    }
+
+    if closest_dist > RACE_LINE_LOST_DIST {
+        player.racing_line_index = None;
+    } else {
+        player.racing_line_index = Some(closest_index);
+    }
+
+    let abs_vel = (vehicle.dx.powi(2) + vehicle.dy.powi(2)).sqrt();
+
+    //steer for a point further along the line
+    let lookahead_dist = RACE_LOOKAHEAD_DIST + abs_vel * RACE_LOOKAHEAD_TIME;
+
+    let mut target_index = closest_index;
+    let mut dist_along = 0.0;
+
+    while dist_along < lookahead_dist {
+        let next_index = (target_index + 1) % len;
+        let (x1, y1) = racing_line.points[target_index];
+        let (x2, y2) = racing_line.points[next_index];
+
+        dist_along += (x2 - x1).hypot(y2 - y1);
+        target_index = next_index;
+
+        if target_index == closest_index {
+            break; //tiny track, went all the way around
+        }
+    }
+
+    let (mut target_x, mut target_y) = racing_line.points[target_index];
+
+    //pull out of the line to get around a vehicle just ahead
+    let (dir_x, dir_y) = racing_line.direction(closest_index);
+    let (left_x, left_y) = (-dir_y, dir_x);
+
+    for other in active_vehicles.iter() {
+        if other.id == player.id {
+            continue;
+        }
+
+        let forward = (other.x - vehicle_x) * dir_x + (other.y - vehicle_y) * dir_y;
+        let lateral = (other.x - vehicle_x) * left_x + (other.y - vehicle_y) * left_y;
+
+        if forward > 0.0 && forward < RACE_OVERTAKE_DIST && lateral.abs() < RACE_OVERTAKE_WIDTH {
+            let room_left = racing_line.clearance_left[target_index] - RACE_OVERTAKE_WALL_MARGIN;
+            let room_right = racing_line.clearance_right[target_index] - RACE_OVERTAKE_WALL_MARGIN;
+
+            //pass on the side away from the other vehicle, unless there's no room there
+            let pass_left = if lateral < 0.0 {
+                room_left > RACE_OVERTAKE_OFFSET / 2.0 || room_left > room_right
+            } else {
+                !(room_right > RACE_OVERTAKE_OFFSET / 2.0 || room_right > room_left)
+            };
+
+            if pass_left {
+                let offset = RACE_OVERTAKE_OFFSET.min(room_left).max(0.0);
+                target_x += left_x * offset;
+                target_y += left_y * offset;
+            } else {
+                let offset = RACE_OVERTAKE_OFFSET.min(room_right).max(0.0);
+                target_x -= left_x * offset;
+                target_y -= left_y * offset;
+            }
+            break;
+        }
+    }
+
+    //fastest speed that still lets the vehicle slow down in time for every corner ahead
+    let vehicle_weight = determine_vehicle_weight(vehicle);
+    let thrust_decel_rate = 60.0 * vehicle.engine_force / vehicle_weight;
+    let thrust_friction_decel_rate = 30.0 * vehicle.engine_force / vehicle_weight;
+    let thrust_strafe_accel_rate = 60.0 * vehicle.engine_force / vehicle_weight;
+
+    let braking_decel = thrust_decel_rate + thrust_friction_decel_rate;
+
+    let cornering_accel = match vehicle.movement_type {
+        VehicleMovementType::Car => TIRE_LATERAL_FRICTION_DECEL_RATE * RACE_CAR_CORNER_GRIP,
+        VehicleMovementType::Tank => TANK_TRACK_LATERAL_FRICTION_DECEL_RATE * RACE_TANK_CORNER_GRIP,
+        VehicleMovementType::Hover => thrust_strafe_accel_rate + thrust_friction_decel_rate,
+    };
+
+    let mut target_speed = vehicle.max_velocity;
+    let mut plan_index = closest_index;
+    let mut plan_dist = 0.0;
+
+    while plan_dist < RACE_SPEED_PLAN_DIST {
+        let corner_speed_sq = cornering_accel * racing_line.corner_radii[plan_index];
+        let allowed_speed = (corner_speed_sq + 2.0 * braking_decel * plan_dist).sqrt();
+
+        target_speed = target_speed.min(allowed_speed);
+
+        let next_index = (plan_index + 1) % len;
+        let (x1, y1) = racing_line.points[plan_index];
+        let (x2, y2) = racing_line.points[next_index];
+
+        plan_dist += (x2 - x1).hypot(y2 - y1);
+        plan_index = next_index;
+
+        if plan_index == closest_index {
+            break;
+        }
+    }
+
+    let x_diff = vehicle_x - target_x;
+    let y_diff = vehicle_y - target_y;
+
+    let target_angle = y_diff.atan2(x_diff) + (PI / 2.0); //rotate by PI/2 to line up with 0deg is pointed towards top
+
+    let angle_diff = clean_angle(vehicle_angle - target_angle);
+
+    let turn_value = (angle_diff.abs() * 2.0).max(0.2).min(1.0);
+
+    if angle_diff > 0.001 {
+        intent.turn = Some(-turn_value);
+    } else if angle_diff < -0.001 {
+        intent.turn = Some(turn_value);
+    } else {
+        intent.turn = Some(0.0);
+    }
+
+    let throttle = if abs_vel > target_speed * 1.1 {
+        -1.0 //brake hard
+    } else if abs_vel > target_speed {
+        0.0 //coast
+    } else {
+        1.0
+    };
+
+    match vehicle.movement_type {
+        VehicleMovementType::Hover => {
+            intent.accel = Some(throttle * angle_diff.cos().max(0.0));
+
+            if angle_diff.abs() < PI / 2.0 {
+                intent.strafe = Some(-angle_diff.sin() * 0.5);
+            } else {
+                intent.strafe = Some(0.0);
+            }
+        }
+        VehicleMovementType::Tank => {
+            if angle_diff.abs() < 0.3 {
+                intent.accel = Some(throttle);
+            } else {
+                intent.accel = Some(throttle.min(0.0));
+            }
+        }
+        VehicleMovementType::Car => {
+            //cars need to keep moving to turn at all
+            if angle_diff.abs() > PI / 2.0 {
+                intent.accel = Some(0.3);
+            } else {
+                intent.accel = Some(throttle);
+            }
+        }
+    }
+
+    player.last_accel_input = intent.accel;
+    player.last_turn_input = intent.turn;
+
+    intent
+}
+
+//Point on the path plan about lookahead_dist further along than the vehicle
+fn get_lookahead_point(
+    path_plan: &Vec<(f32, f32, f32)>,
+    path_plan_index: usize,
+    vehicle_x: f32,
+    vehicle_y: f32,
+    lookahead_dist: f32,
+) -> (f32, f32) {
+    let mut remaining = lookahead_dist;
+    let mut from = (vehicle_x, vehicle_y);
+
+    for idx in path_plan_index..path_plan.len() {
+        let to = (path_plan[idx].0, path_plan[idx].1);
+        let leg_length = (to.0 - from.0).hypot(to.1 - from.1);
+
+        if leg_length >= remaining {
+            let t = remaining / leg_length;
+            return (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        }
+
+        remaining -= leg_length;
+        from = to;
+    }
+
+    from //the lookahead runs off the end of the plan
+}
+
+fn remaining_path_dist(
+    path_plan: &Vec<(f32, f32, f32)>,
+    path_plan_index: usize,
+    vehicle_x: f32,
+    vehicle_y: f32,
+) -> f32 {
+    let mut dist = 0.0;
+    let mut from = (vehicle_x, vehicle_y);
+
+    for idx in path_plan_index..path_plan.len() {
+        let to = (path_plan[idx].0, path_plan[idx].1);
+        dist += (to.0 - from.0).hypot(to.1 - from.1);
+        from = to;
+    }
+
+    dist
+}
+
+//Turn the primary weapon towards the target, driving at them for melee and chasing
+fn aim_at_vehicle(
+    player: &Player,
+    weapon_array: &WeaponArray,
+    vehicle_angle: f32,
+    target: Option<(f32, f32, (f32, f32))>,
+) -> BotIntent {
+    let mut intent = BotIntent::default();
+
+    if let Some((attack_angle, dist, (target_dx, target_dy))) = target {
+        //lead the target with the primary weapon's shots, then miss by the bot's aim error
+        let mut attack_angle = attack_angle;
+
+        if weapon_array.installed.len() > 0 {
+            let fire_stats = &weapon_array.installed[PRIMARY_WEAPON_INDEX]
+                .weapon
+                .stats
+                .fire_stats;
+
+            attack_angle = calc_lead_angle(
+                dist,
+                attack_angle,
+                target_dx,
+                target_dy,
+                fire_stats.shot_speed,
+                fire_stats.accel_rate,
+            );
+        }
+
+        let attack_angle = attack_angle + player.bot_aim_offset;
+
+        let turn_value = 1.0;
+
+        if weapon_array.installed.len() > 0 {
+            let weapon_angle = get_primary_weapon_angle(weapon_array);
+
+            //Prepare magnitude of Turning and Acceleration input
+            if player.bot_mode == BotMode::Swording || player.bot_mode == BotMode::Chasing {
+                if weapon_angle > PI / 4.0 || weapon_angle < -PI / 4.0 {
+                    intent.accel = Some(-0.6); //drive backwards sword fighting
+                } else {
+                    intent.accel = Some(0.6); //drive forwards sword fighting
+                }
+            } else {
+                //hold position around the preferred fighting range
+                let mut range_accel = 0.0;
+
+                if dist < player.bot_profile.preferred_range * BOT_PREFERRED_RANGE_MIN_PCT {
+                    range_accel = -0.4;
+                } else if dist > player.bot_profile.preferred_range * BOT_PREFERRED_RANGE_MAX_PCT {
+                    range_accel = 0.4;
+                }
+
+                if weapon_angle > PI / 4.0 || weapon_angle < -PI / 4.0 {
+                    range_accel = -range_accel;
+                }
+
+                intent.accel = Some(range_accel);
+            }
+
+            //Solve for Angle and Direction to turn
+            let angle_diff = clean_angle(vehicle_angle + weapon_angle - attack_angle);
+
+            if angle_diff > 0.001 {
+                intent.turn = Some(-turn_value);
+            } else if angle_diff < -0.001 {
+                intent.turn = Some(turn_value);
+            } else {
+                intent.turn = Some(0.0);
+            }
+        }
+    }
+
+    intent
+}
@@ -0,0 +1,221 @@
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, Write},
+    renderer::{debug_drawing::DebugLines, palette::Srgba},
+};
+
+use crate::components::{BotMode, Player, Vehicle, VehicleState};
+use crate::resources::{ArenaNavMesh, BotDebugOverlay};
+use crate::systems::{BOT_PREFERRED_RANGE_MAX_PCT, BOT_PREFERRED_RANGE_MIN_PCT};
+
+const OVERLAY_Z: f32 = 0.3;
+const BEHAVIOR_MARKER_RADIUS: f32 = 14.0;
+const CIRCLE_POINTS: u32 = 24;
+const LAST_SEEN_MARKER_SIZE: f32 = 6.0;
+const ATTACK_TARGET_MARKER_RADIUS: f32 = 10.0;
+
+//Draws why the bots do what they do: behavior, targets, path plans, ranges, sight lines and the nav mesh
+#[derive(SystemDesc, Default)]
+pub struct BotDebugOverlaySystem;
+
+impl<'s> System<'s> for BotDebugOverlaySystem {
+    type SystemData = (
+        Write<'s, DebugLines>,
+        Read<'s, BotDebugOverlay>,
+        ReadExpect<'s, ArenaNavMesh>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Vehicle>,
+        ReadStorage<'s, Transform>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut debug_lines_resource,
+            bot_debug_overlay,
+            arena_nav_mesh,
+            players,
+            vehicles,
+            transforms,
+        ): Self::SystemData,
+    ) {
+        if !bot_debug_overlay.enabled {
+            return;
+        }
+
+        //draw nav mesh zones as green debug lines
+        for (v1_index, v2_index, v3_index) in arena_nav_mesh.triangles.iter() {
+            let v1 = arena_nav_mesh.vertices[*v1_index];
+            let v2 = arena_nav_mesh.vertices[*v2_index];
+            let v3 = arena_nav_mesh.vertices[*v3_index];
+
+            debug_lines_resource.draw_line(
+                [v1.0, v1.1, v1.2].into(),
+                [v2.0, v2.1, v2.2].into(),
+                Srgba::new(0.2, 1.0, 0.2, 0.1),
+            );
+
+            debug_lines_resource.draw_line(
+                [v2.0, v2.1, v2.2].into(),
+                [v3.0, v3.1, v3.2].into(),
+                Srgba::new(0.2, 1.0, 0.2, 0.1),
+            );
+
+            debug_lines_resource.draw_line(
+                [v3.0, v3.1, v3.2].into(),
+                [v1.0, v1.1, v1.2].into(),
+                Srgba::new(0.2, 1.0, 0.2, 0.1),
+            );
+        }
+
+        for (player, vehicle, transform) in (&players, &vehicles, &transforms).join() {
+            if !player.is_bot || vehicle.state != VehicleState::Active {
+                continue;
+            }
+
+            let vehicle_x = transform.translation().x;
+            let vehicle_y = transform.translation().y;
+
+            //behavior, as the color of a ring around the bot
+            debug_lines_resource.draw_circle(
+                [vehicle_x, vehicle_y, OVERLAY_Z].into(),
+                BEHAVIOR_MARKER_RADIUS,
+                CIRCLE_POINTS,
+                get_bot_mode_color(&player.bot_mode),
+            );
+
+            //the band this bot tries to hold its attack target in,
+            //it backs off inside the inner radius and closes in outside the outer one
+            debug_lines_resource.draw_circle(
+                [vehicle_x, vehicle_y, OVERLAY_Z].into(),
+                player.bot_profile.preferred_range * BOT_PREFERRED_RANGE_MIN_PCT,
+                CIRCLE_POINTS * 2,
+                Srgba::new(1.0, 0.5, 0.1, 0.15),
+            );
+
+            debug_lines_resource.draw_circle(
+                [vehicle_x, vehicle_y, OVERLAY_Z].into(),
+                player.bot_profile.preferred_range * BOT_PREFERRED_RANGE_MAX_PCT,
+                CIRCLE_POINTS * 2,
+                Srgba::new(0.3, 0.6, 1.0, 0.15),
+            );
+
+            //planned path, and the target it leads to
+            if let Some(path_plan) = &player.path_plan {
+                for leg in path_plan.windows(2) {
+                    debug_lines_resource.draw_line(
+                        [leg[0].0, leg[0].1, OVERLAY_Z].into(),
+                        [leg[1].0, leg[1].1, OVERLAY_Z].into(),
+                        Srgba::new(0.2, 0.8, 1.0, 0.3),
+                    );
+                }
+            }
+
+            if let Some((target_x, target_y, _)) = player.path_target {
+                debug_lines_resource.draw_line(
+                    [vehicle_x, vehicle_y, OVERLAY_Z].into(),
+                    [target_x, target_y, OVERLAY_Z].into(),
+                    Srgba::new(1.0, 1.0, 1.0, 0.1),
+                );
+            }
+
+            //the enemy being attacked, the team's focus target or the closest enemy
+            if let Some((attack_x, attack_y)) = player.bot_attack_target {
+                debug_lines_resource.draw_line(
+                    [vehicle_x, vehicle_y, OVERLAY_Z].into(),
+                    [attack_x, attack_y, OVERLAY_Z].into(),
+                    Srgba::new(1.0, 0.5, 0.1, 0.5),
+                );
+
+                debug_lines_resource.draw_circle(
+                    [attack_x, attack_y, OVERLAY_Z].into(),
+                    ATTACK_TARGET_MARKER_RADIUS,
+                    CIRCLE_POINTS,
+                    Srgba::new(1.0, 0.5, 0.1, 0.5),
+                );
+            }
+
+            //line of sight to the closest enemy, green when clear and red when blocked
+            if let (Some(angle), Some(dist)) = (
+                vehicle.angle_to_closest_vehicle,
+                vehicle.dist_to_closest_vehicle,
+            ) {
+                let sight_color = if vehicle.closest_vehicle_in_sight {
+                    Srgba::new(0.2, 1.0, 0.2, 0.5)
+                } else {
+                    Srgba::new(1.0, 0.2, 0.2, 0.5)
+                };
+
+                debug_lines_resource.draw_line(
+                    [vehicle_x, vehicle_y, OVERLAY_Z].into(),
+                    [
+                        vehicle_x - angle.sin() * dist,
+                        vehicle_y + angle.cos() * dist,
+                        OVERLAY_Z,
+                    ]
+                    .into(),
+                    sight_color,
+                );
+            }
+
+            //where an enemy that went out of sight was last seen
+            if let Some((last_seen_x, last_seen_y)) = player.bot_last_seen_enemy {
+                if !vehicle.closest_vehicle_in_sight {
+                    debug_lines_resource.draw_line(
+                        [
+                            last_seen_x - LAST_SEEN_MARKER_SIZE,
+                            last_seen_y - LAST_SEEN_MARKER_SIZE,
+                            OVERLAY_Z,
+                        ]
+                        .into(),
+                        [
+                            last_seen_x + LAST_SEEN_MARKER_SIZE,
+                            last_seen_y + LAST_SEEN_MARKER_SIZE,
+                            OVERLAY_Z,
+                        ]
+                        .into(),
+                        Srgba::new(1.0, 0.2, 0.2, 0.5),
+                    );
+
+                    debug_lines_resource.draw_line(
+                        [
+                            last_seen_x - LAST_SEEN_MARKER_SIZE,
+                            last_seen_y + LAST_SEEN_MARKER_SIZE,
+                            OVERLAY_Z,
+                        ]
+                        .into(),
+                        [
+                            last_seen_x + LAST_SEEN_MARKER_SIZE,
+                            last_seen_y - LAST_SEEN_MARKER_SIZE,
+                            OVERLAY_Z,
+                        ]
+                        .into(),
+                        Srgba::new(1.0, 0.2, 0.2, 0.5),
+                    );
+                }
+            }
+        }
+    }
+}
+
+//Every behavior gets its own color, related behaviors get related shades
+fn get_bot_mode_color(bot_mode: &BotMode) -> Srgba {
+    match bot_mode {
+        BotMode::Sleep => Srgba::new(0.5, 0.5, 0.5, 0.8),
+        BotMode::RunTo => Srgba::new(0.6, 0.2, 1.0, 0.8),
+        BotMode::RunRandom => Srgba::new(1.0, 1.0, 1.0, 0.8),
+        BotMode::RunBlind => Srgba::new(1.0, 0.6, 1.0, 0.8),
+        BotMode::TakeTheHill => Srgba::new(1.0, 0.9, 0.2, 0.8),
+        BotMode::GuardTheHill => Srgba::new(0.6, 0.6, 0.0, 0.8),
+        BotMode::PickUpWeaponBox => Srgba::new(0.2, 1.0, 1.0, 0.8),
+        BotMode::SearchLastSeen => Srgba::new(1.0, 0.6, 0.6, 0.8),
+        BotMode::Racing => Srgba::new(0.2, 0.4, 1.0, 0.8),
+        BotMode::StopAim => Srgba::new(1.0, 0.2, 0.2, 0.8),
+        BotMode::StrafeAim => Srgba::new(0.6, 0.0, 0.3, 0.8),
+        BotMode::Chasing => Srgba::new(1.0, 0.5, 0.1, 0.8),
+        BotMode::Swording => Srgba::new(1.0, 0.3, 0.7, 0.8),
+        BotMode::Mining => Srgba::new(0.6, 0.4, 0.2, 0.8),
+        BotMode::Repairing => Srgba::new(0.2, 1.0, 0.2, 0.8),
+    }
+}
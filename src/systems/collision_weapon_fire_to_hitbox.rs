@@ -5,9 +5,10 @@ use amethyst::{
     core::{Time, Transform},
     derive::SystemDesc,
     ecs::{
-        Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData, World,
-        WriteStorage,
+        Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData,
+        World, WriteExpect, WriteStorage,
     },
+    renderer::SpriteRender,
 };
 
 use rand::Rng;
@@ -20,8 +21,9 @@ use ncollide2d::query::{self, Proximity, Ray, RayCast};
 use ncollide2d::shape::{Ball, Cuboid};
 
 use crate::components::{
-    get_next_gg_weapon_name, kill_restart_vehicle, update_weapon_properties, vehicle_damage_model,
-    ArenaElement, ArenaNames, ArenaProperties, ArenaStoreResource, DurationDamage, HitboxShape,
+    apply_vehicle_impulse, get_next_gg_weapon_name, kill_restart_vehicle, reflect_weapon_fire,
+    update_weapon_properties, vehicle_damage_model, weapon_fire_is_projectile, ArenaElement,
+    ArenaNames, ArenaProperties, ArenaStoreResource, Deployable, DurationDamage, HitboxShape,
     ObstacleType, Player, PlayerWeaponIcon, Vehicle, VehicleState, WeaponArray, WeaponFire,
     WeaponNames, WeaponStoreResource,
 };
@@ -30,7 +32,9 @@ use crate::entities::{
     chain_fire_weapon, explosion_shockwave, hit_spray, spawn_weapon_box_from_spawner,
 };
 
-use crate::resources::{GameModeSetup, GameModes, GameWeaponSetup, WeaponFireResource};
+use crate::resources::{
+    ArenaNavMesh, GameModeSetup, GameModes, GameWeaponSetup, WeaponFireResource,
+};
 
 use crate::systems::{calc_bounce_angle, clean_angle};
 
@@ -44,6 +48,11 @@ pub const SHOT_SPEED_TRIGGER: f32 = 500.0;
 
 const PRIMARY_WEAPON_INDEX: usize = 0;
 
+//explosives do extra damage to destructible walls
+pub const WALL_EXPLOSIVE_DAMAGE_MULT: f32 = 3.0;
+pub const RUBBLE_Z: f32 = -0.01;
+pub const RUBBLE_SPRITE_NUMBER: usize = 78;
+
 #[derive(SystemDesc, Default)]
 pub struct CollisionWeaponFireHitboxSystem {
     pub hit_sound_cooldown_timer: f32,
@@ -71,6 +80,9 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
         ReadExpect<'s, GameModeSetup>,
         ReadExpect<'s, GameWeaponSetup>,
         ReadExpect<'s, WeaponStoreResource>,
+        WriteStorage<'s, SpriteRender>,
+        WriteExpect<'s, ArenaNavMesh>,
+        WriteStorage<'s, Deployable>,
     );
 
     fn setup(&mut self, world: &mut World) {
@@ -129,6 +141,9 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
             game_mode_setup,
             game_weapon_setup,
             weapon_store_resource,
+            mut sprite_renders,
+            mut arena_nav_mesh,
+            mut deployables,
         ): Self::SystemData,
     ) {
         let dt = time.delta_seconds();
@@ -137,6 +152,8 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
             self.global_weapon_spawner_cooldown_timer -= dt;
         }
 
+        let mut destroyed_walls: Vec<Entity> = Vec::new();
+
         //weapon to non-moving hitbox collisions
         for (entity, mut arena_element, transform) in
             (&*entities, &mut arena_elements, &transforms).join()
@@ -145,6 +162,13 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                 let hitbox_x = transform.translation().x;
                 let hitbox_y = transform.translation().y;
 
+                //walls worn down elsewhere, such as by beams, break here too
+                if let Some(wall_health) = arena_element.health {
+                    if wall_health <= 0.0 {
+                        destroyed_walls.push(entity);
+                    }
+                }
+
                 for (weapon_fire_entity, weapon_fire, weapon_fire_transform) in
                     (&*entities, &mut weapon_fires, &transforms).join()
                 {
@@ -168,7 +192,10 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                         arena_element.hitbox.width / 2.0,
                         arena_element.hitbox.height / 2.0,
                     )); //unused
-                    let hitbox_collider_pos = Isometry2::new(Vector2::new(hitbox_x, hitbox_y), 0.0);
+                    let hitbox_collider_pos = Isometry2::new(
+                        Vector2::new(hitbox_x, hitbox_y),
+                        arena_element.hitbox.angle,
+                    );
 
                     let weapon_fire_hit;
                     if weapon_fire.stats.shot_speed == 0.0 {
@@ -246,8 +273,22 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                         }
                     }
 
-                    if weapon_fire_hit {
+                    if weapon_fire_hit && weapon_fire.active {
                         if !weapon_fire.stats.attached {
+                            if let Some(wall_health) = arena_element.health {
+                                let mut wall_damage = weapon_fire.stats.damage;
+                                if weapon_fire.stats.damage_radius > 0.0 {
+                                    wall_damage *= WALL_EXPLOSIVE_DAMAGE_MULT;
+                                }
+
+                                let new_wall_health = wall_health - wall_damage;
+                                arena_element.health = Some(new_wall_health);
+
+                                if new_wall_health <= 0.0 && !destroyed_walls.contains(&entity) {
+                                    destroyed_walls.push(entity);
+                                }
+                            }
+
                             if weapon_fire.stats.bounces > 0 {
                                 weapon_fire.stats.bounces -= 1;
                                 weapon_fire.owner_player_id = None;
@@ -278,6 +319,7 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                                     hitbox_x,
                                     hitbox_y,
                                     arena_element.hitbox.shape.clone(),
+                                    arena_element.hitbox.angle,
                                     weapon_fire.dx.clone(),
                                     weapon_fire.dy.clone(),
                                 );
@@ -319,6 +361,39 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
             }
         }
 
+        //destroyed walls are left behind as rubble that no longer blocks vehicles or weapon fire
+        if !destroyed_walls.is_empty() {
+            for destroyed_wall in destroyed_walls.iter() {
+                if let Some(arena_element) = arena_elements.get_mut(*destroyed_wall) {
+                    arena_element.obstacle_type = ObstacleType::Open;
+                    arena_element.health = None;
+                    arena_element.sprite = RUBBLE_SPRITE_NUMBER;
+                }
+
+                if let Some(transform) = transforms.get_mut(*destroyed_wall) {
+                    let wall_x = transform.translation().x;
+                    let wall_y = transform.translation().y;
+
+                    transform.set_translation_z(RUBBLE_Z);
+
+                    explosion_shockwave(
+                        &entities,
+                        &weapon_fire_resource,
+                        Vector3::new(wall_x, wall_y, 0.5),
+                        40.0,
+                        &lazy_update,
+                    );
+                }
+
+                if let Some(sprite_render) = sprite_renders.get_mut(*destroyed_wall) {
+                    sprite_render.sprite_number = RUBBLE_SPRITE_NUMBER;
+                }
+            }
+
+            //the nav mesh is rebuilt once this frame by the ArenaNavMeshSystem
+            arena_nav_mesh.dirty = true;
+        }
+
         //update weapon fire angle after potential bouncing
         for (weapon_fire, weapon_fire_transform) in (&weapon_fires, &mut transforms).join() {
             if !weapon_fire.stats.heat_seeking {
@@ -386,6 +461,8 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
         let mut explosion_map: Vec<(usize, WeaponFire, f32, f32)> = Vec::new();
         let mut chain_map: Vec<(WeaponFire, f32, f32)> = Vec::new();
 
+        let mut rng = rand::thread_rng();
+
         for (player, vehicle, vehicle_transform) in
             (&mut players, &mut vehicles, &transforms).join()
         {
@@ -512,6 +589,22 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                         }
                     }
 
+                    //shields can deflect a shot back, handing it over to this vehicle's player
+                    if weapon_fire_hit
+                        && vehicle.shield.value > 0.0
+                        && vehicle.shield.reflect_pct > 0.0
+                        && weapon_fire_is_projectile(weapon_fire)
+                        && rng.gen_range(0.0, 100.0) < vehicle.shield.reflect_pct
+                    {
+                        reflect_weapon_fire(
+                            weapon_fire,
+                            Some(player.id),
+                            fire_x - vehicle_x,
+                            fire_y - vehicle_y,
+                        );
+                        continue;
+                    }
+
                     if weapon_fire_hit {
                         player.last_hit_by_id = weapon_fire.owner_player_id.clone();
                         player.last_hit_timer = 0.0;
@@ -540,6 +633,28 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                                 .insert(player.id.clone(), weapon_fire.owner_player_id.clone());
                         }
 
+                        //Knock the vehicle back along the shot, or away from shots that don't move
+                        if weapon_fire.stats.knockback > 0.0 {
+                            let push_x;
+                            let push_y;
+                            if weapon_fire.dx.abs() + weapon_fire.dy.abs() > 0.001 {
+                                push_x = weapon_fire.dx;
+                                push_y = weapon_fire.dy;
+                            } else {
+                                push_x = vehicle_x - fire_x;
+                                push_y = vehicle_y - fire_y;
+                            }
+
+                            apply_vehicle_impulse(
+                                vehicle,
+                                weapon_fire.stats.knockback,
+                                push_x,
+                                push_y,
+                                fire_x - vehicle_x,
+                                fire_y - vehicle_y,
+                            );
+                        }
+
                         //Pass slow-down effect from weapon to vehicle
                         if weapon_fire.stats.slow_down_effect.timer > 0.0 {
                             vehicle.restricted_velocity_timer =
@@ -745,6 +860,25 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
                                 .insert(player.id.clone(), weapon_fire.owner_player_id.clone());
                         }
 
+                        //Blast pushes vehicles away from its center, weaker towards the edge
+                        if weapon_fire.stats.knockback > 0.0 {
+                            let blast_dx = vehicle_x - fire_x;
+                            let blast_dy = vehicle_y - fire_y;
+                            let blast_dist = (blast_dx.powi(2) + blast_dy.powi(2)).sqrt();
+                            let blast_radius =
+                                vehicle.width / 2.0 + weapon_fire.stats.damage_radius;
+                            let falloff = (1.0 - blast_dist / blast_radius).max(0.0);
+
+                            apply_vehicle_impulse(
+                                vehicle,
+                                weapon_fire.stats.knockback * falloff,
+                                blast_dx,
+                                blast_dy,
+                                0.0,
+                                0.0,
+                            );
+                        }
+
                         //Pass slow-down effect from weapon to vehicle
                         if weapon_fire.stats.slow_down_effect.timer > 0.0 {
                             vehicle.restricted_velocity_timer =
@@ -784,6 +918,28 @@ impl<'s> System<'s> for CollisionWeaponFireHitboxSystem {
             }
         }
 
+        //explosions also wear down enemy turrets and drones caught in the blast
+        if !explosion_map.is_empty() {
+            let player_teams: HashMap<usize, i32> = (&players)
+                .join()
+                .map(|player| (player.id, player.team))
+                .collect();
+
+            for (deployable, deployable_transform) in (&mut deployables, &transforms).join() {
+                let deployable_x = deployable_transform.translation().x;
+                let deployable_y = deployable_transform.translation().y;
+
+                for (_player_id_already_hit, weapon_fire, fire_x, fire_y) in &explosion_map {
+                    if !deployable.is_friendly_fire(weapon_fire.owner_player_id, &player_teams)
+                        && (fire_x - deployable_x).powi(2) + (fire_y - deployable_y).powi(2)
+                            < (deployable.stats.radius + weapon_fire.stats.damage_radius).powi(2)
+                    {
+                        deployable.health -= weapon_fire.stats.damage;
+                    }
+                }
+            }
+        }
+
         //Kill tracking and gun-game weapon hot swap logic
         let mut weapon_icons_old_map = HashMap::new();
 
@@ -0,0 +1,179 @@
+use amethyst::{
+    core::{math::Vector3, Transform},
+    derive::SystemDesc,
+    ecs::{
+        Entities, Entity, Join, LazyUpdate, ReadExpect, ReadStorage, System, SystemData,
+        WriteStorage,
+    },
+};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::components::{reflect_weapon_fire, weapon_fire_is_projectile, Player, WeaponFire};
+use crate::entities::{explosion_shockwave, hit_spray};
+use crate::resources::WeaponFireResource;
+
+//A shot that can take part in a weapon fire to weapon fire collision this frame
+struct ShotSnapshot {
+    entity: Entity,
+    owner_player_id: Option<usize>,
+    team: Option<i32>, //owner's team, None for shots without an owner
+    x: f32,
+    y: f32,
+    radius: f32,
+    damage_radius: f32,
+    projectile: bool,
+    interceptable: bool,
+    reflects: bool,
+}
+
+//Projectiles hitting each other: swords deflect shots, and interceptable shots can be shot down
+#[derive(SystemDesc, Default)]
+pub struct CollisionWeaponFireWeaponFireSystem;
+
+impl<'s> System<'s> for CollisionWeaponFireWeaponFireSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, WeaponFire>,
+        ReadStorage<'s, Transform>,
+        ReadExpect<'s, WeaponFireResource>,
+        ReadExpect<'s, LazyUpdate>,
+        ReadStorage<'s, Player>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut weapon_fires,
+            transforms,
+            weapon_fire_resource,
+            lazy_update,
+            players,
+        ): Self::SystemData,
+    ) {
+        let player_teams: HashMap<usize, i32> = (&players)
+            .join()
+            .map(|player| (player.id, player.team))
+            .collect();
+
+        let mut shots: Vec<ShotSnapshot> = Vec::new();
+
+        for (entity, weapon_fire, transform) in (&*entities, &weapon_fires, &transforms).join() {
+            let projectile = weapon_fire_is_projectile(weapon_fire);
+
+            if weapon_fire.active
+                && (projectile || weapon_fire.stats.interceptable || weapon_fire.stats.reflects)
+            {
+                shots.push(ShotSnapshot {
+                    entity,
+                    owner_player_id: weapon_fire.owner_player_id,
+                    team: match weapon_fire.owner_player_id {
+                        Some(owner_player_id) => player_teams.get(&owner_player_id).copied(),
+                        None => None,
+                    },
+                    x: transform.translation().x,
+                    y: transform.translation().y,
+                    radius: weapon_fire.width.max(weapon_fire.height) / 2.0,
+                    damage_radius: weapon_fire.stats.damage_radius,
+                    projectile,
+                    interceptable: weapon_fire.stats.interceptable,
+                    reflects: weapon_fire.stats.reflects,
+                });
+            }
+        }
+
+        //(shot, new owner, surface normal x, surface normal y)
+        let mut reflected_shots: Vec<(Entity, Option<usize>, f32, f32)> = Vec::new();
+        let mut intercepted_shots: Vec<&ShotSnapshot> = Vec::new();
+        let mut spent_shots: Vec<&ShotSnapshot> = Vec::new();
+
+        let mut handled_shots: HashSet<Entity> = HashSet::new();
+
+        for (index, shot) in shots.iter().enumerate() {
+            for other_shot in shots.iter().skip(index + 1) {
+                //a player's own shots and their teammates' shots never interact
+                if shot.owner_player_id == other_shot.owner_player_id
+                    || (shot.team.is_some() && shot.team == other_shot.team)
+                    || handled_shots.contains(&shot.entity)
+                    || handled_shots.contains(&other_shot.entity)
+                {
+                    continue;
+                }
+
+                if (shot.x - other_shot.x).powi(2) + (shot.y - other_shot.y).powi(2)
+                    >= (shot.radius + other_shot.radius).powi(2)
+                {
+                    continue;
+                }
+
+                if shot.reflects && other_shot.projectile {
+                    //deflected back at whoever fired it
+                    reflected_shots.push((
+                        other_shot.entity,
+                        shot.owner_player_id,
+                        other_shot.x - shot.x,
+                        other_shot.y - shot.y,
+                    ));
+                    handled_shots.insert(other_shot.entity);
+                } else if other_shot.reflects && shot.projectile {
+                    reflected_shots.push((
+                        shot.entity,
+                        other_shot.owner_player_id,
+                        shot.x - other_shot.x,
+                        shot.y - other_shot.y,
+                    ));
+                    handled_shots.insert(shot.entity);
+                } else if shot.interceptable && other_shot.projectile {
+                    //shot down, and the interceptor is spent on it
+                    intercepted_shots.push(shot);
+                    spent_shots.push(other_shot);
+                    handled_shots.insert(shot.entity);
+                    handled_shots.insert(other_shot.entity);
+                } else if other_shot.interceptable && shot.projectile {
+                    intercepted_shots.push(other_shot);
+                    spent_shots.push(shot);
+                    handled_shots.insert(shot.entity);
+                    handled_shots.insert(other_shot.entity);
+                }
+            }
+        }
+
+        for (entity, owner_player_id, normal_x, normal_y) in reflected_shots.iter() {
+            if let Some(weapon_fire) = weapon_fires.get_mut(*entity) {
+                reflect_weapon_fire(weapon_fire, *owner_player_id, *normal_x, *normal_y);
+            }
+        }
+
+        //intercepted explosives go off harmlessly in the air
+        for shot in intercepted_shots.iter() {
+            let position = Vector3::new(shot.x, shot.y, 0.5);
+
+            if shot.damage_radius > 0.0 {
+                explosion_shockwave(
+                    &entities,
+                    &weapon_fire_resource,
+                    position,
+                    shot.damage_radius,
+                    &lazy_update,
+                );
+            } else {
+                hit_spray(
+                    &entities,
+                    &weapon_fire_resource,
+                    false,
+                    position,
+                    &lazy_update,
+                );
+            }
+        }
+
+        for shot in intercepted_shots.iter().chain(spent_shots.iter()) {
+            if let Some(weapon_fire) = weapon_fires.get_mut(shot.entity) {
+                weapon_fire.active = false;
+            }
+
+            let _ = entities.delete(shot.entity);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use amethyst::{
+    core::{math::Vector3, Time, Transform},
+    derive::SystemDesc,
+    ecs::{
+        Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData,
+        WriteStorage,
+    },
+};
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use crate::components::{
+    vehicle_is_cloaked, ArenaElement, Deployable, DeployableTypes, ObstacleType, Player, Vehicle,
+    VehicleState, WeaponArray, WeaponFire,
+};
+use crate::entities::{explosion_shockwave, fire_weapon};
+use crate::resources::WeaponFireResource;
+use crate::systems::has_line_of_sight;
+
+//A shot that can hit a deployable this frame
+struct DeployableShot {
+    entity: Entity,
+    owner_player_id: Option<usize>,
+    x: f32,
+    y: f32,
+    size: f32,
+    damage: f32,
+    damage_radius: f32,
+    attached: bool,
+}
+
+//Moves, arms and wears down the turrets and drones players have deployed
+#[derive(SystemDesc, Default)]
+pub struct DeployableSystem;
+
+impl<'s> System<'s> for DeployableSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Deployable>,
+        WriteStorage<'s, WeaponArray>,
+        WriteStorage<'s, Transform>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Vehicle>,
+        WriteStorage<'s, WeaponFire>,
+        ReadStorage<'s, ArenaElement>,
+        Read<'s, Time>,
+        ReadExpect<'s, WeaponFireResource>,
+        ReadExpect<'s, LazyUpdate>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut deployables,
+            mut weapon_arrays,
+            mut transforms,
+            players,
+            vehicles,
+            mut weapon_fires,
+            arena_elements,
+            time,
+            weapon_fire_resource,
+            lazy_update,
+        ): Self::SystemData,
+    ) {
+        let dt = time.delta_seconds();
+
+        let walls: Vec<&ArenaElement> = arena_elements
+            .join()
+            .filter(|arena_element| arena_element.obstacle_type == ObstacleType::Wall)
+            .collect();
+
+        //(player id, team, x, y, active, cloaked)
+        let mut vehicle_positions: Vec<(usize, i32, f32, f32, bool, bool)> = Vec::new();
+
+        for (player, vehicle, transform) in (&players, &vehicles, &transforms).join() {
+            vehicle_positions.push((
+                player.id,
+                player.team,
+                transform.translation().x,
+                transform.translation().y,
+                vehicle.state == VehicleState::Active,
+                vehicle_is_cloaked(vehicle),
+            ));
+        }
+
+        let player_teams: HashMap<usize, i32> = (&players)
+            .join()
+            .map(|player| (player.id, player.team))
+            .collect();
+
+        let mut shots: Vec<DeployableShot> = Vec::new();
+
+        //runs after the weapon fire collision systems, so shots already spent on vehicles,
+        //walls or other shots are inactive by now and can't hit a deployable as well
+        for (entity, weapon_fire, transform) in (&*entities, &weapon_fires, &transforms).join() {
+            //mines and traps sit still, so they only go off on vehicles
+            if weapon_fire.active
+                && (weapon_fire.stats.shot_speed > 0.0 || weapon_fire.stats.attached)
+            {
+                shots.push(DeployableShot {
+                    entity,
+                    owner_player_id: weapon_fire.owner_player_id,
+                    x: transform.translation().x,
+                    y: transform.translation().y,
+                    size: weapon_fire.width.max(weapon_fire.height) / 2.0,
+                    damage: weapon_fire.stats.damage,
+                    damage_radius: weapon_fire.stats.damage_radius,
+                    attached: weapon_fire.stats.attached,
+                });
+            }
+        }
+
+        let mut spent_shots: Vec<Entity> = Vec::new();
+        //(shot, deployable it burst on) for explosive shells
+        let mut detonations: Vec<(&DeployableShot, Entity)> = Vec::new();
+
+        //enemy fire wears them down
+        for (entity, deployable, transform) in (&*entities, &mut deployables, &transforms).join() {
+            let deployable_x = transform.translation().x;
+            let deployable_y = transform.translation().y;
+
+            for shot in shots.iter() {
+                if !deployable.is_friendly_fire(shot.owner_player_id, &player_teams)
+                    && !spent_shots.contains(&shot.entity)
+                    && (shot.x - deployable_x).powi(2) + (shot.y - deployable_y).powi(2)
+                        < (deployable.stats.radius + shot.size).powi(2)
+                {
+                    deployable.health -= shot.damage;
+
+                    if !shot.attached {
+                        spent_shots.push(shot.entity);
+
+                        if shot.damage_radius > 0.0 {
+                            detonations.push((shot, entity));
+                        }
+                    }
+                }
+            }
+        }
+
+        //explosive shells burst on the deployable they hit, catching other deployables nearby
+        //vehicle splash damage stays with shells bursting on vehicles, in the collision system
+        for (shot, _) in detonations.iter() {
+            explosion_shockwave(
+                &entities,
+                &weapon_fire_resource,
+                Vector3::new(shot.x, shot.y, 0.5),
+                shot.damage_radius,
+                &lazy_update,
+            );
+        }
+
+        for (entity, deployable, transform) in (&*entities, &mut deployables, &transforms).join() {
+            let deployable_x = transform.translation().x;
+            let deployable_y = transform.translation().y;
+
+            for (shot, hit_entity) in detonations.iter() {
+                if entity != *hit_entity
+                    && !deployable.is_friendly_fire(shot.owner_player_id, &player_teams)
+                    && (shot.x - deployable_x).powi(2) + (shot.y - deployable_y).powi(2)
+                        < (deployable.stats.radius + shot.damage_radius).powi(2)
+                {
+                    deployable.health -= shot.damage;
+                }
+            }
+        }
+
+        for (entity, deployable, weapon_array, transform) in (
+            &*entities,
+            &mut deployables,
+            &mut weapon_arrays,
+            &mut transforms,
+        )
+            .join()
+        {
+            deployable.lifetime_timer -= dt;
+
+            //drones circle their owner, and go down with them
+            if deployable.stats.deployable_type == DeployableTypes::Drone {
+                let owner = vehicle_positions
+                    .iter()
+                    .find(|(player_id, _, _, _, _, _)| *player_id == deployable.owner_player_id);
+
+                if let Some((_, _, owner_x, owner_y, true, _)) = owner {
+                    deployable.orbit_angle += deployable.stats.orbit_speed * dt;
+
+                    transform.set_translation_x(
+                        owner_x + deployable.stats.orbit_radius * deployable.orbit_angle.cos(),
+                    );
+                    transform.set_translation_y(
+                        owner_y + deployable.stats.orbit_radius * deployable.orbit_angle.sin(),
+                    );
+                } else {
+                    deployable.health = 0.0;
+                }
+            }
+
+            let deployable_x = transform.translation().x;
+            let deployable_y = transform.translation().y;
+
+            if deployable.health <= 0.0 || deployable.lifetime_timer <= 0.0 {
+                if deployable.health <= 0.0 {
+                    explosion_shockwave(
+                        &entities,
+                        &weapon_fire_resource,
+                        Vector3::new(deployable_x, deployable_y, 0.5),
+                        deployable.stats.radius * 2.0,
+                        &lazy_update,
+                    );
+                }
+
+                let _ = entities.delete(entity);
+                continue;
+            }
+
+            //closest enemy vehicle in range and in sight, never the owner or their teammates
+            let mut target: Option<(f32, f32)> = None;
+            let mut target_dist = deployable.stats.range;
+
+            for (player_id, team, vehicle_x, vehicle_y, active, cloaked) in vehicle_positions.iter()
+            {
+                if *active
+                    && !cloaked
+                    && *player_id != deployable.owner_player_id
+                    && *team != deployable.owner_team
+                {
+                    let dist = ((vehicle_x - deployable_x).powi(2)
+                        + (vehicle_y - deployable_y).powi(2))
+                    .sqrt();
+
+                    if dist < target_dist
+                        && has_line_of_sight(
+                            &walls,
+                            deployable_x,
+                            deployable_y,
+                            *vehicle_x,
+                            *vehicle_y,
+                        )
+                    {
+                        target = Some((*vehicle_x, *vehicle_y));
+                        target_dist = dist;
+                    }
+                }
+            }
+
+            let mut fire_angle = None;
+
+            if let Some((target_x, target_y)) = target {
+                let angle = (deployable_y - target_y).atan2(deployable_x - target_x) + (PI / 2.0);
+
+                transform.set_rotation_2d(angle);
+                fire_angle = Some(angle);
+            }
+
+            for weapon_install in weapon_array.installed.iter_mut() {
+                let weapon = &mut weapon_install.weapon;
+
+                weapon.cooldown_timer = (weapon.cooldown_timer - dt).max(-1.0);
+
+                if let Some(fire_angle) = fire_angle {
+                    if weapon.cooldown_timer <= 0.0 {
+                        fire_weapon(
+                            &entities,
+                            &weapon_fire_resource,
+                            weapon.clone(),
+                            0,
+                            Vector3::new(deployable_x, deployable_y, 0.0),
+                            fire_angle,
+                            0.0,
+                            Some(deployable.owner_player_id),
+                            &lazy_update,
+                        );
+
+                        weapon.cooldown_timer = weapon.stats.cooldown_reset;
+                    }
+                }
+            }
+        }
+
+        //mark spent shots so no later system in the frame can hit something else with them
+        for shot_entity in spent_shots.iter() {
+            if let Some(weapon_fire) = weapon_fires.get_mut(*shot_entity) {
+                weapon_fire.active = false;
+            }
+
+            let _ = entities.delete(*shot_entity);
+        }
+    }
+}
@@ -1,25 +1,44 @@
+pub use self::arena_nav_mesh::ArenaNavMeshSystem;
+pub use self::arena_storm::ArenaStormSystem;
+pub use self::bot_ai::{BotAiSystem, BOT_PREFERRED_RANGE_MAX_PCT, BOT_PREFERRED_RANGE_MIN_PCT};
+pub use self::bot_debug_overlay::BotDebugOverlaySystem;
 pub use self::camera_tracking::CameraTrackingSystem;
 pub use self::collision_vehicle_vehicle::CollisionVehToVehSystem;
 pub use self::collision_weapon_fire_to_hitbox::CollisionWeaponFireHitboxSystem;
+pub use self::collision_weapon_fire_to_weapon_fire::CollisionWeaponFireWeaponFireSystem;
+pub use self::deployables::DeployableSystem;
 pub use self::game_objective_status::VehicleStatusSystem;
 pub use self::move_particles::MoveParticlesSystem;
 pub use self::move_weapon_fire::MoveWeaponFireSystem;
 pub use self::pathing_lines::PathingLinesSystem;
 pub use self::ui_events::UiEventHandlerSystem;
-pub use self::vehicle_move::{calc_bounce_angle, clean_angle, VehicleMoveSystem};
+pub use self::vehicle_abilities::VehicleAbilitySystem;
+pub use self::vehicle_move::{
+    calc_bounce_angle, clean_angle, VehicleMoveSystem, TANK_TRACK_LATERAL_FRICTION_DECEL_RATE,
+    TIRE_LATERAL_FRICTION_DECEL_RATE,
+};
 pub use self::vehicle_shield_armor_health::VehicleShieldArmorHealthSystem;
-pub use self::vehicle_tracking::VehicleTrackingSystem;
-pub use self::vehicle_weapons::VehicleWeaponsSystem;
+pub use self::vehicle_tracking::{has_line_of_sight, VehicleTrackingSystem};
+pub use self::vehicle_weapons::{calc_lead_angle, VehicleWeaponsSystem};
+pub use self::weapon_beam::WeaponBeamSystem;
 
+mod arena_nav_mesh;
+mod arena_storm;
+mod bot_ai;
+mod bot_debug_overlay;
 mod camera_tracking;
 mod collision_vehicle_vehicle;
 mod collision_weapon_fire_to_hitbox;
+mod collision_weapon_fire_to_weapon_fire;
+mod deployables;
 mod game_objective_status;
 mod move_particles;
 mod move_weapon_fire;
 mod pathing_lines;
 mod ui_events;
+mod vehicle_abilities;
 mod vehicle_move;
 mod vehicle_shield_armor_health;
 mod vehicle_tracking;
 mod vehicle_weapons;
+mod weapon_beam;
@@ -1,9 +1,13 @@
 use amethyst::core::math::Vector3;
 use amethyst::core::{Time, Transform};
 use amethyst::derive::SystemDesc;
-use amethyst::ecs::{Entities, Join, Read, ReadStorage, System, SystemData, WriteStorage};
+use amethyst::ecs::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage,
+};
 
-use crate::components::{Particles, Shockwave};
+use crate::components::{ParticleTrail, Particles, Shockwave};
+use crate::entities::trail_particle;
+use crate::resources::WeaponFireResource;
 
 #[derive(SystemDesc)]
 pub struct MoveParticlesSystem;
@@ -14,12 +18,24 @@ impl<'s> System<'s> for MoveParticlesSystem {
         WriteStorage<'s, Transform>,
         WriteStorage<'s, Particles>,
         ReadStorage<'s, Shockwave>,
+        WriteStorage<'s, ParticleTrail>,
         Read<'s, Time>,
+        ReadExpect<'s, WeaponFireResource>,
+        ReadExpect<'s, LazyUpdate>,
     );
 
     fn run(
         &mut self,
-        (entities, mut transforms, mut particles, shockwaves, time): Self::SystemData,
+        (
+            entities,
+            mut transforms,
+            mut particles,
+            shockwaves,
+            mut particle_trails,
+            time,
+            weapon_fire_resource,
+            lazy_update,
+        ): Self::SystemData,
     ) {
         let dt = time.delta_seconds();
 
@@ -46,5 +62,28 @@ impl<'s> System<'s> for MoveParticlesSystem {
 
             transform.set_scale(Vector3::new(scale, scale, 0.0));
         }
+
+        //shots with a trail drop particles as they fly
+        for (particle_trail, transform) in (&mut particle_trails, &transforms).join() {
+            particle_trail.timer -= dt;
+
+            if particle_trail.timer <= 0.0 {
+                particle_trail.timer += particle_trail.trail.rate.max(0.01);
+
+                let position = Vector3::new(
+                    transform.translation().x,
+                    transform.translation().y,
+                    transform.translation().z - 0.01,
+                );
+
+                trail_particle(
+                    &entities,
+                    &weapon_fire_resource,
+                    &particle_trail.trail,
+                    position,
+                    &lazy_update,
+                );
+            }
+        }
     }
 }
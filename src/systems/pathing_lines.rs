@@ -1,16 +1,17 @@
 use amethyst::{
-    core::transform::Transform,
+    core::{transform::Transform, Time},
     derive::SystemDesc,
-    ecs::{Join, ReadExpect, ReadStorage, System, SystemData, World, Write, WriteStorage},
-    renderer::{debug_drawing::DebugLines, palette::Srgba},
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
 };
 
 use navmesh::{NavPathMode, NavQuery};
 
 use crate::components::{ArenaNames, ArenaProperties, ArenaStoreResource, Player, Vehicle};
-use crate::resources::{ArenaNavMesh, ArenaNavMeshFinal, GameModeSetup};
+use crate::resources::{ArenaNavMeshFinal, GameModeSetup};
 
-use crate::rally::DEBUG_LINES;
+const PATH_REPLAN_TIME: f32 = 2.0; //replan now and then anyway, walls may have been destroyed
+const PATH_REPLAN_TARGET_MOVED_DIST: f32 = 20.0;
+const PATH_REPLAN_OFF_PATH_DIST: f32 = 40.0;
 
 #[derive(SystemDesc, Default)]
 pub struct PathingLinesSystem {
@@ -19,12 +20,11 @@ pub struct PathingLinesSystem {
 
 impl<'s> System<'s> for PathingLinesSystem {
     type SystemData = (
-        Write<'s, DebugLines>,
-        ReadExpect<'s, ArenaNavMesh>,
         ReadExpect<'s, ArenaNavMeshFinal>,
         WriteStorage<'s, Player>,
         ReadStorage<'s, Vehicle>,
         ReadStorage<'s, Transform>,
+        Read<'s, Time>,
     );
 
     fn setup(&mut self, world: &mut World) {
@@ -53,14 +53,7 @@ impl<'s> System<'s> for PathingLinesSystem {
 
     fn run(
         &mut self,
-        (
-            mut debug_lines_resource,
-            arena_nav_mesh,
-            arena_nav_mesh_final,
-            mut players,
-            vehicles,
-            transforms,
-        ): Self::SystemData,
+        (arena_nav_mesh_final, mut players, vehicles, transforms, time): Self::SystemData,
     ) {
         let nav_query_type = NavQuery::Accuracy;
         let nav_path_type = NavPathMode::Accuracy;
@@ -86,37 +79,14 @@ impl<'s> System<'s> for PathingLinesSystem {
         }
         */
 
-        //draw nav mesh zones as green debug lines
-        if DEBUG_LINES {
-            for (v1_index, v2_index, v3_index) in arena_nav_mesh.triangles.iter() {
-                let v1 = arena_nav_mesh.vertices[*v1_index];
-                let v2 = arena_nav_mesh.vertices[*v2_index];
-                let v3 = arena_nav_mesh.vertices[*v3_index];
-
-                debug_lines_resource.draw_line(
-                    [v1.0, v1.1, v1.2].into(),
-                    [v2.0, v2.1, v2.2].into(),
-                    Srgba::new(0.2, 1.0, 0.2, 0.1),
-                );
-
-                debug_lines_resource.draw_line(
-                    [v2.0, v2.1, v2.2].into(),
-                    [v3.0, v3.1, v3.2].into(),
-                    Srgba::new(0.2, 1.0, 0.2, 0.1),
-                );
-
-                debug_lines_resource.draw_line(
-                    [v3.0, v3.1, v3.2].into(),
-                    [v1.0, v1.1, v1.2].into(),
-                    Srgba::new(0.2, 1.0, 0.2, 0.1),
-                );
-            }
-        }
+        let dt = time.delta_seconds();
 
         for (player, _vehicle, transform) in (&mut players, &vehicles, &transforms).join() {
             let vehicle_x = transform.translation().x;
             let vehicle_y = transform.translation().y;
 
+            player.path_replan_timer -= dt;
+
             if let Some(mesh) = &arena_nav_mesh_final.mesh {
                 if !player.is_bot {
                     //player.path_target = Some((0.0, 0.0, 0.0));
@@ -128,47 +98,75 @@ impl<'s> System<'s> for PathingLinesSystem {
                 }
 
                 if let Some(target) = player.path_target {
-                    let path = mesh.find_path(
-                        (vehicle_x, vehicle_y, 0.0).into(),
-                        target.into(),
-                        nav_query_type,
-                        nav_path_type,
-                    );
-                    if let Some(path) = path {
-                        let mut prev_x = None;
-                        let mut prev_y = None;
-                        let mut prev_z = None;
-
-                        let mut path_plan = Vec::new();
-
-                        for nav_vector in path.iter() {
-                            let x = nav_vector.x;
-                            let y = nav_vector.y;
-                            let z = nav_vector.z;
-
-                            path_plan.push((x, y, z));
-
-                            if !prev_x.is_none() {
-                                if DEBUG_LINES {
-                                    debug_lines_resource.draw_line(
-                                        [prev_x.unwrap(), prev_y.unwrap(), prev_z.unwrap()].into(),
-                                        [x, y, z].into(),
-                                        Srgba::new(0.2, 0.8, 1.0, 0.3),
-                                    );
-                                }
-                            }
+                    //Only replan when the current plan no longer fits
+                    let target_moved = match player.path_plan_target {
+                        Some(plan_target) => {
+                            (target.0 - plan_target.0).hypot(target.1 - plan_target.1)
+                                > PATH_REPLAN_TARGET_MOVED_DIST
+                        }
+                        None => true,
+                    };
 
-                            prev_x = Some(x);
-                            prev_y = Some(y);
-                            prev_z = Some(z);
+                    let off_path = match &player.path_plan {
+                        Some(path_plan) => {
+                            dist_from_path(path_plan, player.path_plan_index, vehicle_x, vehicle_y)
+                                > PATH_REPLAN_OFF_PATH_DIST
                         }
+                        None => true,
+                    };
+
+                    if target_moved || off_path || player.path_replan_timer <= 0.0 {
+                        player.path_replan_timer = PATH_REPLAN_TIME;
+                        player.path_plan_target = Some(target);
+
+                        let path = mesh.find_path(
+                            (vehicle_x, vehicle_y, 0.0).into(),
+                            target.into(),
+                            nav_query_type,
+                            nav_path_type,
+                        );
+
+                        if let Some(path) = path {
+                            let mut path_plan = Vec::new();
+
+                            for nav_vector in path.iter() {
+                                path_plan.push((nav_vector.x, nav_vector.y, nav_vector.z));
+                            }
 
-                        player.path_plan = Some(path_plan.clone());
+                            player.path_plan = Some(path_plan);
+                            player.path_plan_index = 1;
+                        } else {
+                            player.path_plan = None;
+                        }
                     }
                 } else {
                     player.path_plan = None;
+                    player.path_plan_target = None;
                 }
             }
         }
     }
 }
+
+//Distance from the leg of the path plan currently being driven
+fn dist_from_path(path_plan: &Vec<(f32, f32, f32)>, path_plan_index: usize, x: f32, y: f32) -> f32 {
+    if path_plan_index == 0 || path_plan_index >= path_plan.len() {
+        return 0.0; //finished the plan, nothing to stray from
+    }
+
+    let (x1, y1, _) = path_plan[path_plan_index - 1];
+    let (x2, y2, _) = path_plan[path_plan_index];
+
+    let leg_length_sq = (x2 - x1).powi(2) + (y2 - y1).powi(2);
+
+    let t;
+    if leg_length_sq < 0.0001 {
+        t = 0.0;
+    } else {
+        t = (((x - x1) * (x2 - x1) + (y - y1) * (y2 - y1)) / leg_length_sq)
+            .max(0.0)
+            .min(1.0);
+    }
+
+    (x - (x1 + t * (x2 - x1))).hypot(y - (y1 + t * (y2 - y1)))
+}
@@ -0,0 +1,186 @@
+use amethyst::{
+    core::{Time, Transform},
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage},
+    input::{InputHandler, StringBindings},
+    renderer::{palette::Srgba, resources::Tint},
+    ui::UiText,
+};
+
+use std::f32::consts::PI;
+
+use crate::components::{
+    apply_vehicle_impulse, Player, Vehicle, VehicleAbilityTypes, VehicleState,
+    CLOAKED_VEHICLE_ALPHA,
+};
+use crate::resources::GameModeSetup;
+
+//Third ability slot: nitro boost, side-dash, shield overcharge and cloak
+#[derive(SystemDesc, Default)]
+pub struct VehicleAbilitySystem;
+
+impl<'s> System<'s> for VehicleAbilitySystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Vehicle>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, UiText>,
+        Read<'s, Time>,
+        Read<'s, InputHandler<StringBindings>>,
+        ReadExpect<'s, GameModeSetup>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            players,
+            mut vehicles,
+            transforms,
+            mut tints,
+            mut ui_texts,
+            time,
+            input,
+            game_mode_setup,
+        ): Self::SystemData,
+    ) {
+        let dt = time.delta_seconds();
+
+        for (entity, player, vehicle, transform) in
+            (&*entities, &players, &mut vehicles, &transforms).join()
+        {
+            let vehicle_ability;
+            let vehicle_strafe;
+            if player.is_bot {
+                vehicle_ability = player.bot_intent.ability;
+                vehicle_strafe = player.bot_intent.strafe;
+            } else {
+                vehicle_ability = match player.id {
+                    0 => input.action_is_down("p1_ability"),
+                    1 => input.action_is_down("p2_ability"),
+                    2 => input.action_is_down("p3_ability"),
+                    3 => input.action_is_down("p4_ability"),
+                    _ => None,
+                };
+
+                if game_mode_setup.p1_keyboard {
+                    //p1 using keyboard, p2 using controller 0, ...
+                    vehicle_strafe = match player.id {
+                        0 => input.axis_value("p1kb_strafe"),
+                        1 => input.axis_value("p1_strafe"),
+                        2 => input.axis_value("p2_strafe"),
+                        3 => input.axis_value("p3_strafe"),
+                        _ => None,
+                    };
+                } else {
+                    vehicle_strafe = match player.id {
+                        0 => input.axis_value("p1_strafe"),
+                        1 => input.axis_value("p2_strafe"),
+                        2 => input.axis_value("p3_strafe"),
+                        3 => input.axis_value("p4_strafe"),
+                        _ => None,
+                    };
+                }
+            }
+
+            let is_active = vehicle.state == VehicleState::Active;
+
+            //timers, and which ability just started or ran out
+            let mut activated = None;
+            let mut expired = None;
+
+            if let Some(ability) = &mut vehicle.ability {
+                if ability.update_timers(dt) {
+                    expired = Some(ability.stats.ability_type);
+                }
+
+                if let Some(ability_pressed) = vehicle_ability {
+                    if ability_pressed && is_active && ability.try_activate() {
+                        activated = Some(ability.stats.clone());
+                    }
+                }
+            } else {
+                continue;
+            }
+
+            let (_, _, vehicle_angle) = transform.rotation().euler_angles();
+
+            let veh_x_comp = -vehicle_angle.sin(); //left is -, right is +
+            let veh_y_comp = vehicle_angle.cos(); //up is +, down is -
+
+            let veh_x_strafe_comp = -(vehicle_angle + PI / 2.0).sin();
+            let veh_y_strafe_comp = (vehicle_angle + PI / 2.0).cos();
+
+            if let Some(ability_stats) = activated {
+                match ability_stats.ability_type {
+                    VehicleAbilityTypes::SideDash => {
+                        //dash towards the strafe input, or left if not strafing
+                        let side;
+                        if vehicle_strafe.unwrap_or(0.0) < 0.0 {
+                            side = -1.0;
+                        } else {
+                            side = 1.0;
+                        }
+
+                        apply_vehicle_impulse(
+                            vehicle,
+                            ability_stats.impulse,
+                            side * veh_x_strafe_comp,
+                            side * veh_y_strafe_comp,
+                            0.0,
+                            0.0,
+                        );
+                    }
+                    VehicleAbilityTypes::ShieldOvercharge => {
+                        if vehicle.shield.max > 0.0 {
+                            vehicle.shield.value =
+                                vehicle.shield.max + ability_stats.shield_overcharge;
+                        }
+                    }
+                    VehicleAbilityTypes::NitroBoost | VehicleAbilityTypes::Cloak => {}
+                }
+            }
+
+            //overcharged shields drop back down to their max
+            if expired == Some(VehicleAbilityTypes::ShieldOvercharge) {
+                vehicle.shield.value = vehicle.shield.value.min(vehicle.shield.max);
+            }
+
+            if let Some(ability) = &vehicle.ability {
+                if ability.is_active(VehicleAbilityTypes::NitroBoost) && is_active {
+                    vehicle.dx += ability.stats.accel * veh_x_comp * dt;
+                    vehicle.dy += ability.stats.accel * veh_y_comp * dt;
+                }
+
+                //fade cloaked vehicles out
+                if let Some(tint) = tints.get_mut(entity) {
+                    if ability.is_active(VehicleAbilityTypes::Cloak) {
+                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, CLOAKED_VEHICLE_ALPHA));
+                    } else {
+                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 1.0));
+                    }
+                }
+
+                //HUD indicator: green when ready, white while in use, grey while cooling down
+                if let Some(ui_text) = ui_texts.get_mut(ability.entity) {
+                    if ability.active_timer > 0.0 {
+                        ui_text.text = ability.stats.display_name.clone();
+                        ui_text.color = [1.0, 1.0, 1.0, 1.0];
+                    } else if ability.cooldown_timer > 0.0 {
+                        ui_text.text = format!(
+                            "{} {:.0}",
+                            ability.stats.display_name,
+                            ability.cooldown_timer.ceil()
+                        );
+                        ui_text.color = [0.5, 0.5, 0.5, 1.0];
+                    } else {
+                        ui_text.text = ability.stats.display_name.clone();
+                        ui_text.color = [0.3, 1.0, 0.3, 1.0];
+                    }
+                }
+            }
+        }
+    }
+}
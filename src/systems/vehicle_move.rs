@@ -22,17 +22,19 @@ use ncollide2d::query::{self, Proximity};
 use ncollide2d::shape::{Ball, Cuboid};
 
 use crate::components::{
-    check_respawn_vehicle, determine_vehicle_weight, get_random_weapon_name,
-    get_random_weapon_name_build_chance, kill_restart_vehicle, update_weapon_properties,
-    vehicle_damage_model, ArenaElement, ArenaNames, ArenaProperties, ArenaStoreResource, BotMode,
-    DurationDamage, HitboxShape, ObstacleType, Player, PlayerWeaponIcon, RaceCheckpointType,
-    Vehicle, VehicleMovementType, VehicleState, WeaponArray, WeaponStoreResource,
+    add_weapon_modifier, check_respawn_vehicle, determine_vehicle_weight,
+    get_random_weapon_modifier_name, get_random_weapon_name, get_random_weapon_name_build_chance,
+    kill_restart_vehicle, refresh_weapon_modifiers, update_weapon_properties, vehicle_damage_model,
+    ArenaElement, ArenaNames, ArenaProperties, ArenaStoreResource, DurationDamage, HitboxShape,
+    ObstacleType, Player, PlayerWeaponIcon, RaceCheckpointType, Vehicle, VehicleMovementType,
+    VehicleState, WeaponArray, WeaponStoreResource,
 };
 
 use crate::entities::{acceleration_spray, malfunction_sparking};
 
 use crate::resources::{
-    GameModeSetup, GameModes, GameWeaponSelectionMode, GameWeaponSetup, WeaponFireResource,
+    ArenaStorm, GameModeSetup, GameModes, GameWeaponSelectionMode, GameWeaponSetup,
+    WeaponFireResource,
 };
 
 use crate::rally::{
@@ -42,21 +44,16 @@ use crate::rally::{
 
 use crate::audio::{play_bounce_sound, Sounds};
 
-const BOT_COLLISION_TURN_COOLDOWN_RESET: f32 = 0.3;
-const BOT_COLLISION_MOVE_COOLDOWN_RESET: f32 = 0.3;
-
-const BOT_ENGAGE_DISTANCE: f32 = 160.0;
-const BOT_DISENGAGE_DISTANCE: f32 = 240.0;
-
-const BOT_NO_HIT_MOVE_COOLDOWN: f32 = 2.0;
-
 const WALL_HIT_BOUNCE_DECEL_PCT: f32 = 0.35;
 
 const ROCKET_SPRAY_COOLDOWN_RESET: f32 = 0.05;
 
-const PRIMARY_WEAPON_INDEX: usize = 0;
 const SECONDARY_WEAPON_INDEX: usize = 1;
 
+//shared with the bots so they know how hard each vehicle type can corner
+pub const TIRE_LATERAL_FRICTION_DECEL_RATE: f32 = 15.0;
+pub const TANK_TRACK_LATERAL_FRICTION_DECEL_RATE: f32 = 200.0;
+
 #[derive(SystemDesc, Default)]
 pub struct VehicleMoveSystem {
     pub last_spawn_index: u32,
@@ -85,6 +82,7 @@ impl<'s> System<'s> for VehicleMoveSystem {
         ReadExpect<'s, LazyUpdate>,
         ReadExpect<'s, WeaponStoreResource>,
         Write<'s, DebugLines>,
+        ReadExpect<'s, ArenaStorm>,
     );
 
     fn setup(&mut self, world: &mut World) {
@@ -136,6 +134,7 @@ impl<'s> System<'s> for VehicleMoveSystem {
             lazy_update,
             weapon_store_resource,
             mut debug_lines_resource,
+            arena_storm,
         ): Self::SystemData,
     ) {
         let mut rng = rand::thread_rng();
@@ -170,10 +169,16 @@ impl<'s> System<'s> for VehicleMoveSystem {
                 if vehicle.state == VehicleState::Active {
                     if game_weapon_setup.new_ammo_on_respawn {
                         for weapon_install in weapon_array.installed.iter_mut() {
+                            let weapon = &mut weapon_install.weapon;
+
                             if !weapon_install.ammo.is_none() {
-                                let mut weapon = &mut weapon_install.weapon;
                                 weapon.ammo = Some(weapon_install.ammo.unwrap());
                             }
+
+                            //full magazine and reserves
+                            weapon.magazine = weapon.stats.magazine_size;
+                            weapon.reserve_ammo = weapon.stats.reserve_ammo;
+                            weapon.reload_timer = 0.0;
                         }
                     }
 
@@ -209,6 +214,12 @@ impl<'s> System<'s> for VehicleMoveSystem {
                                 &lazy_update,
                             );
                         } //else, hadn't picked up a weapon spawn box yet
+
+                        //Modifiers are lost along with the picked up weapons
+                        if weapon_array.modifiers.len() > 0 {
+                            weapon_array.modifiers.clear();
+                            refresh_weapon_modifiers(&mut weapon_array, &weapon_store_resource);
+                        }
                     }
                 }
             }
@@ -223,10 +234,11 @@ impl<'s> System<'s> for VehicleMoveSystem {
             let thrust_friction_decel_rate: f32 = 30.0 * vehicle.engine_force / vehicle_weight;
 
             let tire_longitudinal_friction_decel_rate: f32 = 30.0;
-            let tire_lateral_friction_decel_rate: f32 = 15.0;
+            let tire_lateral_friction_decel_rate: f32 = TIRE_LATERAL_FRICTION_DECEL_RATE;
 
             let tank_track_longitudinal_friction_decel_rate: f32 = 80.0;
-            let tank_track_lateral_friction_decel_rate: f32 = 200.0;
+            let tank_track_lateral_friction_decel_rate: f32 =
+                TANK_TRACK_LATERAL_FRICTION_DECEL_RATE;
 
             let wall_hit_non_bounce_decel_pct: f32 = WALL_HIT_BOUNCE_DECEL_PCT;
             let wall_hit_bounce_decel_pct: f32 = -wall_hit_non_bounce_decel_pct;
@@ -294,432 +306,11 @@ impl<'s> System<'s> for VehicleMoveSystem {
             let vehicle_rotation = transform.rotation();
             let (_, _, vehicle_angle) = vehicle_rotation.euler_angles();
 
-            //Issue Bot commands
+            //Bot control intents are chosen by the BotAiSystem
             if player.is_bot {
-                player.bot_move_cooldown -= dt;
-                player.path_cooldown -= dt;
-
-                if vehicle.state == VehicleState::Active {
-                    if player.bot_mode == BotMode::Sleep {
-                        //Wakeup-logic needed here
-                        //In most game modes the bot should immediately wake up
-                        if game_mode_setup.game_mode == GameModes::Race {
-                            player.bot_mode = BotMode::Racing;
-                            debug!("{} Racing", player.id);
-                        } else {
-                            player.bot_mode = BotMode::RunRandom;
-                            debug!("{} Run Random", player.id);
-                        }
-                    } else if player.bot_mode == BotMode::Racing {
-                        //Determine which point to race to
-
-                        let next_checkpoint;
-                        if player.checkpoint_completed as usize
-                            >= (self.arena_properties.race_checkpoints.len() - 1)
-                        {
-                            next_checkpoint = self.arena_properties.race_checkpoints[0];
-                        //return to finish line
-                        } else {
-                            //go to next checkpoint
-                            next_checkpoint = self.arena_properties.race_checkpoints
-                                [(player.checkpoint_completed + 1) as usize];
-                        }
-
-                        player.path_target = Some((next_checkpoint.x, next_checkpoint.y, 0.0));
-
-                        if let Some(path_plan) = player.path_plan.clone() {
-                            if path_plan.len() >= 2 {
-                                let first_target = path_plan[1];
-                                let path_x = first_target.0;
-                                let path_y = first_target.1;
-
-                                let x_diff = vehicle_x - path_x;
-                                let y_diff = vehicle_y - path_y;
-
-                                let dist = (x_diff.powi(2) + y_diff.powi(2)).sqrt();
-
-                                let sq_vel = vehicle.dx.powi(2) + vehicle.dy.powi(2);
-                                let abs_vel = sq_vel.sqrt(); //Why does velocity not seem accurate without this weird multiplier???
-
-                                let approx_t_to_arrival = dist / abs_vel;
-                                let approx_t_to_rest = abs_vel / thrust_friction_decel_rate;
-
-                                let target_angle = y_diff.atan2(x_diff) + (PI / 2.0); //rotate by PI/2 to line up with 0deg is pointed towards top
-
-                                let mut angle_diff = vehicle_angle - target_angle;
-
-                                if angle_diff > PI {
-                                    angle_diff = -(2.0 * PI - angle_diff);
-                                } else if angle_diff < -PI {
-                                    angle_diff = -(-2.0 * PI - angle_diff);
-                                }
-
-                                let turn_value = 0.2;
-
-                                if angle_diff > 0.001 {
-                                    vehicle_turn = Some(-turn_value);
-                                } else if angle_diff < -0.001 {
-                                    vehicle_turn = Some(turn_value);
-                                } else {
-                                    vehicle_turn = Some(0.0);
-                                }
-
-                                if angle_diff.abs() < 0.2 {
-                                    if approx_t_to_arrival < approx_t_to_rest {
-                                        vehicle_accel = Some(0.5);
-                                    } else {
-                                        vehicle_accel = Some(0.8);
-                                    }
-                                }
-                            } else {
-                                vehicle_accel = Some(0.0);
-                                vehicle_turn = Some(0.0);
-                            }
-                        }
-                        player.last_accel_input = vehicle_accel;
-                        player.last_turn_input = vehicle_turn;
-
-                        if player.bot_move_cooldown < 0.0 {
-                            player.bot_move_cooldown = player.bot_move_cooldown_reset;
-                        }
-                    } else if player.bot_mode == BotMode::RunTo
-                        || player.bot_mode == BotMode::RunRandom
-                        || player.bot_mode == BotMode::RunBlind
-                        || player.bot_mode == BotMode::TakeTheHill
-                        || player.bot_mode == BotMode::Mining
-                        || player.bot_mode == BotMode::Repairing
-                    {
-                        if game_mode_setup.game_mode == GameModes::KingOfTheHill
-                            && player.on_hill == false
-                        {
-                            player.bot_mode = BotMode::TakeTheHill;
-                            debug!("{} TakeTheHill", player.id);
-                        } else {
-                            //check to change mode
-                            if let Some(dist_to_closest_vehicle) = vehicle.dist_to_closest_vehicle {
-                                if dist_to_closest_vehicle <= BOT_ENGAGE_DISTANCE
-                                    && player.bot_move_cooldown < 0.0
-                                {
-                                    if weapon_array.installed.len() > 0 {
-                                        let primary_weapon =
-                                            &weapon_array.installed[PRIMARY_WEAPON_INDEX].weapon;
-                                        //change modes to attack
-                                        if primary_weapon.stats.fire_stats.attached {
-                                            //Typically just LaserSword
-                                            player.bot_mode = BotMode::Swording;
-                                            debug!("{} Swording", player.id);
-                                            player.bot_move_cooldown = 5.0;
-                                            player.last_made_hit_timer = 0.0;
-                                        } else if primary_weapon.stats.fire_stats.shot_speed <= 0.0
-                                        {
-                                            //Typically just Mines or Traps
-                                            player.bot_mode = BotMode::Mining;
-                                            debug!("{} Mining", player.id);
-                                        } else {
-                                            player.bot_mode = BotMode::StopAim;
-                                            debug!("{} StopAim", player.id);
-                                            player.bot_move_cooldown = 1.0;
-                                            player.last_made_hit_timer = 0.0;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        if player.bot_mode == BotMode::RunTo
-                            || player.bot_mode == BotMode::RunRandom
-                            || player.bot_mode == BotMode::RunBlind
-                            || player.bot_mode == BotMode::TakeTheHill
-                            || player.bot_mode == BotMode::Mining
-                            || player.bot_mode == BotMode::Repairing
-                        {
-                            if player.bot_mode == BotMode::TakeTheHill {
-                                if player.on_hill == false {
-                                    player.path_target = Some((
-                                        self.arena_properties.width / 2.0,
-                                        (self.arena_properties.height) / 2.0,
-                                        0.0,
-                                    ));
-                                } else {
-                                    player.path_target = None;
-                                }
-                            } else if player.bot_mode == BotMode::RunTo
-                                || player.bot_mode == BotMode::RunRandom
-                                || player.bot_mode == BotMode::Mining
-                                || player.bot_mode == BotMode::Repairing
-                            {
-                                if player.path_target.is_none() || player.path_cooldown <= 0.0 {
-                                    let random_x =
-                                        rng.gen_range(0.0, self.arena_properties.width) as f32;
-                                    let random_y =
-                                        rng.gen_range(0.0, self.arena_properties.height) as f32;
-
-                                    player.path_target = Some((random_x, random_y, 0.0));
-
-                                    player.path_cooldown = player.path_cooldown_reset;
-                                }
-                            } else if player.bot_mode == BotMode::RunBlind {
-                                player.path_target = None;
-                            } else {
-                                player.path_target = None;
-                            }
-
-                            if let Some(dist_to_closest_vehicle) = vehicle.dist_to_closest_vehicle {
-                                if (vehicle.health.value < vehicle.health.max
-                                    || (vehicle.shield.max > 0.0 && vehicle.shield.value == 0.0))
-                                    && dist_to_closest_vehicle > BOT_DISENGAGE_DISTANCE
-                                    && player.last_hit_timer > 1.0
-                                {
-                                    player.bot_mode = BotMode::Repairing;
-                                }
-                            }
-
-                            if let Some(path_plan) = player.path_plan.clone() {
-                                if path_plan.len() >= 2 {
-                                    let first_target = path_plan[1];
-                                    let path_x = first_target.0;
-                                    let path_y = first_target.1;
-
-                                    let x_diff = vehicle_x - path_x;
-                                    let y_diff = vehicle_y - path_y;
-
-                                    let dist = (x_diff.powi(2) + y_diff.powi(2)).sqrt();
-
-                                    let sq_vel = vehicle.dx.powi(2) + vehicle.dy.powi(2);
-                                    let abs_vel = sq_vel.sqrt() * 10.0; //Why does velocity not seem accurate without this weird multiplier???
-
-                                    let approx_t_to_arrival = dist / abs_vel;
-                                    let approx_t_to_rest = abs_vel / thrust_friction_decel_rate;
-
-                                    let target_angle = y_diff.atan2(x_diff) + (PI / 2.0); //rotate by PI/2 to line up with 0deg is pointed towards top
-
-                                    let mut angle_diff = vehicle_angle - target_angle;
-
-                                    if angle_diff > PI {
-                                        angle_diff = -(2.0 * PI - angle_diff);
-                                    } else if angle_diff < -PI {
-                                        angle_diff = -(-2.0 * PI - angle_diff);
-                                    }
-
-                                    let turn_value = 0.2;
-
-                                    if angle_diff > 0.001 {
-                                        vehicle_turn = Some(-turn_value);
-                                    } else if angle_diff < -0.001 {
-                                        vehicle_turn = Some(turn_value);
-                                    } else {
-                                        vehicle_turn = Some(0.0);
-                                    }
-
-                                    if angle_diff.abs() < 0.2 {
-                                        if approx_t_to_arrival < approx_t_to_rest {
-                                            vehicle_accel = Some(0.5);
-                                        } else {
-                                            vehicle_accel = Some(0.8);
-                                        }
-                                    }
-                                } else {
-                                    vehicle_accel = Some(0.0);
-                                    vehicle_turn = Some(0.0);
-                                }
-                            } else {
-                                //random movement input when no path is specified
-                                if player.bot_move_cooldown < 0.0 {
-                                    //issue new move command
-                                    vehicle_accel = Some(rng.gen_range(0.3, 0.5) as f32);
-                                    vehicle_turn = Some(rng.gen_range(-0.3, 0.3) as f32);
-                                } else {
-                                    //hold previous random move
-                                    vehicle_accel = player.last_accel_input;
-                                    vehicle_turn = player.last_turn_input;
-                                }
-                            }
-                            player.last_accel_input = vehicle_accel;
-                            player.last_turn_input = vehicle_turn;
-
-                            if player.bot_move_cooldown < 0.0 {
-                                player.bot_move_cooldown = player.bot_move_cooldown_reset;
-                            }
-                        }
-                    } else if player.bot_mode == BotMode::StopAim
-                        || player.bot_mode == BotMode::StrafeAim
-                        || player.bot_mode == BotMode::Chasing
-                        || player.bot_mode == BotMode::Swording
-                    {
-                        player.last_made_hit_timer += dt;
-
-                        let continue_with_attacking_mode;
-
-                        if let Some(dist_to_closest_vehicle) = vehicle.dist_to_closest_vehicle {
-                            //if the closest vehicle is too far away to engage
-                            if dist_to_closest_vehicle > BOT_DISENGAGE_DISTANCE
-                                && player.bot_move_cooldown < 0.0
-                            {
-                                continue_with_attacking_mode = false;
-
-                                player.bot_move_cooldown = player.bot_move_cooldown_reset;
-
-                                let run_or_chase = rng.gen::<bool>();
-
-                                if run_or_chase {
-                                    player.bot_mode = BotMode::RunRandom;
-                                    debug!("{} Run Random", player.id);
-                                } else {
-                                    player.bot_mode = BotMode::Chasing;
-                                    debug!("{} Chasing", player.id);
-                                    player.last_made_hit_timer = 0.0;
-                                }
-                            } else {
-                                //closest vehicle is close enough to engage
-                                //vehicle is close, but the bot isn't hitting it within 3 seconds
-                                if player.last_made_hit_timer > 3.0
-                                    && player.bot_mode != BotMode::Swording
-                                {
-                                    continue_with_attacking_mode = false;
-
-                                    player.bot_mode = BotMode::RunRandom;
-                                    player.bot_move_cooldown = BOT_NO_HIT_MOVE_COOLDOWN;
-                                } else {
-                                    //closest vehicle is out of current weapon range though
-                                    if weapon_array.installed.len() > 0 {
-                                        let primary_weapon =
-                                            &weapon_array.installed[PRIMARY_WEAPON_INDEX].weapon;
-                                        if dist_to_closest_vehicle > primary_weapon.range_calc {
-                                            player.bot_mode = BotMode::Chasing;
-                                            debug!("{} Chasing", player.id);
-                                            player.last_made_hit_timer = 0.0;
-                                        }
-                                    }
-                                    continue_with_attacking_mode = true;
-                                }
-                            }
-                        } else {
-                            //no closest vehicle exists, only vehicle alive right now?
-                            continue_with_attacking_mode = false;
-
-                            player.bot_move_cooldown = player.bot_move_cooldown_reset;
-
-                            let run_or_chase = rng.gen::<bool>();
-
-                            if run_or_chase {
-                                player.bot_mode = BotMode::RunRandom;
-                                debug!("{} Run Random", player.id);
-                            } else {
-                                player.bot_mode = BotMode::Chasing;
-                                debug!("{} Chasing", player.id);
-                                player.last_made_hit_timer = 0.0;
-                            }
-                        }
-
-                        if continue_with_attacking_mode {
-                            //continue with Attacking mode
-                            if player.bot_mode == BotMode::StopAim && player.last_hit_timer < 2.0 {
-                                player.bot_mode = BotMode::StrafeAim;
-                                debug!("{} StrafeAim {}", player.id, player.bot_move_cooldown);
-                            }
-
-                            if player.bot_move_cooldown < 0.0 {
-                                if player.bot_mode == BotMode::StrafeAim {
-                                    let left_or_right_strafe = rng.gen::<bool>();
-
-                                    player.bot_move_cooldown = player.bot_move_cooldown_reset;
-                                    if left_or_right_strafe {
-                                        vehicle_strafe = Some(0.8);
-                                    } else {
-                                        vehicle_strafe = Some(-0.8);
-                                    }
-                                } else {
-                                    player.bot_move_cooldown = player.bot_move_cooldown_reset;
-                                    vehicle_strafe = Some(0.0);
-                                }
-                            }
-
-                            if let Some(attack_angle) = vehicle.angle_to_closest_vehicle {
-                                let turn_value = 1.0;
-
-                                if weapon_array.installed.len() > 0 {
-                                    let primary_weapon_install =
-                                        &weapon_array.installed[PRIMARY_WEAPON_INDEX];
-
-                                    let mounted_angle;
-
-                                    if let Some(mounted_angle_from_install) =
-                                        primary_weapon_install.mounted_angle
-                                    {
-                                        mounted_angle = mounted_angle_from_install;
-                                    } else {
-                                        mounted_angle = 0.0;
-                                    }
-
-                                    //Prepare magnitude of Turning and Acceleration input
-                                    if player.bot_mode == BotMode::Swording
-                                        || player.bot_mode == BotMode::Chasing
-                                    {
-                                        if (mounted_angle
-                                            + primary_weapon_install
-                                                .weapon
-                                                .stats
-                                                .fire_stats
-                                                .mount_angle_special_offset)
-                                            > PI / 4.0
-                                            || (mounted_angle
-                                                + primary_weapon_install
-                                                    .weapon
-                                                    .stats
-                                                    .fire_stats
-                                                    .mount_angle_special_offset)
-                                                < -PI / 4.0
-                                        {
-                                            vehicle_accel = Some(-0.6); //drive backwards sword fighting
-                                        } else {
-                                            vehicle_accel = Some(0.6); //drive forwards sword fighting
-                                        }
-                                    }
-
-                                    //Solve for Angle and Direction to turn
-                                    let angle_diff = clean_angle(
-                                        vehicle_angle
-                                            + mounted_angle
-                                            + primary_weapon_install
-                                                .weapon
-                                                .stats
-                                                .fire_stats
-                                                .mount_angle_special_offset
-                                            - attack_angle,
-                                    );
-
-                                    if angle_diff > 0.001 {
-                                        vehicle_turn = Some(-turn_value);
-                                    } else if angle_diff < -0.001 {
-                                        vehicle_turn = Some(turn_value);
-                                    } else {
-                                        vehicle_turn = Some(0.0);
-                                    }
-                                }
-                            }
-                        }
-                    } else if player.bot_mode == BotMode::CollisionTurn {
-                        vehicle_accel = Some(0.5);
-                        vehicle_turn = Some(1.0);
-
-                        if player.bot_move_cooldown < 0.0 {
-                            player.bot_mode = BotMode::CollisionMove;
-                            debug!("{} CollisionMove", player.id);
-                            player.bot_move_cooldown = BOT_COLLISION_MOVE_COOLDOWN_RESET;
-                        }
-                    } else if player.bot_mode == BotMode::CollisionMove {
-                        vehicle_accel = Some(0.5);
-                        vehicle_turn = Some(0.0);
-
-                        if game_mode_setup.game_mode == GameModes::Race {
-                            player.bot_mode = BotMode::Racing;
-                            debug!("{} Racing", player.id);
-                        } else if player.bot_move_cooldown < 0.0 {
-                            player.bot_mode = BotMode::RunRandom;
-                            debug!("{} Run Random", player.id);
-                        }
-                    }
-                }
+                vehicle_accel = player.bot_intent.accel;
+                vehicle_turn = player.bot_intent.turn;
+                vehicle_strafe = player.bot_intent.strafe;
             }
 
             let veh_x_comp = -vehicle_angle.sin(); //left is -, right is +
@@ -924,9 +515,17 @@ impl<'s> System<'s> for VehicleMoveSystem {
                 vehicle.restricted_velocity_timer -= dt;
             }
 
-            if abs_vel > vehicle.restricted_max_velocity {
-                vehicle.dx *= vehicle.restricted_max_velocity / abs_vel;
-                vehicle.dy *= vehicle.restricted_max_velocity / abs_vel;
+            //boosts and dashes lift the top speed while they last
+            let mut max_velocity = vehicle.restricted_max_velocity;
+            if let Some(ability) = &vehicle.ability {
+                if ability.active_timer > 0.0 {
+                    max_velocity *= 1.0 + ability.stats.max_velocity_pct / 100.0;
+                }
+            }
+
+            if abs_vel > max_velocity {
+                vehicle.dx *= max_velocity / abs_vel;
+                vehicle.dy *= max_velocity / abs_vel;
             }
 
             //Transform on vehicle velocity
@@ -1108,16 +707,6 @@ impl<'s> System<'s> for VehicleMoveSystem {
                 }
             }
 
-            if player.is_bot
-                && (x_collision || y_collision)
-                && (player.bot_mode != BotMode::CollisionTurn)
-                && (player.bot_mode != BotMode::CollisionMove)
-            {
-                player.bot_mode = BotMode::CollisionTurn;
-                debug!("{} CollisionTurn", player.id);
-                player.bot_move_cooldown = BOT_COLLISION_TURN_COOLDOWN_RESET;
-            }
-
             vehicle.collision_cooldown_timer -= dt;
         }
 
@@ -1158,7 +747,10 @@ impl<'s> System<'s> for VehicleMoveSystem {
 
                 if arena_element.hitbox.shape == HitboxShape::Circle {
                     let hitbox_collider_shape = Ball::new(arena_element.hitbox.width / 2.0);
-                    let hitbox_collider_pos = Isometry2::new(Vector2::new(hitbox_x, hitbox_y), 0.0);
+                    let hitbox_collider_pos = Isometry2::new(
+                        Vector2::new(hitbox_x, hitbox_y),
+                        arena_element.hitbox.angle,
+                    );
 
                     let collision = query::proximity(
                         &vehicle_collider_pos,
@@ -1188,7 +780,10 @@ impl<'s> System<'s> for VehicleMoveSystem {
                         arena_element.hitbox.width / 2.0,
                         arena_element.hitbox.height / 2.0,
                     ));
-                    let hitbox_collider_pos = Isometry2::new(Vector2::new(hitbox_x, hitbox_y), 0.0);
+                    let hitbox_collider_pos = Isometry2::new(
+                        Vector2::new(hitbox_x, hitbox_y),
+                        arena_element.hitbox.angle,
+                    );
 
                     let collision = query::proximity(
                         &vehicle_collider_pos,
@@ -1218,7 +813,10 @@ impl<'s> System<'s> for VehicleMoveSystem {
                         arena_element.hitbox.width / 2.0,
                         arena_element.hitbox.height / 2.0,
                     ));
-                    let hitbox_collider_pos = Isometry2::new(Vector2::new(hitbox_x, hitbox_y), 0.0);
+                    let hitbox_collider_pos = Isometry2::new(
+                        Vector2::new(hitbox_x, hitbox_y),
+                        arena_element.hitbox.angle,
+                    );
 
                     let collision = query::proximity(
                         &vehicle_collider_pos,
@@ -1248,7 +846,10 @@ impl<'s> System<'s> for VehicleMoveSystem {
                         arena_element.hitbox.width / 2.0,
                         arena_element.hitbox.height / 2.0,
                     ));
-                    let hitbox_collider_pos = Isometry2::new(Vector2::new(hitbox_x, hitbox_y), 0.0);
+                    let hitbox_collider_pos = Isometry2::new(
+                        Vector2::new(hitbox_x, hitbox_y),
+                        arena_element.hitbox.angle,
+                    );
 
                     let collision = query::proximity(
                         &vehicle_collider_pos,
@@ -1291,6 +892,7 @@ impl<'s> System<'s> for VehicleMoveSystem {
                             hitbox_x,
                             hitbox_y,
                             arena_element.hitbox.shape,
+                            arena_element.hitbox.angle,
                             vehicle.dx.clone(),
                             vehicle.dy.clone(),
                         );
@@ -1300,15 +902,6 @@ impl<'s> System<'s> for VehicleMoveSystem {
 
                         player_arena_bounce_map.insert(player.id.clone(), contact_pt);
 
-                        if player.is_bot
-                            && (player.bot_mode != BotMode::CollisionTurn)
-                            && (player.bot_mode != BotMode::CollisionMove)
-                        {
-                            player.bot_mode = BotMode::CollisionTurn;
-                            debug!("{} CollisionTurn", player.id);
-                            player.bot_move_cooldown = BOT_COLLISION_TURN_COOLDOWN_RESET;
-                        }
-
                         if vehicle.collision_cooldown_timer <= 0.0 {
                             let damage: f32 = BASE_COLLISION_DAMAGE * abs_vel / 100.0;
                             debug!("Player {} has collided with {} damage", player.id, damage);
@@ -1400,6 +993,22 @@ impl<'s> System<'s> for VehicleMoveSystem {
                             if arena_element.is_weapon_box {
                                 let _ = entities.delete(hitbox_entity);
 
+                                //some boxes hold a modifier for the installed weapons instead
+                                let modifier_added = match get_random_weapon_modifier_name(
+                                    &weapon_store_resource.modifier_table,
+                                ) {
+                                    Some(modifier_name) => add_weapon_modifier(
+                                        &mut weapon_array,
+                                        modifier_name,
+                                        &weapon_store_resource,
+                                    ),
+                                    None => false,
+                                };
+
+                                if modifier_added {
+                                    continue;
+                                }
+
                                 let new_weapon_name;
                                 if arena_element.weapon_names.is_none() {
                                     //get random weapon from global list
@@ -1478,6 +1087,33 @@ impl<'s> System<'s> for VehicleMoveSystem {
                     }
                 }
             }
+
+            //Shrinking arena boundary damage
+            if arena_storm.is_outside(vehicle_x, vehicle_y) {
+                let vehicle_destroyed: bool = vehicle_damage_model(
+                    vehicle,
+                    None,
+                    None,
+                    arena_storm.damage_rate * dt,
+                    COLLISION_PIERCING_DAMAGE_PCT,
+                    COLLISION_SHIELD_DAMAGE_PCT,
+                    COLLISION_ARMOR_DAMAGE_PCT,
+                    COLLISION_HEALTH_DAMAGE_PCT,
+                    DurationDamage::default(),
+                );
+
+                if vehicle_destroyed && !player_destroyed.contains(&player.id) {
+                    player_destroyed.push(player.id.clone());
+
+                    player.deaths += 1;
+
+                    if player.last_hit_timer <= game_mode_setup.last_hit_threshold {
+                        if let Some(last_hit_by_id) = player.last_hit_by_id {
+                            earned_collision_kills.push(last_hit_by_id);
+                        }
+                    }
+                }
+            }
         }
 
         for (player, vehicle, transform) in (&mut players, &mut vehicles, &mut transforms).join() {
@@ -1513,7 +1149,11 @@ impl<'s> System<'s> for VehicleMoveSystem {
         }
 
         //King of the Hill - Hill tint
-        for (entity, _arena_element) in (&*entities, &arena_elements).join() {
+        for (entity, arena_element) in (&*entities, &arena_elements).join() {
+            if !arena_element.is_hill {
+                continue;
+            }
+
             if let Some(tint) = tints.get_mut(entity) {
                 if players_on_hill.len() == 1 {
                     *tint = Tint(Srgba::new(
@@ -1646,13 +1286,14 @@ pub fn calc_bounce_angle(
     hitbox_x: f32,
     hitbox_y: f32,
     hitbox_shape: HitboxShape,
+    hitbox_angle: f32,
     moving_dx: f32,
     moving_dy: f32,
 ) -> (f32, f32, f32) {
     //(new_dx, new_dy, new_angle)
     //Input:
     //contact_pt.x, contact_pt.y
-    //hitbox_x, hitbox_y, hitbox_shape
+    //hitbox_x, hitbox_y, hitbox_shape, hitbox_angle
 
     //Output:
     //moving.dx, moving.dy
@@ -1669,11 +1310,11 @@ pub fn calc_bounce_angle(
 
         new_angle = clean_angle(contact_perp_angle + (contact_perp_angle - moving_angle));
     } else
-    //rectangle
+    //rectangle, mirrored about the rectangle's rotated x axis
     {
-        let contact_perp_angle = 0.0;
+        let contact_perp_angle = hitbox_angle;
 
-        new_angle = contact_perp_angle - moving_angle;
+        new_angle = clean_angle(contact_perp_angle + (contact_perp_angle - moving_angle));
     }
 
     let moving_speed = (moving_dx.powi(2) + moving_dy.powi(2)).sqrt();
@@ -1707,6 +1348,7 @@ mod tests {
             1.0,
             0.0, //hitbox (x,y)
             HitboxShape::Circle,
+            0.0,
             -1.0,
             1.0, //moving dx, dy
         );
@@ -1723,6 +1365,7 @@ mod tests {
             0.0,
             -1.0, //hitbox (x,y)
             HitboxShape::Circle,
+            0.0,
             1.0,
             1.0, //moving dx, dy
         );
@@ -1739,6 +1382,7 @@ mod tests {
             0.0,
             -1.0, //hitbox (x,y)
             HitboxShape::Circle,
+            0.0,
             0.125,
             0.9, //moving dx, dy
         );
@@ -1756,6 +1400,24 @@ mod tests {
             -1.0 / (2.0 as f32).sqrt(), //hitbox (x,y)
             HitboxShape::Circle,
             0.0,
+            0.0,
+            1.0, //moving dx, dy
+        );
+
+        assert_approx_eq!(moving_dx_new, 1.0);
+        assert_approx_eq!(moving_dy_new, 0.0);
+    }
+
+    #[test]
+    fn test_calc_bounce_angle_rotated_rectangle() {
+        let (moving_dx_new, moving_dy_new, _new_angle) = calc_bounce_angle(
+            0.0,
+            0.0, //contact pt (x,y)
+            1.0,
+            1.0, //hitbox (x,y)
+            HitboxShape::Rectangle,
+            PI / 4.0,
+            0.0,
             1.0, //moving dx, dy
         );
 
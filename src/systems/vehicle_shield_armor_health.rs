@@ -9,7 +9,7 @@ use amethyst::{
 use rand::Rng;
 use std::collections::HashMap;
 
-use crate::components::{BotMode, Player, Vehicle, VehicleState};
+use crate::components::{vehicle_cloak_alpha, Player, Vehicle, VehicleState};
 
 #[derive(SystemDesc)]
 pub struct VehicleShieldArmorHealthSystem;
@@ -72,8 +72,8 @@ impl<'s> System<'s> for VehicleShieldArmorHealthSystem {
 
             //Repairing must be initiated by the player
             let vehicle_repair;
-            if player.is_bot && player.bot_mode == BotMode::Repairing {
-                vehicle_repair = Some(true);
+            if player.is_bot {
+                vehicle_repair = player.bot_intent.repair;
             } else {
                 // if MP_BINDINGS {
                 //     vehicle_repair = input.action_is_down(&ActionBinding::VehicleRepair(player.id));
@@ -187,6 +187,9 @@ impl<'s> System<'s> for VehicleShieldArmorHealthSystem {
                     shield_repair_pct,
                 ) = owner_data;
 
+                //overlays fade out along with a cloaked vehicle
+                let cloak_alpha = vehicle_cloak_alpha(vehicle);
+
                 //Shield update
                 {
                     let transform = transforms.get_mut(vehicle.shield.entity).unwrap();
@@ -197,9 +200,9 @@ impl<'s> System<'s> for VehicleShieldArmorHealthSystem {
 
                     let tint = tints.get_mut(vehicle.shield.entity).unwrap();
                     if *shield_pct < 0.5 {
-                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, (*shield_pct) * 2.0));
+                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, (*shield_pct) * 2.0 * cloak_alpha));
                     } else {
-                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 1.0));
+                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, cloak_alpha));
                     }
                 }
 
@@ -213,9 +216,9 @@ impl<'s> System<'s> for VehicleShieldArmorHealthSystem {
 
                     let tint = tints.get_mut(vehicle.armor.entity).unwrap();
                     if *armor_pct < 0.5 {
-                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, *(armor_pct) * 2.0));
+                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, *(armor_pct) * 2.0 * cloak_alpha));
                     } else {
-                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 1.0));
+                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, cloak_alpha));
                     }
                 }
 
@@ -229,9 +232,14 @@ impl<'s> System<'s> for VehicleShieldArmorHealthSystem {
 
                     let tint = tints.get_mut(vehicle.health.entity).unwrap();
                     if *health_pct <= 0.0 {
-                        *tint = Tint(Srgba::new(0.0, 0.0, 0.0, 1.0));
+                        *tint = Tint(Srgba::new(0.0, 0.0, 0.0, cloak_alpha));
                     } else if *health_pct < (4. / 5.) {
-                        *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 1.0 - ((*health_pct) * (5. / 4.))));
+                        *tint = Tint(Srgba::new(
+                            1.0,
+                            1.0,
+                            1.0,
+                            (1.0 - ((*health_pct) * (5. / 4.))) * cloak_alpha,
+                        ));
                     } else {
                         *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 0.0));
                     }
@@ -250,10 +258,10 @@ impl<'s> System<'s> for VehicleShieldArmorHealthSystem {
                     let tint = tints.get_mut(vehicle.repair.entity).unwrap();
                     if *shield_repair_pct > 0.01 {
                         let blue = rng.gen_range(0.5, 1.0);
-                        *tint = Tint(Srgba::new(0.0, 0.0, blue, blue));
+                        *tint = Tint(Srgba::new(0.0, 0.0, blue, blue * cloak_alpha));
                     } else if *health_repair_pct > 0.01 {
                         let red = rng.gen_range(0.5, 1.0);
-                        *tint = Tint(Srgba::new(red, 0.0, 0.0, red));
+                        *tint = Tint(Srgba::new(red, 0.0, 0.0, red * cloak_alpha));
                     } else {
                         *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 0.0));
                     }
@@ -5,7 +5,15 @@ use amethyst::ecs::{Join, ReadStorage, System, SystemData, WriteStorage};
 use std::collections::HashMap;
 use std::f32::consts::PI;
 
-use crate::components::{Player, Vehicle, VehicleState, WeaponArray};
+extern crate nalgebra as na;
+use na::{Isometry2, Point2, Vector2};
+use ncollide2d::query::{Ray, RayCast};
+use ncollide2d::shape::{Ball, Cuboid};
+
+use crate::components::{
+    vehicle_is_cloaked, ArenaElement, HitboxShape, ObstacleType, Player, Vehicle, VehicleState,
+    WeaponArray,
+};
 
 #[derive(SystemDesc)]
 pub struct VehicleTrackingSystem;
@@ -16,9 +24,18 @@ impl<'s> System<'s> for VehicleTrackingSystem {
         ReadStorage<'s, Transform>,
         WriteStorage<'s, Vehicle>,
         ReadStorage<'s, WeaponArray>,
+        ReadStorage<'s, ArenaElement>,
     );
 
-    fn run(&mut self, (mut players, transforms, mut vehicles, weapon_arrays): Self::SystemData) {
+    fn run(
+        &mut self,
+        (mut players, transforms, mut vehicles, weapon_arrays, arena_elements): Self::SystemData,
+    ) {
+        let walls: Vec<&ArenaElement> = arena_elements
+            .join()
+            .filter(|arena_element| arena_element.obstacle_type == ObstacleType::Wall)
+            .collect();
+
         //Find closest vehicle, and closest targetable vehicle within weapon angle target range
         let mut closest_target_angles_map = HashMap::new();
 
@@ -30,9 +47,12 @@ impl<'s> System<'s> for VehicleTrackingSystem {
 
                 let mut closest_vehicle_dist: Option<f32> = None;
                 let mut closest_vehicle_target_angle: Option<f32> = None;
+                let mut closest_vehicle_velocity: Option<(f32, f32)> = None;
+                let mut closest_vehicle_in_sight = false;
 
                 let mut closest_targetable_vehicle_dist: Option<f32> = None;
                 let mut closest_targetable_vehicle_target_angle: Option<f32> = None;
+                let mut closest_targetable_vehicle_velocity: Option<(f32, f32)> = None;
 
                 let vehicle1_x = vehicle1_transform.translation().x;
                 let vehicle1_y = vehicle1_transform.translation().y;
@@ -54,10 +74,11 @@ impl<'s> System<'s> for VehicleTrackingSystem {
                 for (player2, vehicle2, vehicle2_transform) in
                     (&players, &vehicles, &transforms).join()
                 {
-                    //Other player must be active and not on the same team
+                    //Other player must be active, not on the same team, and not cloaked
                     if player1.id != player2.id
                         && player1.team != player2.team
                         && vehicle2.state == VehicleState::Active
+                        && !vehicle_is_cloaked(vehicle2)
                     {
                         let vehicle2_x = vehicle2_transform.translation().x;
                         let vehicle2_y = vehicle2_transform.translation().y;
@@ -75,10 +96,22 @@ impl<'s> System<'s> for VehicleTrackingSystem {
                             target_angle -= 2.0 * PI;
                         }
 
-                        //Save closest vehicle, ...
-                        if closest_vehicle_dist.is_none() || dist < closest_vehicle_dist.unwrap() {
+                        let in_sight = has_line_of_sight(
+                            &walls, vehicle1_x, vehicle1_y, vehicle2_x, vehicle2_y,
+                        );
+
+                        //Save closest vehicle, preferring ones that can be seen, ...
+                        let closer_vehicle = if in_sight != closest_vehicle_in_sight {
+                            in_sight
+                        } else {
+                            closest_vehicle_dist.is_none() || dist < closest_vehicle_dist.unwrap()
+                        };
+
+                        if closer_vehicle {
                             closest_vehicle_dist = Some(dist);
                             closest_vehicle_target_angle = Some(target_angle);
+                            closest_vehicle_velocity = Some((vehicle2.dx, vehicle2.dy));
+                            closest_vehicle_in_sight = in_sight;
                         }
 
                         //...and closest targetable vehicle
@@ -93,7 +126,7 @@ impl<'s> System<'s> for VehicleTrackingSystem {
                         }
 
                         let targetable;
-                        if angle_diff.abs() < primary_weapon.stats.tracking_angle {
+                        if in_sight && angle_diff.abs() < primary_weapon.stats.tracking_angle {
                             targetable = true;
                         } else {
                             targetable = false;
@@ -105,6 +138,8 @@ impl<'s> System<'s> for VehicleTrackingSystem {
                             {
                                 closest_targetable_vehicle_dist = Some(dist);
                                 closest_targetable_vehicle_target_angle = Some(target_angle);
+                                closest_targetable_vehicle_velocity =
+                                    Some((vehicle2.dx, vehicle2.dy));
                             }
                         }
                     }
@@ -115,8 +150,11 @@ impl<'s> System<'s> for VehicleTrackingSystem {
                     (
                         closest_vehicle_target_angle,
                         closest_vehicle_dist,
+                        closest_vehicle_velocity,
+                        closest_vehicle_in_sight,
                         closest_targetable_vehicle_target_angle,
                         closest_targetable_vehicle_dist,
+                        closest_targetable_vehicle_velocity,
                     ),
                 );
             }
@@ -130,17 +168,50 @@ impl<'s> System<'s> for VehicleTrackingSystem {
                 let (
                     closest_vehicle_target_angle,
                     closest_vehicle_dist,
+                    closest_vehicle_velocity,
+                    closest_vehicle_in_sight,
                     closest_targetable_vehicle_target_angle,
                     closest_targetable_vehicle_dist,
+                    closest_targetable_vehicle_velocity,
                 ) = closest_target_angles;
 
                 vehicle.angle_to_closest_vehicle = *closest_vehicle_target_angle;
                 vehicle.dist_to_closest_vehicle = *closest_vehicle_dist;
+                vehicle.velocity_of_closest_vehicle = *closest_vehicle_velocity;
+                vehicle.closest_vehicle_in_sight = *closest_vehicle_in_sight;
 
                 vehicle.angle_to_closest_targetable_vehicle =
                     *closest_targetable_vehicle_target_angle;
                 vehicle.dist_to_closest_targetable_vehicle = *closest_targetable_vehicle_dist;
+                vehicle.velocity_of_closest_targetable_vehicle =
+                    *closest_targetable_vehicle_velocity;
             }
         }
     }
 }
+
+//Whether the straight line between two points is clear of arena walls
+pub fn has_line_of_sight(walls: &Vec<&ArenaElement>, x1: f32, y1: f32, x2: f32, y2: f32) -> bool {
+    //a ray scaled to the length of the line, so a time of impact past 1.0 is beyond the end point
+    let sight_ray = Ray::new(Point2::new(x1, y1), Vector2::new(x2 - x1, y2 - y1));
+
+    for wall in walls.iter() {
+        let wall_pos = Isometry2::new(Vector2::new(wall.x, wall.y), wall.hitbox.angle);
+
+        let toi = if wall.hitbox.shape == HitboxShape::Circle {
+            Ball::new(wall.hitbox.width / 2.0).toi_with_ray(&wall_pos, &sight_ray, 1.0, true)
+        } else {
+            Cuboid::new(Vector2::new(
+                wall.hitbox.width / 2.0,
+                wall.hitbox.height / 2.0,
+            ))
+            .toi_with_ray(&wall_pos, &sight_ray, 1.0, true)
+        };
+
+        if toi.is_some() {
+            return false;
+        }
+    }
+
+    true
+}
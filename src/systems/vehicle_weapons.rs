@@ -4,14 +4,21 @@ use amethyst::{
     ecs::{Entities, Join, LazyUpdate, Read, ReadExpect, System, SystemData, WriteStorage},
     input::{InputHandler, StringBindings},
     renderer::{palette::Srgba, resources::Tint},
+    ui::{UiText, UiTransform},
 };
 
 use rand::Rng;
 use std::f32::consts::PI;
 
-use crate::components::{BotMode, Player, Vehicle, VehicleState, WeaponArray};
-use crate::entities::fire_weapon;
+use crate::components::{
+    get_weapon_modifiers_text, Player, Vehicle, VehicleState, WeaponArray, WeaponStoreResource,
+    WEAPON_HEAT_BAR_HEIGHT, WEAPON_MAX_HEAT, WEAPON_SPLIT_SHOT_ANGLE,
+};
+use crate::entities::{deploy_weapon, fire_weapon};
 use crate::resources::WeaponFireResource;
+use crate::systems::clean_angle;
+
+const LEAD_AIM_ITERATIONS: usize = 8;
 
 #[derive(SystemDesc)]
 pub struct VehicleWeaponsSystem;
@@ -24,7 +31,10 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
         WriteStorage<'s, Vehicle>,
         WriteStorage<'s, WeaponArray>,
         WriteStorage<'s, Tint>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
         ReadExpect<'s, WeaponFireResource>,
+        ReadExpect<'s, WeaponStoreResource>,
         ReadExpect<'s, LazyUpdate>,
         Read<'s, Time>,
         Read<'s, InputHandler<StringBindings>>, //MovementBindingTypes
@@ -39,7 +49,10 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
             mut vehicles,
             mut weapon_arrays,
             mut tints,
+            mut ui_transforms,
+            mut ui_texts,
             weapon_fire_resource,
+            weapon_store_resource,
             lazy_update,
             time,
             input,
@@ -56,25 +69,26 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
         )
             .join()
         {
+            //list the weapon modifiers picked up so far
+            if let Some(modifier_text_entity) = weapon_array.modifier_text_entity {
+                if let Some(ui_text) = ui_texts.get_mut(modifier_text_entity) {
+                    ui_text.text = get_weapon_modifiers_text(
+                        &weapon_array.modifiers,
+                        &weapon_store_resource.modifier_table,
+                    );
+                }
+            }
+
             if vehicle.state == VehicleState::Active {
                 //let vehicle_weapon_is_firing = input.action_is_down(&ActionBinding::VehicleShoot(player.id));
                 let primary_fire: Option<bool>;
                 let secondary_fire: Option<bool>;
+                let reload: Option<bool>;
 
                 if player.is_bot {
-                    if player.bot_mode == BotMode::StopAim
-                        || player.bot_mode == BotMode::StrafeAim
-                        || player.bot_mode == BotMode::Mining
-                        || player.bot_mode == BotMode::Chasing
-                        || player.bot_mode == BotMode::Swording
-                        || player.bot_mode == BotMode::Racing
-                    {
-                        primary_fire = Some(true);
-                        secondary_fire = Some(rng.gen::<bool>());
-                    } else {
-                        primary_fire = None;
-                        secondary_fire = None;
-                    }
+                    primary_fire = player.bot_intent.primary_fire;
+                    secondary_fire = player.bot_intent.secondary_fire;
+                    reload = player.bot_intent.reload;
                 } else {
                     // if MP_BINDINGS {
                     //     primary_fire = input.action_is_down(&ActionBinding::VehiclePriFire(player.id));
@@ -94,6 +108,14 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
                         3 => input.action_is_down("p4_alt_fire"),
                         _ => None,
                     };
+                    //reload bindings are optional, weapons still reload on their own when empty
+                    reload = match player.id {
+                        0 => input.action_is_down("p1_reload"),
+                        1 => input.action_is_down("p2_reload"),
+                        2 => input.action_is_down("p3_reload"),
+                        3 => input.action_is_down("p4_reload"),
+                        _ => None,
+                    };
                 }
 
                 for (weapon_index, weapon_install) in weapon_array.installed.iter_mut().enumerate()
@@ -121,16 +143,26 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
                     //if trying to fire weapon
                     //Manage cooldown, burst, spin-up, and charging delays
                     if weapon_is_attempting_to_fire && !vehicle.repair.activated {
-                        if weapon.cooldown_timer <= 0.0 {
+                        if weapon.cooldown_timer <= 0.0 && weapon.overheat_timer <= 0.0 {
                             if weapon.spin_up_timer <= 0.0 {
                                 if weapon.charge_timer <= 0.0 {
+                                    //weapons without a magazine fire straight from their ammo
+                                    let magazine_loaded = weapon.stats.magazine_size == 0
+                                        || (weapon.reload_timer <= 0.0 && weapon.magazine > 0);
+
                                     //if weapon.ammo is None, this means infinite ammo
-                                    if weapon.ammo.is_none() || weapon.ammo.unwrap() > 0 {
+                                    if (weapon.ammo.is_none() || weapon.ammo.unwrap() > 0)
+                                        && magazine_loaded
+                                    {
                                         //finally, the weapon can now fire
                                         if !weapon.ammo.is_none() {
                                             weapon.ammo = Some(weapon.ammo.unwrap() - 1);
                                         }
 
+                                        if weapon.stats.magazine_size > 0 {
+                                            weapon.magazine -= 1;
+                                        }
+
                                         let vehicle_rotation = transform.rotation();
                                         let (_, _, vehicle_angle) = vehicle_rotation.euler_angles();
 
@@ -163,25 +195,30 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
                                         if let Some(angle_to_closest_targetable_vehicle) =
                                             vehicle.angle_to_closest_targetable_vehicle
                                         {
-                                            let angle_to_selected_vehicle =
-                                                angle_to_closest_targetable_vehicle;
                                             let dist_to_selected_vehicle =
                                                 vehicle.dist_to_closest_targetable_vehicle.unwrap();
 
-                                            fire_angle = calc_tracking_fire_angle(
-                                                dist_to_selected_vehicle,
-                                                angle_to_selected_vehicle,
-                                                standard_angle,
-                                                weapon.stats.tracking_angle,
-                                                weapon.stats.tracking_range,
-                                            );
-                                        } else if let Some(angle_to_closest_targetable_vehicle) =
-                                            vehicle.angle_to_closest_targetable_vehicle
-                                        {
-                                            let angle_to_selected_vehicle =
-                                                angle_to_closest_targetable_vehicle;
-                                            let dist_to_selected_vehicle =
-                                                vehicle.dist_to_closest_targetable_vehicle.unwrap();
+                                            //lead moving targets so slow shots can still hit
+                                            let mut angle_to_selected_vehicle;
+                                            if let Some((target_dx, target_dy)) =
+                                                vehicle.velocity_of_closest_targetable_vehicle
+                                            {
+                                                angle_to_selected_vehicle = calc_lead_angle(
+                                                    dist_to_selected_vehicle,
+                                                    angle_to_closest_targetable_vehicle,
+                                                    target_dx,
+                                                    target_dy,
+                                                    weapon.stats.fire_stats.shot_speed,
+                                                    weapon.stats.fire_stats.accel_rate,
+                                                );
+                                            } else {
+                                                angle_to_selected_vehicle =
+                                                    angle_to_closest_targetable_vehicle;
+                                            }
+
+                                            if player.is_bot {
+                                                angle_to_selected_vehicle += player.bot_aim_offset;
+                                            }
 
                                             fire_angle = calc_tracking_fire_angle(
                                                 dist_to_selected_vehicle,
@@ -217,7 +254,30 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
                                             }
                                         }
 
-                                        if weapon.deployed {
+                                        if let Some(deployable_stats) =
+                                            weapon.stats.fire_stats.deployable
+                                        {
+                                            //deploying weapons drop a turret or drone instead of a shot
+                                            deploy_weapon(
+                                                &entities,
+                                                &weapon_fire_resource,
+                                                &weapon_store_resource,
+                                                weapon,
+                                                deployable_stats,
+                                                fire_position,
+                                                fire_angle,
+                                                player.id,
+                                                player.team,
+                                                &lazy_update,
+                                            );
+                                        } else if weapon.stats.fire_stats.beam_range > 0.0 {
+                                            //beams are raycast by the WeaponBeamSystem instead of spawning shots
+                                            weapon.beam = Some((
+                                                fire_position.x,
+                                                fire_position.y,
+                                                fire_angle,
+                                            ));
+                                        } else if weapon.deployed {
                                             fire_weapon(
                                                 &entities,
                                                 &weapon_fire_resource,
@@ -229,8 +289,37 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
                                                 Some(player.id),
                                                 &lazy_update,
                                             );
+
+                                            //split shot modifiers fan extra shots out to either side
+                                            for split_index in 0..weapon.split_shots {
+                                                let side;
+                                                if split_index % 2 == 0 {
+                                                    side = 1.0;
+                                                } else {
+                                                    side = -1.0;
+                                                }
+
+                                                let split_angle = fire_angle
+                                                    + side
+                                                        * ((split_index / 2 + 1) as f32)
+                                                        * WEAPON_SPLIT_SHOT_ANGLE;
+
+                                                fire_weapon(
+                                                    &entities,
+                                                    &weapon_fire_resource,
+                                                    weapon.clone(),
+                                                    weapon_index,
+                                                    fire_position,
+                                                    split_angle,
+                                                    weapon_angle_offset,
+                                                    Some(player.id),
+                                                    &lazy_update,
+                                                );
+                                            }
                                         }
 
+                                        weapon.add_shot_heat(dt);
+
                                         //manage cooldown timer reset and burst fire reset
                                         if weapon.stats.burst_shot_limit > 0
                                             && weapon.burst_shots < weapon.stats.burst_shot_limit
@@ -274,14 +363,57 @@ impl<'s> System<'s> for VehicleWeaponsSystem {
                         weapon.charges = 0;
                     }
 
+                    //reload once the magazine runs dry, or whenever asked to
+                    if weapon.reload_timer > 0.0 {
+                        weapon.reload_timer -= dt;
+
+                        if weapon.reload_timer <= 0.0 {
+                            weapon.finish_reload();
+                        }
+                    }
+
+                    let reload_requested = !reload.is_none() && reload.unwrap();
+
+                    if weapon.can_reload() && (weapon.magazine == 0 || reload_requested) {
+                        weapon.reload_timer = weapon.stats.reload_time;
+
+                        if weapon.reload_timer <= 0.0 {
+                            weapon.finish_reload();
+                        }
+                    }
+
                     //manage cooldown timer and weapon icon indicators
                     weapon.cooldown_timer = (weapon.cooldown_timer - dt).max(-1.0);
 
+                    //weapons cool down whether or not they're firing
+                    weapon.cool_down(dt);
+
+                    if let Some(heat_bar_entity) = weapon.heat_bar_entity {
+                        if let Some(heat_bar_transform) = ui_transforms.get_mut(heat_bar_entity) {
+                            heat_bar_transform.height =
+                                WEAPON_HEAT_BAR_HEIGHT * weapon.heat / WEAPON_MAX_HEAT;
+                        }
+
+                        if let Some(tint) = tints.get_mut(heat_bar_entity) {
+                            if weapon.overheat_timer > 0.0 {
+                                *tint = Tint(Srgba::new(1.0, 0.1, 0.1, 1.0));
+                            } else {
+                                *tint = Tint(Srgba::new(1.0, 0.6, 0.1, 1.0));
+                            }
+                        }
+                    }
+
                     let cooldown_pct = weapon.cooldown_timer / weapon.stats.cooldown_reset;
                     let tint_component = tints.get_mut(weapon.icon_entity);
 
                     if let Some(tint) = tint_component {
-                        if cooldown_pct < 0.0 {
+                        if weapon.reload_timer > 0.0 {
+                            //fade the icon back in as the reload completes
+                            let reload_pct = 1.0 - weapon.reload_timer / weapon.stats.reload_time;
+                            *tint = Tint(Srgba::new(0.5, 0.7, 1.0, 0.15 + 0.85 * reload_pct));
+                        } else if weapon.stats.magazine_size > 0 && weapon.magazine == 0 {
+                            *tint = Tint(Srgba::new(1.0, 0.3, 0.3, 0.3)); //out of ammo
+                        } else if cooldown_pct < 0.0 {
                             *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 1.0));
                         } else {
                             *tint = Tint(Srgba::new(1.0, 1.0, 1.0, 0.15));
@@ -328,3 +460,109 @@ fn calc_tracking_fire_angle(
 
     fire_angle
 }
+
+//Angle to fire at so that a shot meets a target moving at (target_dx, target_dy)
+//Falls back to the target's current angle when the shot can never catch it
+//Shots leave at shot_speed in arena space without picking up the shooter's velocity,
+//  so only the target's own velocity is led, not its velocity relative to the shooter
+pub fn calc_lead_angle(
+    dist_to_target: f32,
+    angle_to_target: f32,
+    target_dx: f32,
+    target_dy: f32,
+    shot_speed: f32,
+    accel_rate: f32,
+) -> f32 {
+    if shot_speed <= 0.0 {
+        return angle_to_target; //mines and attached weapons don't travel
+    }
+
+    //target position relative to the shooter, angles are 0deg pointed towards top
+    let target_x = -dist_to_target * angle_to_target.sin();
+    let target_y = dist_to_target * angle_to_target.cos();
+
+    let mut intercept_x = target_x;
+    let mut intercept_y = target_y;
+
+    //iterate towards the intercept point, converges quickly for shots faster than the target
+    for _ in 0..LEAD_AIM_ITERATIONS {
+        let intercept_dist = (intercept_x.powi(2) + intercept_y.powi(2)).sqrt();
+
+        let travel_time;
+        if let Some(time) = calc_shot_travel_time(intercept_dist, shot_speed, accel_rate) {
+            travel_time = time;
+        } else {
+            return angle_to_target;
+        }
+
+        intercept_x = target_x + target_dx * travel_time;
+        intercept_y = target_y + target_dy * travel_time;
+    }
+
+    clean_angle((-intercept_y).atan2(-intercept_x) + (PI / 2.0))
+}
+
+//Time for a shot to travel a distance, None if a decelerating shot stops short of it
+fn calc_shot_travel_time(dist: f32, shot_speed: f32, accel_rate: f32) -> Option<f32> {
+    if accel_rate.abs() < 0.001 {
+        Some(dist / shot_speed)
+    } else {
+        //dist = shot_speed*t + 0.5*accel_rate*t^2
+        let discriminant = shot_speed.powi(2) + 2.0 * accel_rate * dist;
+
+        if discriminant < 0.0 {
+            None
+        } else {
+            Some((-shot_speed + discriminant.sqrt()) / accel_rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_calc_lead_angle_stationary_target() {
+        let angle = calc_lead_angle(100.0, 0.5, 0.0, 0.0, 200.0, 0.0);
+        assert_approx_eq!(angle, 0.5);
+    }
+
+    #[test]
+    fn test_calc_lead_angle_crossing_target() {
+        //target straight above moving right at half the shot speed
+        let angle = calc_lead_angle(100.0, 0.0, 100.0, 0.0, 200.0, 0.0);
+
+        //intercept satisfies sin(lead) = target_speed / shot_speed, leading to the right is -angle
+        assert_approx_eq!(angle, -(0.5 as f32).asin(), 0.001);
+    }
+
+    #[test]
+    fn test_calc_lead_angle_shot_meets_target_in_arena_space() {
+        let (target_x, target_y): (f32, f32) = (60.0, 80.0);
+        let (target_dx, target_dy): (f32, f32) = (-30.0, 40.0);
+        let shot_speed: f32 = 150.0;
+
+        let dist = target_x.hypot(target_y);
+        let angle_to_target = (-target_y).atan2(-target_x) + (PI / 2.0);
+
+        let angle = calc_lead_angle(dist, angle_to_target, target_dx, target_dy, shot_speed, 0.0);
+
+        //fire from the origin like entities::fire_weapon does, the shooter's own motion plays no part
+        let (shot_dx, shot_dy) = (-shot_speed * angle.sin(), shot_speed * angle.cos());
+
+        //the shot closes the gap to the target at their relative speed
+        let time = dist / (target_dx - shot_dx).hypot(target_dy - shot_dy);
+
+        assert_approx_eq!(shot_dx * time, target_x + target_dx * time, 0.01);
+        assert_approx_eq!(shot_dy * time, target_y + target_dy * time, 0.01);
+    }
+
+    #[test]
+    fn test_calc_lead_angle_decelerating_shot_out_of_reach() {
+        //shot stops after 200.0*200.0 / (2*400.0) = 50.0 units
+        let angle = calc_lead_angle(100.0, 1.0, 50.0, 50.0, 200.0, -400.0);
+        assert_approx_eq!(angle, 1.0);
+    }
+}
@@ -0,0 +1,552 @@
+use amethyst::{
+    core::{math::Vector3, Time, Transform},
+    derive::SystemDesc,
+    ecs::{
+        Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, SystemData,
+        Write, WriteStorage,
+    },
+    renderer::{debug_drawing::DebugLines, palette::Srgba},
+};
+
+use std::collections::HashMap;
+
+extern crate nalgebra as na;
+use na::{Isometry2, Point2, Vector2};
+use ncollide2d::query::{Ray, RayCast};
+use ncollide2d::shape::{Ball, Cuboid};
+
+use crate::components::{
+    apply_vehicle_impulse, kill_restart_vehicle, vehicle_damage_model, ArenaElement, Deployable,
+    DurationDamage, HitboxShape, ObstacleType, Player, Vehicle, VehicleState, WeaponArray,
+    WeaponFireStats, WeaponNames,
+};
+use crate::entities::hit_spray;
+use crate::resources::{GameModeSetup, GameModes, WeaponFireResource};
+
+const BEAM_Z: f32 = 0.4;
+const BEAM_BOUNCE_OFFSET: f32 = 0.1; //start a reflected beam just off the wall it bounced from
+const BEAM_HIT_SPRAY_COOLDOWN_RESET: f32 = 0.1;
+
+//Something a beam can stop at and damage
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum BeamTargetId {
+    Vehicle(usize), //player id
+    Deployable(Entity),
+}
+
+#[derive(Copy, Clone, Debug)]
+struct BeamTarget {
+    id: BeamTargetId,
+    x: f32,
+    y: f32,
+    radius: f32,
+}
+
+//Where a beam went this frame
+struct BeamTrace {
+    segments: Vec<(f32, f32, f32, f32)>, //(x1, y1, x2, y2)
+    hit_target: Option<BeamTargetId>,
+    hit_walls: Vec<usize>, //indices of the walls it stopped at or bounced off
+}
+
+//A beam fired this frame, cast out from where its weapon is mounted
+struct ActiveBeam {
+    owner_id: usize,
+    weapon_name: WeaponNames,
+    stats: WeaponFireStats,
+    x: f32,
+    y: f32,
+    angle: f32,
+}
+
+//Continuous beam weapons: a raycast every frame instead of a stream of WeaponFire entities
+#[derive(SystemDesc, Default)]
+pub struct WeaponBeamSystem {
+    pub hit_spray_cooldown_timer: f32,
+}
+
+impl<'s> System<'s> for WeaponBeamSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Player>,
+        WriteStorage<'s, Vehicle>,
+        WriteStorage<'s, WeaponArray>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, ArenaElement>,
+        Write<'s, DebugLines>,
+        Read<'s, Time>,
+        ReadExpect<'s, GameModeSetup>,
+        ReadExpect<'s, WeaponFireResource>,
+        ReadExpect<'s, LazyUpdate>,
+        WriteStorage<'s, Deployable>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut players,
+            mut vehicles,
+            mut weapon_arrays,
+            transforms,
+            mut arena_elements,
+            mut debug_lines_resource,
+            time,
+            game_mode_setup,
+            weapon_fire_resource,
+            lazy_update,
+            mut deployables,
+        ): Self::SystemData,
+    ) {
+        let dt = time.delta_seconds();
+
+        self.hit_spray_cooldown_timer -= dt;
+
+        let mut beams: Vec<ActiveBeam> = Vec::new();
+
+        for (player, weapon_array) in (&players, &mut weapon_arrays).join() {
+            for weapon_install in weapon_array.installed.iter_mut() {
+                let weapon = &mut weapon_install.weapon;
+
+                //a beam only lasts the frame it was fired in
+                if let Some((x, y, angle)) = weapon.beam.take() {
+                    beams.push(ActiveBeam {
+                        owner_id: player.id,
+                        weapon_name: weapon.name,
+                        stats: weapon.stats.fire_stats.clone(),
+                        x,
+                        y,
+                        angle,
+                    });
+                }
+            }
+        }
+
+        if beams.is_empty() {
+            return;
+        }
+
+        let mut walls: Vec<&ArenaElement> = Vec::new();
+        let mut wall_entities: Vec<Entity> = Vec::new();
+
+        for (entity, arena_element) in (&*entities, &arena_elements).join() {
+            if arena_element.obstacle_type == ObstacleType::Wall {
+                walls.push(arena_element);
+                wall_entities.push(entity);
+            }
+        }
+
+        let player_teams: HashMap<usize, i32> = (&players)
+            .join()
+            .map(|player| (player.id, player.team))
+            .collect();
+
+        let mut vehicle_targets: Vec<BeamTarget> = Vec::new();
+
+        for (player, vehicle, transform) in (&players, &vehicles, &transforms).join() {
+            if vehicle.state == VehicleState::Active {
+                vehicle_targets.push(BeamTarget {
+                    id: BeamTargetId::Vehicle(player.id),
+                    x: transform.translation().x,
+                    y: transform.translation().y,
+                    radius: vehicle.width / 2.0,
+                });
+            }
+        }
+
+        //victim player id -> beams hitting it this frame, and the last leg of each beam
+        let mut beam_hits: HashMap<usize, Vec<(&ActiveBeam, (f32, f32, f32, f32))>> =
+            HashMap::new();
+
+        let mut wall_damage: Vec<(Entity, f32)> = Vec::new();
+
+        for beam in beams.iter() {
+            //the owner's and their teammates' turrets and drones don't block the beam
+            let mut targets = vehicle_targets.clone();
+
+            for (entity, deployable, transform) in (&*entities, &deployables, &transforms).join() {
+                if !deployable.is_friendly_fire(Some(beam.owner_id), &player_teams) {
+                    targets.push(BeamTarget {
+                        id: BeamTargetId::Deployable(entity),
+                        x: transform.translation().x,
+                        y: transform.translation().y,
+                        radius: deployable.stats.radius,
+                    });
+                }
+            }
+
+            let beam_trace = cast_beam(
+                &walls,
+                &targets,
+                beam.owner_id,
+                beam.x,
+                beam.y,
+                beam.angle,
+                beam.stats.beam_range,
+                beam.stats.bounces,
+            );
+
+            for (x1, y1, x2, y2) in beam_trace.segments.iter() {
+                debug_lines_resource.draw_line(
+                    [*x1, *y1, BEAM_Z].into(),
+                    [*x2, *y2, BEAM_Z].into(),
+                    Srgba::new(1.0, 0.2, 0.2, 0.9),
+                );
+            }
+
+            match beam_trace.hit_target {
+                Some(BeamTargetId::Vehicle(hit_id)) => {
+                    if let Some(last_segment) = beam_trace.segments.last() {
+                        beam_hits
+                            .entry(hit_id)
+                            .or_insert_with(Vec::new)
+                            .push((beam, *last_segment));
+                    }
+                }
+                Some(BeamTargetId::Deployable(hit_entity)) => {
+                    //the DeployableSystem blows it up once its health runs out
+                    if let Some(deployable) = deployables.get_mut(hit_entity) {
+                        deployable.health -= beam.stats.damage * dt;
+                    }
+                }
+                None => {}
+            }
+
+            for wall_idx in beam_trace.hit_walls.iter() {
+                wall_damage.push((wall_entities[*wall_idx], beam.stats.damage * dt));
+            }
+        }
+
+        //destructible walls wear down under a beam,
+        //the collision system turns them to rubble once their health runs out
+        for (wall_entity, damage) in wall_damage.iter() {
+            if let Some(arena_element) = arena_elements.get_mut(*wall_entity) {
+                if let Some(wall_health) = arena_element.health {
+                    arena_element.health = Some(wall_health - damage);
+                }
+            }
+        }
+
+        let mut player_makes_hit_ids: Vec<usize> = Vec::new();
+        let mut player_makes_kill_map: HashMap<usize, WeaponNames> = HashMap::new();
+
+        for (player, vehicle, transform) in (&mut players, &mut vehicles, &transforms).join() {
+            if let Some(hitting_beams) = beam_hits.get(&player.id) {
+                for (beam, (from_x, from_y, hit_x, hit_y)) in hitting_beams.iter() {
+                    if vehicle.state != VehicleState::Active {
+                        break;
+                    }
+
+                    player.last_hit_by_id = Some(beam.owner_id);
+                    player.last_hit_timer = 0.0;
+
+                    player_makes_hit_ids.push(beam.owner_id);
+
+                    //beam damage is per second
+                    let vehicle_destroyed: bool = vehicle_damage_model(
+                        vehicle,
+                        Some(beam.owner_id),
+                        Some(beam.weapon_name),
+                        beam.stats.damage * dt,
+                        beam.stats.piercing_damage_pct,
+                        beam.stats.shield_damage_pct,
+                        beam.stats.armor_damage_pct,
+                        beam.stats.health_damage_pct,
+                        DurationDamage::default(), //re-applied every frame, so lasting effects would stack
+                    );
+
+                    //beams push steadily along their last leg
+                    if beam.stats.knockback > 0.0 {
+                        apply_vehicle_impulse(
+                            vehicle,
+                            beam.stats.knockback * dt,
+                            hit_x - from_x,
+                            hit_y - from_y,
+                            hit_x - transform.translation().x,
+                            hit_y - transform.translation().y,
+                        );
+                    }
+
+                    //Pass ion malfunction effect from weapon to vehicle
+                    if vehicle.shield.value == 0.0 && beam.stats.ion_malfunction_pct > 0.0 {
+                        vehicle.ion_malfunction_pct = beam.stats.ion_malfunction_pct;
+                    }
+
+                    if self.hit_spray_cooldown_timer < 0.0 {
+                        let position = Vector3::new(*hit_x, *hit_y, 0.5);
+                        let shields_up = vehicle.shield.value > 0.0;
+                        hit_spray(
+                            &entities,
+                            &weapon_fire_resource,
+                            shields_up,
+                            position,
+                            &lazy_update,
+                        );
+                        self.hit_spray_cooldown_timer = BEAM_HIT_SPRAY_COOLDOWN_RESET;
+                    }
+
+                    if vehicle_destroyed {
+                        player_makes_kill_map.insert(beam.owner_id, beam.weapon_name);
+
+                        player.deaths += 1;
+
+                        kill_restart_vehicle(
+                            player,
+                            vehicle,
+                            transform,
+                            game_mode_setup.stock_lives,
+                        );
+                    }
+                }
+            }
+        }
+
+        //Kill tracking, the gun-game weapon swap is handled with the other kills in the collision system
+        for (player, weapon_array) in (&mut players, &weapon_arrays).join() {
+            if player_makes_hit_ids.contains(&player.id) {
+                player.last_made_hit_timer = 0.0;
+            }
+
+            if let Some(weapon_name) = player_makes_kill_map.get(&player.id) {
+                if weapon_array.installed.len() > 0 {
+                    let primary_weapon = &weapon_array.installed[0].weapon;
+
+                    if game_mode_setup.game_mode == GameModes::ClassicGunGame
+                        && *weapon_name == primary_weapon.name
+                    {
+                        player.gun_game_kills += 1;
+                    } else {
+                        player.kills += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+//Trace a beam out to its range, reflecting off walls up to bounces times
+fn cast_beam(
+    walls: &Vec<&ArenaElement>,
+    targets: &Vec<BeamTarget>,
+    owner_id: usize,
+    x: f32,
+    y: f32,
+    angle: f32,
+    range: f32,
+    bounces: u32,
+) -> BeamTrace {
+    let mut segments: Vec<(f32, f32, f32, f32)> = Vec::new();
+    let mut hit_walls: Vec<usize> = Vec::new();
+
+    let mut origin = Point2::new(x, y);
+    let mut dir = Vector2::new(-angle.sin(), angle.cos()); //0deg is pointed towards top
+    let mut remaining_range = range;
+    let mut bounces_left = bounces;
+
+    loop {
+        let beam_ray = Ray::new(origin, dir); //unit direction, so time of impact is distance
+
+        //closest wall in the way, its surface normal for reflecting, and which wall it is
+        let mut wall_hit: Option<(f32, Vector2<f32>, usize)> = None;
+
+        for (wall_idx, wall) in walls.iter().enumerate() {
+            let wall_pos = Isometry2::new(Vector2::new(wall.x, wall.y), wall.hitbox.angle);
+
+            let intersection = if wall.hitbox.shape == HitboxShape::Circle {
+                Ball::new(wall.hitbox.width / 2.0).toi_and_normal_with_ray(
+                    &wall_pos,
+                    &beam_ray,
+                    remaining_range,
+                    true,
+                )
+            } else {
+                Cuboid::new(Vector2::new(
+                    wall.hitbox.width / 2.0,
+                    wall.hitbox.height / 2.0,
+                ))
+                .toi_and_normal_with_ray(
+                    &wall_pos,
+                    &beam_ray,
+                    remaining_range,
+                    true,
+                )
+            };
+
+            if let Some(intersection) = intersection {
+                if wall_hit.is_none() || intersection.toi < wall_hit.unwrap().0 {
+                    wall_hit = Some((intersection.toi, intersection.normal, wall_idx));
+                }
+            }
+        }
+
+        //closest vehicle or deployable in the way
+        let mut vehicle_hit: Option<(f32, BeamTargetId)> = None;
+
+        for target in targets.iter() {
+            if target.id == BeamTargetId::Vehicle(owner_id) {
+                continue;
+            }
+
+            let target_pos = Isometry2::new(Vector2::new(target.x, target.y), 0.0);
+
+            let toi = Ball::new(target.radius).toi_with_ray(
+                &target_pos,
+                &beam_ray,
+                remaining_range,
+                true,
+            );
+
+            if let Some(toi) = toi {
+                if vehicle_hit.is_none() || toi < vehicle_hit.unwrap().0 {
+                    vehicle_hit = Some((toi, target.id));
+                }
+            }
+        }
+
+        let wall_toi = wall_hit.map_or(remaining_range, |(toi, _, _)| toi);
+
+        if let Some((vehicle_toi, target_id)) = vehicle_hit {
+            if vehicle_toi <= wall_toi {
+                let end = origin + dir * vehicle_toi;
+                segments.push((origin.x, origin.y, end.x, end.y));
+
+                return BeamTrace {
+                    segments,
+                    hit_target: Some(target_id),
+                    hit_walls,
+                };
+            }
+        }
+
+        let end = origin + dir * wall_toi;
+        segments.push((origin.x, origin.y, end.x, end.y));
+
+        if let Some((toi, normal, wall_idx)) = wall_hit {
+            hit_walls.push(wall_idx);
+
+            if bounces_left > 0 && toi > 0.0 {
+                bounces_left -= 1;
+                remaining_range -= toi;
+
+                dir = dir - normal * (2.0 * dir.dot(&normal));
+                origin = end + dir * BEAM_BOUNCE_OFFSET;
+
+                continue;
+            }
+        }
+
+        return BeamTrace {
+            segments,
+            hit_target: None,
+            hit_walls,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Hitbox, RaceCheckpointType};
+    use assert_approx_eq::assert_approx_eq;
+    use std::f32::consts::PI;
+
+    fn wall(x: f32, y: f32, width: f32, height: f32) -> ArenaElement {
+        ArenaElement {
+            obstacle_type: ObstacleType::Wall,
+            is_hill: false,
+            checkpoint: RaceCheckpointType::NotCheckpoint,
+            checkpoint_id: 0,
+            is_weapon_box: false,
+            is_spawn_point: false,
+            is_weapon_spawn_point: false,
+            x,
+            y,
+            z: 0.0,
+            is_sprite: false,
+            sprite: 71,
+            sprite_scale: 1.0,
+            weapon_names: None,
+            first_spawn_time: None,
+            spawn_time: None,
+            spawn_timer: None,
+            ammo: None,
+            hitbox: Hitbox::new(width, height, 0.0, HitboxShape::Rectangle),
+            effects: None,
+            health: None,
+        }
+    }
+
+    fn vehicle(player_id: usize, x: f32, y: f32) -> BeamTarget {
+        BeamTarget {
+            id: BeamTargetId::Vehicle(player_id),
+            x,
+            y,
+            radius: 5.0,
+        }
+    }
+
+    #[test]
+    fn test_cast_beam_skips_owner_and_stops_at_target() {
+        let targets = vec![vehicle(0, 0.0, 0.0), vehicle(1, 0.0, 50.0)];
+
+        let beam_trace = cast_beam(&vec![], &targets, 0, 0.0, 0.0, 0.0, 100.0, 0);
+
+        assert_eq!(beam_trace.hit_target, Some(BeamTargetId::Vehicle(1)));
+        assert_eq!(beam_trace.segments.len(), 1);
+        assert_approx_eq!(beam_trace.segments[0].3, 45.0, 0.001);
+        assert!(beam_trace.hit_walls.is_empty());
+    }
+
+    #[test]
+    fn test_cast_beam_stops_at_wall_without_bounces() {
+        let walls = vec![wall(0.0, 60.0, 100.0, 20.0)];
+        let targets = vec![vehicle(1, 0.0, 90.0)];
+
+        let beam_trace = cast_beam(
+            &walls.iter().collect(),
+            &targets,
+            0,
+            0.0,
+            0.0,
+            0.0,
+            100.0,
+            0,
+        );
+
+        assert_eq!(beam_trace.hit_target, None);
+        assert_eq!(beam_trace.segments.len(), 1);
+        assert_approx_eq!(beam_trace.segments[0].3, 50.0, 0.001);
+        assert_eq!(beam_trace.hit_walls, vec![0]);
+    }
+
+    #[test]
+    fn test_cast_beam_bounces_off_wall() {
+        let walls = vec![wall(0.0, 60.0, 200.0, 20.0)];
+
+        //up and to the right, hitting the wall's bottom face at (50, 50)
+        let beam_trace = cast_beam(
+            &walls.iter().collect(),
+            &vec![],
+            0,
+            0.0,
+            0.0,
+            -PI / 4.0,
+            100.0,
+            1,
+        );
+
+        assert_eq!(beam_trace.segments.len(), 2);
+        assert_approx_eq!(beam_trace.segments[0].2, 50.0, 0.01);
+        assert_approx_eq!(beam_trace.segments[0].3, 50.0, 0.01);
+
+        //reflected down and to the right with the remaining range
+        let (x1, y1, x2, y2) = beam_trace.segments[1];
+        assert!(x2 > x1 && y2 < y1);
+        assert_approx_eq!(
+            (x2 - 50.0).hypot(y2 - 50.0),
+            100.0 - 50.0 * (2.0 as f32).sqrt(),
+            0.2
+        );
+        assert_eq!(beam_trace.hit_walls, vec![0]);
+    }
+}
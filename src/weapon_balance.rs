@@ -0,0 +1,558 @@
+use crate::components::{
+    calculate_layered_damage, DurationDamage, VehicleNames, VehicleStats, VehicleStoreResource,
+    WeaponFireStats, WeaponNames, WeaponStats, WeaponStoreResource, WEAPON_MAX_HEAT,
+};
+use crate::load_ron_asset;
+
+//Shield, armor or health points, either a vehicle's pool or the damage done to it
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DefenseLayers {
+    pub shield: f32,
+    pub armor: f32,
+    pub health: f32,
+}
+
+impl DefenseLayers {
+    pub fn from_vehicle_stats(vehicle_stats: &VehicleStats) -> DefenseLayers {
+        DefenseLayers {
+            shield: vehicle_stats.max_shield,
+            armor: vehicle_stats.max_armor,
+            health: vehicle_stats.max_health,
+        }
+    }
+
+    //Same layering as vehicle_damage_model, through calculate_layered_damage
+    //Returns which of (shield, armor, health) took damage, for triggering duration damage
+    pub fn take_damage(
+        &mut self,
+        damage: f32,
+        piercing_damage_pct: f32,
+        shield_damage_pct: f32,
+        armor_damage_pct: f32,
+        health_damage_pct: f32,
+    ) -> (bool, bool, bool) {
+        //healing isn't part of the balance numbers
+        if damage <= 0.0 {
+            return (false, false, false);
+        }
+
+        let layered_damage = calculate_layered_damage(
+            self.shield,
+            self.armor,
+            damage,
+            piercing_damage_pct,
+            shield_damage_pct,
+            armor_damage_pct,
+            health_damage_pct,
+        );
+
+        self.shield -= layered_damage.shield;
+        self.armor -= layered_damage.armor;
+        self.health = (self.health - layered_damage.health).max(0.0);
+
+        (
+            layered_damage.shield_applied,
+            layered_damage.armor_applied,
+            layered_damage.health_applied,
+        )
+    }
+}
+
+//Sustained damage of one weapon against each defense layer, and how long it takes to kill each vehicle
+#[derive(Clone, Debug)]
+pub struct WeaponBalance {
+    pub weapon_name: WeaponNames,
+    pub display_name: String,
+    pub fire_rate: f32,  //shots per second, or beam uptime
+    pub shield_dps: f32, //shield points per second while shields are up
+    pub armor_dps: f32,  //armor points per second once shields are down
+    pub health_dps: f32, //health points per second once shields and armor are down
+    pub chain_dps: f32,  //health damage per second chained onto other nearby vehicles
+    pub time_to_kill: Vec<(VehicleNames, Option<f32>)>, //None if it never gets through
+}
+
+//Bigger pools than any weapon can get through in one hit, for measuring a single layer
+const UNBREAKABLE_LAYER: f32 = 100_000.0;
+//Other vehicles a chained shot can jump to in a 4 player match
+const CHAIN_TARGETS_MAX: u32 = 3;
+//Time-to-kill gives up after this long, and steps through the fight this finely
+const TIME_TO_KILL_LIMIT: f32 = 120.0;
+const TIME_TO_KILL_STEP: f32 = 0.01;
+
+//Shots per second over a long fight, including bursts, charging, reloads and overheating
+//Beams return the fraction of the time they can keep firing, as their damage is already per second
+//Spin-up is left out: it only resets when the trigger is let go, so in sustained fire
+//  it is a one-off delay before the first shot, which calculate_time_to_kill adds instead
+pub fn calculate_fire_rate(stats: &WeaponStats) -> f32 {
+    let beam = stats.fire_stats.beam_range > 0.0;
+
+    let mut shot_interval: f32;
+    if beam {
+        shot_interval = 1.0;
+    } else if stats.burst_shot_limit > 0 {
+        shot_interval = (stats.cooldown_reset
+            + ((stats.burst_shot_limit + 1) as f32) * stats.burst_cooldown_reset)
+            / ((stats.burst_shot_limit + 1) as f32);
+    } else {
+        shot_interval = stats.cooldown_reset;
+    }
+
+    //held charge weapons settle at their fastest charge time
+    if !beam && stats.charge_timer_reset > 0.0 {
+        shot_interval = shot_interval.max(stats.charge_timer_decrease_min);
+    }
+
+    //can't fire more than once a frame
+    shot_interval = shot_interval.max(1.0 / 60.0);
+
+    let mut fire_rate = 1.0 / shot_interval;
+
+    //heat builds up faster than it dissipates, so the weapon cycles through overheating
+    let net_heat_per_shot = stats.heat_per_shot - stats.heat_dissipation_rate * shot_interval;
+
+    if stats.heat_per_shot > 0.0 && net_heat_per_shot > 0.0 {
+        let heat_after_lockout =
+            (WEAPON_MAX_HEAT - stats.heat_dissipation_rate * stats.overheat_lockout).max(0.0);
+        let shots_per_overheat =
+            ((WEAPON_MAX_HEAT - heat_after_lockout) / net_heat_per_shot).max(1.0);
+
+        fire_rate =
+            shots_per_overheat / (shots_per_overheat * shot_interval + stats.overheat_lockout);
+    }
+
+    //magazines empty and need a reload, reserve ammo is taken as plentiful
+    if !beam && stats.magazine_size > 0 && stats.reload_time > 0.0 {
+        let magazine_time = (stats.magazine_size as f32) / fire_rate;
+
+        fire_rate *= magazine_time / (magazine_time + stats.reload_time);
+    }
+
+    fire_rate
+}
+
+//Total damage one hit's duration damage does before wearing off
+pub fn calculate_duration_damage_total(duration_damage: &DurationDamage) -> f32 {
+    duration_damage.damage_per_second * duration_damage.timer.max(0.0)
+}
+
+fn duration_damage_triggers(
+    duration_damage: &DurationDamage,
+    (shield_damage_applied, armor_damage_applied, health_damage_applied): (bool, bool, bool),
+) -> bool {
+    duration_damage.timer > 0.0
+        && ((shield_damage_applied && duration_damage.shield_damage_pct > 0.0)
+            || (armor_damage_applied && duration_damage.armor_damage_pct > 0.0)
+            || (health_damage_applied && duration_damage.health_damage_pct > 0.0))
+}
+
+//Damage to each layer from a single hit, including the duration damage it leaves behind
+//Beams never leave duration damage, as they hit every frame
+fn calculate_hit_damage(fire_stats: &WeaponFireStats, layers: DefenseLayers) -> DefenseLayers {
+    let mut damaged_layers = layers;
+
+    let damage_applied = damaged_layers.take_damage(
+        fire_stats.damage,
+        fire_stats.piercing_damage_pct,
+        fire_stats.shield_damage_pct,
+        fire_stats.armor_damage_pct,
+        fire_stats.health_damage_pct,
+    );
+
+    let duration_damage = fire_stats.duration_damage;
+
+    if fire_stats.beam_range <= 0.0 && duration_damage_triggers(&duration_damage, damage_applied) {
+        damaged_layers.take_damage(
+            calculate_duration_damage_total(&duration_damage),
+            duration_damage.piercing_damage_pct,
+            duration_damage.shield_damage_pct,
+            duration_damage.armor_damage_pct,
+            duration_damage.health_damage_pct,
+        );
+    }
+
+    DefenseLayers {
+        shield: layers.shield - damaged_layers.shield,
+        armor: layers.armor - damaged_layers.armor,
+        health: layers.health - damaged_layers.health,
+    }
+}
+
+//DPS against each layer on its own: shields up, then armor with shields down, then bare health
+pub fn calculate_weapon_dps(stats: &WeaponStats) -> DefenseLayers {
+    let fire_rate = calculate_fire_rate(stats);
+
+    let shield_hit = calculate_hit_damage(
+        &stats.fire_stats,
+        DefenseLayers {
+            shield: UNBREAKABLE_LAYER,
+            armor: UNBREAKABLE_LAYER,
+            health: UNBREAKABLE_LAYER,
+        },
+    );
+    let armor_hit = calculate_hit_damage(
+        &stats.fire_stats,
+        DefenseLayers {
+            shield: 0.0,
+            armor: UNBREAKABLE_LAYER,
+            health: UNBREAKABLE_LAYER,
+        },
+    );
+    let health_hit = calculate_hit_damage(
+        &stats.fire_stats,
+        DefenseLayers {
+            shield: 0.0,
+            armor: 0.0,
+            health: UNBREAKABLE_LAYER,
+        },
+    );
+
+    DefenseLayers {
+        shield: shield_hit.shield * fire_rate,
+        armor: armor_hit.armor * fire_rate,
+        health: health_hit.health * fire_rate,
+    }
+}
+
+//Health damage per second that chains off each hit onto other vehicles nearby
+//Each jump can split into several prongs, but never onto the same vehicle twice
+pub fn calculate_chain_dps(stats: &WeaponStats) -> f32 {
+    let chaining_damage = stats.fire_stats.chaining_damage;
+
+    let mut chain_damage = 0.0;
+    let mut chain_targets = 0;
+    let mut jump_targets = 1;
+    let mut jump_damage = stats.fire_stats.damage;
+
+    for _jump in 0..chaining_damage.jumps {
+        jump_targets =
+            (jump_targets * chaining_damage.prongs).min(CHAIN_TARGETS_MAX - chain_targets);
+        jump_damage *= chaining_damage.damage_pct / 100.0;
+
+        chain_damage += (jump_targets as f32) * jump_damage;
+        chain_targets += jump_targets;
+
+        if jump_targets == 0 {
+            break;
+        }
+    }
+
+    chain_damage * stats.fire_stats.health_damage_pct / 100.0 * calculate_fire_rate(stats)
+}
+
+//Seconds of sustained fire to destroy a fresh vehicle, shields are kept from recharging by the hits
+pub fn calculate_time_to_kill(stats: &WeaponStats, vehicle_stats: &VehicleStats) -> Option<f32> {
+    calculate_time_to_destroy(stats, DefenseLayers::from_vehicle_stats(vehicle_stats))
+}
+
+//Counted from first pulling the trigger, so spin-up delays the first shot
+fn calculate_time_to_destroy(stats: &WeaponStats, mut layers: DefenseLayers) -> Option<f32> {
+    let fire_stats = &stats.fire_stats;
+    let beam = fire_stats.beam_range > 0.0;
+    let fire_rate = calculate_fire_rate(stats);
+
+    if fire_stats.damage <= 0.0 || fire_rate <= 0.0 {
+        return None;
+    }
+
+    let spin_up_time = stats.spin_up_timer_reset.max(0.0);
+
+    let mut duration_damages: Vec<DurationDamage> = Vec::new();
+
+    let mut time = 0.0;
+    let mut shot_timer = spin_up_time;
+
+    while time < TIME_TO_KILL_LIMIT {
+        if beam {
+            if time >= spin_up_time {
+                layers.take_damage(
+                    fire_stats.damage * fire_rate * TIME_TO_KILL_STEP,
+                    fire_stats.piercing_damage_pct,
+                    fire_stats.shield_damage_pct,
+                    fire_stats.armor_damage_pct,
+                    fire_stats.health_damage_pct,
+                );
+            }
+        } else if shot_timer <= 0.0 {
+            let damage_applied = layers.take_damage(
+                fire_stats.damage,
+                fire_stats.piercing_damage_pct,
+                fire_stats.shield_damage_pct,
+                fire_stats.armor_damage_pct,
+                fire_stats.health_damage_pct,
+            );
+
+            if duration_damage_triggers(&fire_stats.duration_damage, damage_applied) {
+                duration_damages.push(fire_stats.duration_damage);
+            }
+
+            shot_timer += 1.0 / fire_rate;
+        }
+
+        for duration_damage in duration_damages.iter_mut() {
+            layers.take_damage(
+                duration_damage.damage_per_second * TIME_TO_KILL_STEP,
+                duration_damage.piercing_damage_pct,
+                duration_damage.shield_damage_pct,
+                duration_damage.armor_damage_pct,
+                duration_damage.health_damage_pct,
+            );
+
+            duration_damage.timer -= TIME_TO_KILL_STEP;
+        }
+
+        duration_damages.retain(|duration_damage| duration_damage.timer > 0.0);
+
+        if layers.health <= 0.0 {
+            return Some(time);
+        }
+
+        time += TIME_TO_KILL_STEP;
+        shot_timer -= TIME_TO_KILL_STEP;
+    }
+
+    None
+}
+
+//Deployers are rated by the gun their turret or drone fires
+pub fn calculate_weapon_balance(
+    weapon_name: WeaponNames,
+    weapon_store: &WeaponStoreResource,
+    vehicle_store: &VehicleStoreResource,
+) -> Option<WeaponBalance> {
+    let weapon_stats = weapon_store.properties.get(&weapon_name)?;
+
+    let firing_stats;
+    if let Some(deployable) = weapon_stats.fire_stats.deployable {
+        firing_stats = weapon_store.properties.get(&deployable.weapon_name)?;
+    } else {
+        firing_stats = weapon_stats;
+    }
+
+    let weapon_dps = calculate_weapon_dps(firing_stats);
+
+    let mut time_to_kill = Vec::new();
+
+    for vehicle_name in vehicle_store.order.iter() {
+        if let Some(vehicle_stats) = vehicle_store.properties.get(vehicle_name) {
+            time_to_kill.push((
+                *vehicle_name,
+                calculate_time_to_kill(firing_stats, vehicle_stats),
+            ));
+        }
+    }
+
+    Some(WeaponBalance {
+        weapon_name,
+        display_name: weapon_stats.display_name.clone(),
+        fire_rate: calculate_fire_rate(firing_stats),
+        shield_dps: weapon_dps.shield,
+        armor_dps: weapon_dps.armor,
+        health_dps: weapon_dps.health,
+        chain_dps: calculate_chain_dps(firing_stats),
+        time_to_kill,
+    })
+}
+
+pub fn format_time_to_kill(time_to_kill: Option<f32>) -> String {
+    match time_to_kill {
+        Some(time_to_kill) => format!("{:.1}s", time_to_kill),
+        None => "-".to_string(),
+    }
+}
+
+//Command line report, run with `cargo run -- weapon-balance`
+pub fn weapon_balance_command() -> bool {
+    let weapon_store = WeaponStoreResource {
+        properties: load_ron_asset(&["game", "weapon_properties.ron"]),
+        spawn_chance: load_ron_asset(&["game", "weapon_spawn_chance.ron"]),
+        gun_game_order: load_ron_asset(&["game", "weapon_gun_game_order.ron"]),
+        gun_game_random_order: load_ron_asset(&["game", "weapon_gun_game_order.ron"]),
+        selection_order: load_ron_asset(&["game", "weapon_selection_order.ron"]),
+        modifier_table: load_ron_asset(&["game", "weapon_modifiers.ron"]),
+    };
+
+    let vehicle_store = VehicleStoreResource {
+        properties: load_ron_asset(&["game", "vehicle_properties.ron"]),
+        order: load_ron_asset(&["game", "vehicle_selection_order.ron"]),
+        type_sprites: load_ron_asset(&["game", "vehicle_type_sprites.ron"]),
+    };
+
+    let mut header = format!(
+        "{:<28} {:>7} {:>8} {:>8} {:>8} {:>8}",
+        "Weapon", "Rate", "Shield", "Armor", "Health", "Chain"
+    );
+    for vehicle_name in vehicle_store.order.iter() {
+        header.push_str(&format!(" {:>16}", format!("{:?}", vehicle_name)));
+    }
+    println!("{}", header);
+
+    let mut weapons_rated = 0;
+
+    for weapon_name in weapon_store.selection_order.iter() {
+        if let Some(weapon_balance) =
+            calculate_weapon_balance(*weapon_name, &weapon_store, &vehicle_store)
+        {
+            let mut row = format!(
+                "{:<28} {:>7.2} {:>8.1} {:>8.1} {:>8.1} {:>8.1}",
+                weapon_balance.display_name,
+                weapon_balance.fire_rate,
+                weapon_balance.shield_dps,
+                weapon_balance.armor_dps,
+                weapon_balance.health_dps,
+                weapon_balance.chain_dps,
+            );
+            for (_vehicle_name, time_to_kill) in weapon_balance.time_to_kill.iter() {
+                row.push_str(&format!(" {:>16}", format_time_to_kill(*time_to_kill)));
+            }
+            println!("{}", row);
+
+            weapons_rated += 1;
+        } else {
+            println!("{:?}: not defined in weapon_properties.ron", weapon_name);
+        }
+    }
+
+    println!(
+        "{} weapons rated, DPS is per second of sustained fire, time-to-kill against each vehicle at full strength",
+        weapons_rated
+    );
+
+    weapons_rated == weapon_store.selection_order.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{ChainingDamage, WeaponFireTypes};
+    use assert_approx_eq::assert_approx_eq;
+
+    fn test_weapon(damage: f32, cooldown_reset: f32) -> WeaponStats {
+        WeaponStats {
+            display_name: "Test Gun".to_string(),
+            weapon_fire_type: WeaponFireTypes::ProjectileMedium,
+            tracking_angle: 0.0,
+            tracking_range: 0.0,
+            spread_angle: 0.0,
+            cooldown_reset,
+            burst_shot_limit: 0,
+            burst_cooldown_reset: 0.0,
+            charge_timer_reset: 0.0,
+            charge_timer_decrease: 0.0,
+            charge_timer_decrease_min: 0.0,
+            spin_up_timer_reset: 0.0,
+            heat_per_shot: 0.0,
+            heat_dissipation_rate: 0.0,
+            overheat_lockout: 0.0,
+            magazine_size: 0,
+            reload_time: 0.0,
+            reserve_ammo: None,
+            weight: 0.0,
+            fire_stats: WeaponFireStats {
+                damage,
+                shot_speed: 100.0,
+                shield_damage_pct: 100.0,
+                armor_damage_pct: 100.0,
+                health_damage_pct: 100.0,
+                ..WeaponFireStats::default()
+            },
+        }
+    }
+
+    #[test]
+    fn dps_per_layer_follows_damage_pcts() {
+        let mut stats = test_weapon(10.0, 0.5);
+        stats.fire_stats.shield_damage_pct = 50.0;
+        stats.fire_stats.armor_damage_pct = 200.0;
+
+        let weapon_dps = calculate_weapon_dps(&stats);
+
+        assert_eq!(weapon_dps.shield, 10.0);
+        assert_eq!(weapon_dps.armor, 40.0);
+        assert_eq!(weapon_dps.health, 20.0);
+    }
+
+    #[test]
+    fn duration_damage_adds_to_dps() {
+        let mut stats = test_weapon(10.0, 1.0);
+        stats.fire_stats.duration_damage = DurationDamage {
+            timer: 2.0,
+            damage_per_second: 5.0,
+            health_damage_pct: 100.0,
+            ..DurationDamage::default()
+        };
+
+        assert_eq!(calculate_weapon_dps(&stats).health, 20.0);
+        //shields take no duration damage, so it never starts while they are up
+        assert_eq!(calculate_weapon_dps(&stats).shield, 10.0);
+    }
+
+    #[test]
+    fn piercing_gets_through_shields_that_block_the_rest() {
+        let vehicle_layers = DefenseLayers {
+            shield: 50.0,
+            armor: 0.0,
+            health: 100.0,
+        };
+
+        let mut layers = vehicle_layers;
+        layers.take_damage(20.0, 0.0, 0.0, 100.0, 100.0);
+        assert_eq!(layers, vehicle_layers);
+
+        layers.take_damage(20.0, 50.0, 0.0, 100.0, 100.0);
+        assert_eq!(layers.shield, 50.0);
+        assert_eq!(layers.health, 90.0);
+    }
+
+    #[test]
+    fn chained_damage_is_capped_by_other_vehicles() {
+        let mut stats = test_weapon(10.0, 1.0);
+        stats.fire_stats.chaining_damage = ChainingDamage {
+            damage_pct: 50.0,
+            radius: 50.0,
+            jumps: 3,
+            prongs: 2,
+        };
+
+        //2 vehicles at 5.0, then only 1 left for the next jump at 2.5
+        assert_eq!(calculate_chain_dps(&stats), 12.5);
+    }
+
+    #[test]
+    fn fire_rate_includes_reloads_and_overheating() {
+        let mut stats = test_weapon(10.0, 0.5);
+        stats.magazine_size = 4;
+        stats.reload_time = 2.0;
+
+        assert_eq!(calculate_fire_rate(&stats), 1.0);
+
+        let mut stats = test_weapon(10.0, 0.5);
+        stats.heat_per_shot = 40.0;
+        stats.heat_dissipation_rate = 40.0;
+        stats.overheat_lockout = 2.0;
+
+        //cools to 20 heat while locked out, then 4 shots to overheat again
+
+        assert_eq!(calculate_fire_rate(&stats), 1.0);
+    }
+
+    #[test]
+    fn spin_up_delays_the_kill_but_not_the_fire_rate() {
+        let mut stats = test_weapon(10.0, 0.5);
+        let layers = DefenseLayers {
+            shield: 0.0,
+            armor: 0.0,
+            health: 25.0,
+        };
+
+        //hits at 0.0, 0.5 and 1.0
+        let time_to_destroy = calculate_time_to_destroy(&stats, layers).unwrap();
+        assert_approx_eq!(time_to_destroy, 1.0, 0.02);
+
+        stats.spin_up_timer_reset = 2.0;
+
+        assert_eq!(calculate_fire_rate(&stats), 2.0);
+
+        let time_to_destroy = calculate_time_to_destroy(&stats, layers).unwrap();
+        assert_approx_eq!(time_to_destroy, 3.0, 0.02);
+    }
+}